@@ -0,0 +1,348 @@
+//! # colback-codegen
+//!
+//! Generation of colback struct definitions from existing schemas.
+//!
+//! This is meant for build scripts and tools that generate structs for many tables at once, such
+//! as from a schema registry. The generated source derives `ColbackView`, so it can be written to
+//! a file in `OUT_DIR` and included with `include!`.
+//!
+//! Without its default `schema` feature, this only names the fields of generated structs, which
+//! doesn't need polars. The `files` feature reads the schemas of data files.
+
+#[cfg(feature = "files")]
+use polars::prelude::{
+    CsvReadOptions, IpcReader, ParquetReader, PolarsError, PolarsResult, SerReader,
+};
+#[cfg(feature = "schema")]
+use polars::prelude::{DataType, Schema, TimeUnit};
+use std::collections::HashSet;
+#[cfg(feature = "schema")]
+use std::fmt::Write;
+#[cfg(feature = "files")]
+use std::{fs::File, path::Path};
+
+/// Keywords that can't be used as plain field names.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// The field type that the derive macros support for a dtype, along with the time unit attribute
+/// that it needs.
+///
+/// Milliseconds are the default time unit of datetime fields, so they don't need an attribute.
+#[cfg(feature = "schema")]
+pub fn field_type(dtype: &DataType) -> Option<(&'static str, Option<&'static str>)> {
+    Some(match dtype {
+        DataType::Boolean => ("bool", None),
+        DataType::UInt8 => ("u8", None),
+        DataType::UInt16 => ("u16", None),
+        DataType::UInt32 => ("u32", None),
+        DataType::UInt64 => ("u64", None),
+        DataType::Int32 => ("i32", None),
+        DataType::Int64 => ("i64", None),
+        DataType::Float32 => ("f32", None),
+        DataType::Float64 => ("f64", None),
+        DataType::String => ("String", None),
+        DataType::Datetime(unit, None) => (
+            "NaiveDateTime",
+            match unit {
+                TimeUnit::Nanoseconds => Some("ns"),
+                TimeUnit::Microseconds => Some("us"),
+                TimeUnit::Milliseconds => None,
+            },
+        ),
+        DataType::List(inner) => (
+            match **inner {
+                DataType::UInt8 => "Vec<u8>",
+                DataType::UInt16 => "Vec<u16>",
+                DataType::UInt32 => "Vec<u32>",
+                DataType::UInt64 => "Vec<u64>",
+                DataType::Int32 => "Vec<i32>",
+                DataType::Int64 => "Vec<i64>",
+                DataType::Float32 => "Vec<f32>",
+                DataType::Float64 => "Vec<f64>",
+                _ => return None,
+            },
+            None,
+        ),
+        _ => return None,
+    })
+}
+
+/// Convert a column name into a snake case field name that is a valid identifier.
+fn field_name(column: &str) -> String {
+    let mut name = String::with_capacity(column.len());
+    let mut prev = None;
+    for c in column.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            // Split camel case words, but not runs of capitals such as acronyms
+            if c.is_ascii_uppercase()
+                && prev.is_some_and(|p: char| p.is_ascii_lowercase() || p.is_ascii_digit())
+            {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+        prev = Some(c);
+    }
+    let mut name = name.trim_end_matches('_').to_string();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "col_");
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+/// Field names for the columns of a struct.
+///
+/// Columns are named in snake case, and columns that end up with the same name, such as "a b"
+/// and "a_b", are numbered in order.
+#[derive(Debug, Default)]
+pub struct FieldNames {
+    seen: HashSet<String>,
+}
+
+impl FieldNames {
+    /// Field names for a struct with no fields yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The name of the field for the next column of the struct.
+    pub fn field_name(&mut self, column: &str) -> String {
+        let base = field_name(column);
+        let mut field = base.clone();
+        let mut n = 1;
+        while !self.seen.insert(field.clone()) {
+            n += 1;
+            field = format!("{base}_{n}");
+        }
+        field
+    }
+}
+
+/// The name, dtype, and nullability of every column of a parquet, CSV, or IPC file.
+///
+/// The format is picked by the file's extension. CSV files don't declare nullability, so it's
+/// inferred from the data, which is read in full.
+///
+/// # Errors
+///
+/// This errors if the file can't be read or has an unsupported extension.
+#[cfg(feature = "files")]
+pub fn file_columns(path: &Path) -> PolarsResult<Vec<(String, DataType, bool)>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    let schema = match extension {
+        "parquet" | "pq" => ParquetReader::new(File::open(path)?).schema()?,
+        "arrow" | "ipc" | "feather" => IpcReader::new(File::open(path)?).schema()?,
+        "csv" => {
+            let df = CsvReadOptions::default()
+                .try_into_reader_with_file_path(Some(path.to_path_buf()))?
+                .finish()?;
+            return Ok(df
+                .get_columns()
+                .iter()
+                .map(|c| (c.name().to_string(), c.dtype().clone(), c.null_count() > 0))
+                .collect());
+        }
+        _ => {
+            return Err(PolarsError::InvalidOperation(
+                format!("unsupported file extension {extension:?}").into(),
+            ));
+        }
+    };
+    Ok(schema
+        .iter_values()
+        .map(|f| {
+            let dtype = DataType::from_arrow_field(f);
+            (f.name.to_string(), dtype, f.is_nullable)
+        })
+        .collect())
+}
+
+/// Options for the structs generated by [generate_struct].
+#[cfg(feature = "schema")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructOptions {
+    /// The name of the struct.
+    pub name: String,
+    /// Derive macros from colback to apply along with `ColbackView`, such as `ColbackOwned`.
+    pub derives: Vec<String>,
+    /// The columns that can hold nulls, which are wrapped in `Option`.
+    pub nullable: Vec<String>,
+    /// Whether to emit the `use` declarations that the struct needs.
+    ///
+    /// Turn this off when generating several structs into the same module, and import the
+    /// derive macros (and `chrono::NaiveDateTime`, for datetime columns) once instead.
+    pub imports: bool,
+}
+
+#[cfg(feature = "schema")]
+impl StructOptions {
+    /// Options for a struct with the given name, with no extra derives or nullable columns.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            derives: Vec::new(),
+            nullable: Vec::new(),
+            imports: true,
+        }
+    }
+}
+
+/// Generate the source of a struct that derives `ColbackView` for the columns of a schema.
+///
+/// This is meant to be used from build scripts, to generate structs for tables whose schemas
+/// are kept elsewhere, such as in a schema registry. See [struct_definition] for how the fields
+/// are generated.
+#[cfg(feature = "schema")]
+pub fn generate_struct(schema: &Schema, options: &StructOptions) -> String {
+    let columns = schema.iter().map(|(name, dtype)| {
+        let nullable = options.nullable.iter().any(|c| c == name.as_str());
+        (name.as_str(), dtype, nullable)
+    });
+    emit(options, columns)
+}
+
+/// Generate the source of a struct that derives `ColbackView` for the given columns.
+///
+/// Each column is given as its name, dtype, and whether it can hold nulls. Fields are named in
+/// snake case, with a `name` attribute for columns whose names aren't valid field names, and
+/// nullable columns are wrapped in `Option`. Columns with dtypes that the derive macros don't
+/// support are left out, with a comment noting their dtype.
+#[cfg(feature = "schema")]
+pub fn struct_definition<'a>(
+    name: &str,
+    columns: impl IntoIterator<Item = (&'a str, &'a DataType, bool)>,
+) -> String {
+    emit(&StructOptions::new(name), columns)
+}
+
+/// Emit the source of a struct for the given columns.
+#[cfg(feature = "schema")]
+fn emit<'a>(
+    options: &StructOptions,
+    columns: impl IntoIterator<Item = (&'a str, &'a DataType, bool)>,
+) -> String {
+    let mut fields = String::new();
+    let mut uses_datetime = false;
+    let mut names = FieldNames::new();
+    for (column, dtype, nullable) in columns {
+        let Some((ty, time_unit)) = field_type(dtype) else {
+            let _ = writeln!(
+                fields,
+                "    // unsupported column {column:?} with dtype {dtype}"
+            );
+            continue;
+        };
+        uses_datetime |= ty == "NaiveDateTime";
+
+        let field = names.field_name(column);
+        let mut attrs = Vec::new();
+        if field != column {
+            attrs.push(format!("name = {column:?}"));
+        }
+        if nullable {
+            attrs.push("null = \"option\"".to_string());
+        }
+        if let Some(unit) = time_unit {
+            attrs.push(format!("time_unit = {unit:?}"));
+        }
+        if !attrs.is_empty() {
+            let _ = writeln!(fields, "    #[polars({})]", attrs.join(", "));
+        }
+        let ty = if nullable {
+            format!("Option<{ty}>")
+        } else {
+            ty.to_string()
+        };
+        let _ = writeln!(fields, "    pub {field}: {ty},");
+    }
+
+    let derives = std::iter::once("ColbackView")
+        .chain(options.derives.iter().map(String::as_str))
+        .collect::<Vec<_>>();
+    let mut source = String::new();
+    if options.imports {
+        if uses_datetime {
+            source.push_str("use chrono::NaiveDateTime;\n");
+        }
+        let mut imports = derives.clone();
+        imports.sort_unstable();
+        match imports.as_slice() {
+            [derive] => {
+                let _ = writeln!(source, "use colback::{derive};\n");
+            }
+            _ => {
+                let _ = writeln!(source, "use colback::{{{}}};\n", imports.join(", "));
+            }
+        }
+    }
+    let _ = write!(
+        source,
+        "#[derive({})]\npub struct {} {{\n{fields}}}\n",
+        derives.join(", "),
+        options.name
+    );
+    source
+}
+
+#[cfg(all(test, feature = "schema"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_struct() {
+        let schema = Schema::from_iter([
+            ("id".into(), DataType::UInt64),
+            ("note".into(), DataType::String),
+        ]);
+        let options = StructOptions {
+            derives: vec!["ColbackOwned".to_string()],
+            nullable: vec!["note".to_string()],
+            ..StructOptions::new("Orders")
+        };
+        let expected = "\
+use colback::{ColbackOwned, ColbackView};
+
+#[derive(ColbackView, ColbackOwned)]
+pub struct Orders {
+    pub id: u64,
+    #[polars(null = \"option\")]
+    pub note: Option<String>,
+}
+";
+        assert_eq!(generate_struct(&schema, &options), expected);
+
+        let options = StructOptions {
+            imports: false,
+            ..StructOptions::new("Ids")
+        };
+        let schema = Schema::from_iter([("id".into(), DataType::UInt64)]);
+        assert_eq!(
+            generate_struct(&schema, &options),
+            "#[derive(ColbackView)]\npub struct Ids {\n    pub id: u64,\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_field_names() {
+        let mut names = FieldNames::new();
+        let fields = ["OrderID", "a b", "a_b", "1st", "type", ""].map(|c| names.field_name(c));
+        assert_eq!(
+            fields,
+            ["order_id", "a_b", "a_b_2", "col_1st", "type_", "col_"]
+        );
+    }
+}
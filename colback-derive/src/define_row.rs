@@ -0,0 +1,135 @@
+//! Generation of structs from inline schemas.
+
+use crate::emit::{ColumnSpec, emit_struct};
+use quote::quote;
+use syn::{
+    Token, braced, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+};
+
+/// The input of `define_row!`: `StructName { "column" => Dtype, ... }`.
+pub struct RowInput {
+    name: syn::Ident,
+    columns: Punctuated<ColumnInput, Token![,]>,
+}
+
+/// A column of `define_row!`: `"column" => Dtype` or `"column" => Option<Dtype>`.
+struct ColumnInput {
+    name: syn::LitStr,
+    dtype: syn::Ident,
+    time_unit: Option<syn::Ident>,
+    nullable: bool,
+}
+
+impl Parse for RowInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let content;
+        braced!(content in input);
+        let columns = content.parse_terminated(ColumnInput::parse, Token![,])?;
+        Ok(Self { name, columns })
+    }
+}
+
+impl Parse for ColumnInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let mut dtype: syn::Ident = input.parse()?;
+        let nullable = dtype == "Option";
+        if nullable {
+            input.parse::<Token![<]>()?;
+            dtype = input.parse()?;
+        }
+        // Datetimes take a time unit and a time zone, which must be `None`
+        let time_unit = if input.peek(syn::token::Paren) {
+            let args;
+            parenthesized!(args in input);
+            let unit: syn::Ident = args.parse()?;
+            if args.parse::<Option<Token![,]>>()?.is_some() {
+                let tz: syn::Ident = args.parse()?;
+                if tz != "None" {
+                    return Err(syn::Error::new(tz.span(), "time zones aren't supported"));
+                }
+            }
+            Some(unit)
+        } else {
+            None
+        };
+        if nullable {
+            input.parse::<Token![>]>()?;
+        }
+        Ok(Self {
+            name,
+            dtype,
+            time_unit,
+            nullable,
+        })
+    }
+}
+
+/// Expand `define_row!` into a struct deriving `ColbackView` with the given columns.
+pub fn expand(
+    rt: &proc_macro2::TokenStream,
+    input: RowInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut columns = Vec::new();
+    for column in input.columns {
+        let dtype = &column.dtype;
+        let ty = match dtype.to_string().as_str() {
+            "Boolean" => quote!(bool),
+            "UInt8" => quote!(u8),
+            "UInt16" => quote!(u16),
+            "UInt32" => quote!(u32),
+            "UInt64" => quote!(u64),
+            "Int32" => quote!(i32),
+            "Int64" => quote!(i64),
+            "Float32" => quote!(f32),
+            "Float64" => quote!(f64),
+            "String" => quote!(String),
+            "Datetime" => quote!(#rt::__chrono::NaiveDateTime),
+            _ => {
+                return Err(syn::Error::new(
+                    dtype.span(),
+                    format!("unsupported dtype {dtype}"),
+                ));
+            }
+        };
+        let time_unit = match (dtype == "Datetime", &column.time_unit) {
+            (true, Some(unit)) => match unit.to_string().as_str() {
+                "ns" => Some("ns"),
+                "us" => Some("us"),
+                // Milliseconds are the default unit, as in generated struct definitions
+                "ms" => None,
+                _ => {
+                    return Err(syn::Error::new(
+                        unit.span(),
+                        "time unit must be one of ns, us or ms",
+                    ));
+                }
+            },
+            (true, None) => {
+                return Err(syn::Error::new(
+                    dtype.span(),
+                    "Datetime requires a time unit, e.g. Datetime(ms, None)",
+                ));
+            }
+            (false, Some(unit)) => {
+                return Err(syn::Error::new(
+                    unit.span(),
+                    format!("{dtype} doesn't take arguments"),
+                ));
+            }
+            (false, None) => None,
+        };
+        columns.push(ColumnSpec {
+            name: column.name.value(),
+            ty,
+            time_unit,
+            nullable: column.nullable,
+        });
+    }
+    let doc = format!("The columns of the `{}` schema.", input.name);
+    Ok(emit_struct(rt, &input.name, &doc, columns))
+}
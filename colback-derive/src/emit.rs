@@ -0,0 +1,65 @@
+//! Emission of struct definitions from column specifications.
+
+use colback_codegen::FieldNames;
+use quote::{format_ident, quote};
+
+/// A column of a generated struct.
+pub struct ColumnSpec {
+    /// The name of the column in the dataframe.
+    pub name: String,
+    /// The field type, without an `Option` wrapper.
+    pub ty: proc_macro2::TokenStream,
+    /// The time unit attribute of a datetime field, which is left out for the default unit.
+    pub time_unit: Option<&'static str>,
+    /// Whether the column can hold nulls.
+    pub nullable: bool,
+}
+
+/// Emit a struct deriving `ColbackView` with a field for each column.
+///
+/// Fields are named in snake case, with a `name` attribute for columns whose names aren't valid
+/// field names, and nullable columns are wrapped in `Option`.
+pub fn emit_struct(
+    rt: &proc_macro2::TokenStream,
+    name: &syn::Ident,
+    doc: &str,
+    columns: Vec<ColumnSpec>,
+) -> proc_macro2::TokenStream {
+    let mut fields = Vec::new();
+    let mut names = FieldNames::new();
+    for column in columns {
+        let field = names.field_name(&column.name);
+        let ident = format_ident!("{}", field);
+
+        let mut attrs = Vec::new();
+        if field != column.name {
+            let col_name = &column.name;
+            attrs.push(quote!(name = #col_name));
+        }
+        if column.nullable {
+            attrs.push(quote!(null = "option"));
+        }
+        if let Some(unit) = column.time_unit {
+            attrs.push(quote!(time_unit = #unit));
+        }
+        let attrs = (!attrs.is_empty()).then(|| quote!(#[polars(#(#attrs),*)]));
+        let ty = column.ty;
+        let ty = if column.nullable {
+            quote!(Option<#ty>)
+        } else {
+            ty
+        };
+        fields.push(quote! {
+            #attrs
+            pub #ident: #ty
+        });
+    }
+
+    quote! {
+        #[doc = #doc]
+        #[derive(#rt::ColbackView)]
+        pub struct #name {
+            #(#fields),*
+        }
+    }
+}
@@ -1,12 +1,22 @@
+mod define_row;
+mod emit;
+#[cfg(feature = "schema")]
+mod schema;
 mod type_helpers;
 
-use crate::type_helpers::{map_type, option_inner};
-use darling::FromField;
+use crate::type_helpers::{
+    TypeMap, is_integer, map_category, map_epoch, map_type, option_inner, time_unit,
+    time_zone_ident, valid_time_zone, widens,
+};
+use darling::{
+    FromDeriveInput, FromField, FromVariant,
+    util::{Flag, PathList, SpannedValue},
+};
 use proc_macro::TokenStream;
 use proc_macro_crate::{FoundCrate, crate_name};
 use proc_macro_error::{abort, proc_macro_error};
-use quote::{format_ident, quote};
-use syn::{Data, DeriveInput, Fields, parse_macro_input};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{Data, DeriveInput, Fields, ext::IdentExt, parse_macro_input, spanned::Spanned};
 
 /// Get the runtime path of the colback crate.
 ///
@@ -22,6 +32,424 @@ fn runtime_path() -> proc_macro2::TokenStream {
     }
 }
 
+/// Implement `Serialize` for a generated row struct, keyed by the struct's column names.
+///
+/// This only generates code when the `serde` feature is enabled, and the implementation refers
+/// to the `serde` crate through the runtime so that users don't need to depend on it directly.
+fn serialize_impl(
+    rt: &proc_macro2::TokenStream,
+    ty: proc_macro2::TokenStream,
+    label: &syn::Ident,
+    idents: &[syn::Ident],
+    col_names: &[String],
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "serde") {
+        return quote!();
+    }
+    let label = label.to_string();
+    let n_fields = idents.len();
+    quote! {
+        impl #rt::__serde::Serialize for #ty {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: #rt::__serde::Serializer,
+            {
+                use #rt::__serde::ser::SerializeStruct as _;
+                let mut state = serializer.serialize_struct(#label, #n_fields)?;
+                #(state.serialize_field(#col_names, &self.#idents)?;)*
+                state.end()
+            }
+        }
+    }
+}
+
+/// Implement `Serialize` and `Deserialize` for an enum deriving `ColbackCategory`, as the names
+/// of its categories.
+///
+/// This only generates code when the `serde` feature is enabled, so that rows with category
+/// fields can be serialized and owned rows deserialized like rows with string fields.
+fn category_serde_impl(rt: &proc_macro2::TokenStream, ty: &syn::Ident) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "serde") {
+        return quote!();
+    }
+    quote! {
+        impl #rt::__serde::Serialize for #ty {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: #rt::__serde::Serializer,
+            {
+                serializer.serialize_str(#rt::ColbackCategory::category(self))
+            }
+        }
+
+        impl<'de> #rt::__serde::Deserialize<'de> for #ty {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: #rt::__serde::Deserializer<'de>,
+            {
+                let name = <::std::borrow::Cow<'de, str> as #rt::__serde::Deserialize>::deserialize(
+                    deserializer,
+                )?;
+                <Self as #rt::ColbackCategory>::from_category(&name).ok_or_else(|| {
+                    let categories = <Self as #rt::ColbackCategory>::VARIANTS
+                        .iter()
+                        .map(#rt::ColbackCategory::category)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    <D::Error as #rt::__serde::de::Error>::custom(format!(
+                        "unknown category {name:?}, expected one of {categories}"
+                    ))
+                })
+            }
+        }
+    }
+}
+
+/// Implement proptest's `Arbitrary` for a generated owned row struct.
+///
+/// This only generates code when the `proptest` feature is enabled. Fields use the `Arbitrary`
+/// impl of their type, except for datetimes, which are limited to the range of their time unit.
+/// The field strategies are nested in pairs so that structs can have any number of fields.
+fn arbitrary_impl(
+    rt: &proc_macro2::TokenStream,
+    ty: &syn::Ident,
+    idents: &[syn::Ident],
+    strategies: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "proptest") {
+        return quote!();
+    }
+    let (strategy, pattern) = idents.iter().zip(strategies).rev().fold(
+        (quote!(#rt::__proptest::strategy::Just(())), quote!(())),
+        |(strategy, pattern), (ident, field)| {
+            (quote!((#field, #strategy)), quote!((#ident, #pattern)))
+        },
+    );
+    quote! {
+        impl #rt::__proptest::arbitrary::Arbitrary for #ty {
+            type Parameters = ();
+            type Strategy = #rt::__proptest::strategy::BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                use #rt::__proptest::strategy::Strategy as _;
+                #strategy
+                    .prop_map(|#pattern| Self { #(#idents),* })
+                    .boxed()
+            }
+        }
+    }
+}
+
+/// Generate methods on a view that pack its numeric fields into an ndarray matrix.
+///
+/// This only generates code when the `ndarray` feature is enabled. `numeric` holds the variants
+/// of the integer and float fields along with an `f64` expression for each field of a `row`, and
+/// `other` holds the variants of the remaining fields.
+fn ndarray_impl(
+    rt: &proc_macro2::TokenStream,
+    view: &syn::Ident,
+    field_enum: &syn::Ident,
+    numeric: &[(syn::Ident, proc_macro2::TokenStream)],
+    other: &[syn::Ident],
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "ndarray") {
+        return quote!();
+    }
+    let (variants, values): (Vec<_>, Vec<_>) = numeric.iter().cloned().unzip();
+    let other_arm = (!other.is_empty()).then(
+        || quote!(#(#field_enum::#other)|* => unreachable!("only numeric fields are packed"),),
+    );
+    quote! {
+        impl<'a> #view<'a> {
+            /// Pack the numeric fields of the rows into a matrix, with a column per field in field
+            /// order.
+            ///
+            /// See `to_ndarray_of` for how rows and nulls are handled.
+            pub fn to_ndarray(&self) -> #rt::Result<#rt::__ndarray::Array2<f64>> {
+                self.to_ndarray_of(&[#(#field_enum::#variants),*])
+            }
+
+            /// Pack the given numeric fields of the rows into a matrix, with a column per field in
+            /// the order they're given.
+            ///
+            /// Rows are read with the null policies of their fields, so skipped rows are left out
+            /// and nulls in `Option` fields are packed as NaN.
+            ///
+            /// # Errors
+            ///
+            /// This errors if any of the fields isn't an integer or float field, or if a row
+            /// can't be materialized.
+            pub fn to_ndarray_of(
+                &self,
+                fields: &[#field_enum],
+            ) -> #rt::Result<#rt::__ndarray::Array2<f64>> {
+                const NUMERIC: &[#field_enum] = &[#(#field_enum::#variants),*];
+                if let Some(field) = fields.iter().find(|field| !NUMERIC.contains(field)) {
+                    return Err(::polars::prelude::PolarsError::InvalidOperation(
+                        format!(
+                            "column {} isn't numeric",
+                            #rt::ColumnField::column_name(field),
+                        )
+                        .into(),
+                    )
+                    .into());
+                }
+                let mut values = Vec::with_capacity(self.len() * fields.len());
+                let mut rows = 0;
+                for idx in 0..self.len() {
+                    let Some(row) = self.unskipped_row(idx).transpose()? else {
+                        continue;
+                    };
+                    // Structs without numeric fields only have unreachable arms
+                    values.extend(fields.iter().map(|field| -> f64 {
+                        match field {
+                            #(#field_enum::#variants => #values,)*
+                            #other_arm
+                        }
+                    }));
+                    rows += 1;
+                }
+                Ok(
+                    #rt::__ndarray::Array2::from_shape_vec((rows, fields.len()), values)
+                        .expect("every row has a value per field"),
+                )
+            }
+        }
+    }
+}
+
+/// Generate methods that convert rows into nalgebra vectors and views into nalgebra matrices.
+///
+/// This only generates code when the `nalgebra` feature is enabled, and only for structs whose
+/// fields all have the same float type. `float_tys` holds the type of each float field that isn't
+/// an `Option`, and `None` for the other fields.
+fn nalgebra_impl(
+    rt: &proc_macro2::TokenStream,
+    view: &syn::Ident,
+    rowref: &syn::Ident,
+    idents: &[syn::Ident],
+    float_tys: &[Option<String>],
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "nalgebra") {
+        return quote!();
+    }
+    let Some(Some(ty)) = float_tys.first() else {
+        return quote!();
+    };
+    if float_tys.iter().any(|t| t.as_ref() != Some(ty)) {
+        return quote!();
+    }
+    let ty = format_ident!("{}", ty);
+    let n = idents.len();
+    quote! {
+        impl #rowref<'_> {
+            /// The row's fields as a vector, in field order.
+            pub fn to_vector(&self) -> #rt::__nalgebra::SVector<#ty, #n> {
+                #rt::__nalgebra::SVector::from([#(self.#idents),*])
+            }
+        }
+
+        impl<'a> #view<'a> {
+            /// The rows as a matrix, with a row per view row and a column per field in field
+            /// order.
+            ///
+            /// Rows are read with the null policies of their fields, so skipped rows are left out.
+            ///
+            /// # Errors
+            ///
+            /// This errors if a row can't be materialized.
+            pub fn to_matrix(&self) -> #rt::Result<#rt::__nalgebra::DMatrix<#ty>> {
+                let mut values = Vec::with_capacity(self.len() * #n);
+                for idx in 0..self.len() {
+                    if let Some(row) = self.unskipped_row(idx).transpose()? {
+                        values.extend([#(row.#idents),*]);
+                    }
+                }
+                Ok(#rt::__nalgebra::DMatrix::from_row_slice(
+                    values.len() / #n,
+                    #n,
+                    &values,
+                ))
+            }
+        }
+    }
+}
+
+/// Generate a method on a view that maps its chunks to dataframes in parallel.
+///
+/// This only generates code when the `rayon` feature is enabled.
+fn rayon_impl(
+    rt: &proc_macro2::TokenStream,
+    struct_name: &syn::Ident,
+    view: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "rayon") {
+        return quote!();
+    }
+    quote! {
+        impl<'a> #view<'a> {
+            /// Map each chunk of the view's rows to a dataframe in parallel, stacking the outputs
+            /// in row order.
+            ///
+            /// Each chunk is viewed on its own, so its columns can be read as slices through
+            /// `raw`. See `colback::par_map_chunks`.
+            ///
+            /// # Errors
+            ///
+            /// This errors if the struct has fields that fill their nulls from other rows, if `f`
+            /// errors for any chunk, or if the outputs can't be stacked.
+            pub fn par_map_chunks<F>(&self, f: F) -> #rt::Result<::polars::prelude::DataFrame>
+            where
+                F: Fn(#view<'_>) -> #rt::Result<::polars::prelude::DataFrame> + Sync,
+            {
+                #rt::par_map_chunks::<#struct_name, _, _>(self, f)
+            }
+        }
+    }
+}
+
+/// Convert a snake case field name to the pascal case name of its field enum variant.
+fn variant_ident(ident: &syn::Ident) -> syn::Ident {
+    let name: String = ident
+        .unraw()
+        .to_string()
+        .split('_')
+        .flat_map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_uppercase)
+                .chain(chars)
+        })
+        .collect();
+    syn::Ident::new(&name, ident.span())
+}
+
+/// A `min` or `max` bound as a value of the field's type `ty`, which is checked at the attribute
+/// as for defaults.
+fn bound_value(ty: &syn::Type, bound: &syn::Expr) -> proc_macro2::TokenStream {
+    quote_spanned!(bound.span()=> ::std::convert::identity::<#ty>(#bound))
+}
+
+/// Format the tokens of an expression as it's usually written, for the labels of constraints.
+///
+/// `quote!(..).to_string()` puts a space between every token, so e.g. `col("a").gt(lit(-1))`
+/// would read as `col ("a") . gt (lit (- 1))`. This only spaces out binary operators and
+/// adjacent words, and puts a space after commas.
+fn expr_label(tokens: proc_macro2::TokenStream) -> String {
+    use proc_macro2::{Delimiter, Spacing, TokenTree};
+
+    let mut label = String::new();
+    // Whether the previous token ends an operand, so that a following `-` is a binary operator
+    let mut operand = false;
+    // Whether the previous token is part of an operator that continues, such as the `>` of `>=`
+    let mut joint = false;
+    // Whether the current operator is a binary operator
+    let mut binary = false;
+    for token in tokens {
+        let TokenTree::Punct(punct) = &token else {
+            match &token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace => ("{ ", " }"),
+                        Delimiter::None => ("", ""),
+                    };
+                    label.push_str(open);
+                    label.push_str(&expr_label(group.stream()));
+                    label.push_str(close);
+                }
+                _ => {
+                    if operand {
+                        label.push(' ');
+                    }
+                    label.push_str(&token.to_string());
+                }
+            }
+            (operand, joint) = (true, false);
+            continue;
+        };
+        let c = punct.as_char();
+        match c {
+            '.' | ':' | '!' => label.push(c),
+            ',' => label.push_str(", "),
+            _ => {
+                if !joint {
+                    binary = operand;
+                    if binary {
+                        label.push(' ');
+                    }
+                }
+                label.push(c);
+                if binary && punct.spacing() == Spacing::Alone {
+                    label.push(' ');
+                }
+            }
+        }
+        (operand, joint) = (false, punct.spacing() == Spacing::Joint);
+    }
+    label
+}
+
+/// Struct attributes that apply to the whole derived view.
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(polars))]
+struct ColbackStructOpts {
+    /// The structs whose rows this key struct can join or group.
+    ///
+    /// This is only valid for `ColbackKey`. Each listed struct must have all of the key's fields
+    /// with the same types, which is checked at compile time.
+    #[darling(default)]
+    key_of: PathList,
+
+    /// Comparison traits to implement for the generated row references and owned rows, by
+    /// comparing their field values.
+    ///
+    /// Derive macros can't see the other traits that a struct derives, so the traits are listed
+    /// again here, as in `#[polars(derive(PartialEq, Eq, Hash))]`. Only `PartialEq`, `Eq` and
+    /// `Hash` are supported.
+    #[darling(default)]
+    derive: PathList,
+
+    /// Constraints on the columns that every row must satisfy, as polars expressions that
+    /// evaluate to a boolean column, such as `check = col("price").gt(lit(0))`.
+    ///
+    /// This can be given several times. The constraints are evaluated over whole columns when a
+    /// view is created, and a view of a dataframe where any of them is `false` for some row
+    /// errors with `ConstraintViolations`, which labels each constraint with its expression as
+    /// it's written here. Null results pass, as in SQL.
+    ///
+    /// `sample_df` and `arb_df` don't know about these constraints, so their dataframes may not
+    /// satisfy them, unlike a field's `min` and `max`.
+    #[darling(multiple)]
+    check: Vec<syn::Expr>,
+}
+
+impl ColbackStructOpts {
+    /// Whether a comparison trait was listed in `derive`, aborting on unsupported traits.
+    fn derives(&self, name: &str) -> bool {
+        self.derive.iter().any(|path| {
+            if !["PartialEq", "Eq", "Hash"].iter().any(|t| path.is_ident(t)) {
+                abort!(
+                    path,
+                    "only PartialEq, Eq and Hash can be listed in derive(...)"
+                );
+            }
+            path.is_ident(name)
+        })
+    }
+}
+
+/// Parse the struct level attributes of a derive input.
+fn struct_opts(input: &DeriveInput) -> ColbackStructOpts {
+    match ColbackStructOpts::from_derive_input(input) {
+        Ok(v) => v,
+        Err(e) => abort!(input.ident, "invalid #[polars(...)] on struct: {}", e),
+    }
+}
+
 /// Field attributes specifying how a column value should map to a row view.
 #[derive(Debug, FromField)]
 #[darling(attributes(polars))]
@@ -42,23 +470,1204 @@ struct ColbackFieldOpts {
     ///   be null. This is the only allowed value if the original struct has a field set to
     ///   optional.
     /// - "default": Null row values will be replaced by some default value.
+    /// - "skip": Rows with a null value are left out when iterating over or aggregating the view,
+    ///   and getting one of them returns a `RowSkipped` error.
     #[darling(default)]
-    null: Option<String>,
+    null: Option<SpannedValue<String>>,
 
     /// The default value to use for null row values.
     ///
-    /// This is required if "default" is selected for the null handling policy. Setting this value
-    /// is an error if the `null` field is set to anything besides "default".
+    /// This is required if "default" is selected for the null handling policy, for a missing
+    /// column or for NaN values. Setting this value is an error if none of them is "default".
     #[darling(default)]
     default: Option<syn::Expr>,
+
+    /// What to do when the column is missing from the dataframe. Can be one of:
+    ///
+    /// - "error": Will raise an error when creating the view. This is the default.
+    /// - "default": Every row will have the default value, as if the column was filled with it.
+    ///   This is separate from the null policy, which still applies when the column is present.
+    #[darling(default)]
+    missing: Option<SpannedValue<String>>,
+
+    /// How to handle NaN values of a float field. Can be one of:
+    ///
+    /// - "error": Will raise an error with the row index when getting a row with a NaN value.
+    /// - "null": NaN values are treated as nulls, so they're handled by the null policy.
+    /// - "default": NaN values will be replaced by the default value.
+    ///
+    /// If this isn't set, NaN values are passed through like any other float.
+    #[darling(default)]
+    nan: Option<SpannedValue<String>>,
+
+    /// Accept a column of a wider integer type than the field, such as "i64" for an `i32` field.
+    ///
+    /// Values are narrowed when a row is read, and values that don't fit are handled by
+    /// `overflow`.
+    #[darling(default)]
+    cast_from: Option<syn::Type>,
+
+    /// How to handle values of a `cast_from` column that don't fit in the field's type. Can be
+    /// one of:
+    ///
+    /// - "error": Will raise a `ValueOutOfRange` error with the row index. This is the default.
+    /// - "saturate": Values are clamped to the smallest or largest value of the field's type.
+    #[darling(default)]
+    overflow: Option<SpannedValue<String>>,
+
+    /// The smallest value allowed in a numeric column, checked over the whole column when a view
+    /// is created. See the struct's `check` attribute. It's given as a value of the field's type,
+    /// and `sample_df` and `arb_df` only generate values within it.
+    #[darling(default)]
+    min: Option<syn::Expr>,
+
+    /// The largest value allowed in a numeric column, checked over the whole column when a view
+    /// is created. See the struct's `check` attribute. It's given as a value of the field's type,
+    /// and `sample_df` and `arb_df` only generate values within it.
+    #[darling(default)]
+    max: Option<syn::Expr>,
+
+    /// Fill null values from other values of the column, instead of using a null policy. Can be
+    /// one of:
+    ///
+    /// - "forward": Use the closest non-null value before the row.
+    /// - "backward": Use the closest non-null value after the row.
+    /// - "mean": Use the mean of the column's non-null values. Only numeric fields support this,
+    ///   and integer fields use the mean rounded to the nearest integer, with halves rounded away
+    ///   from zero.
+    /// - "zero": Use the default value of the field's type, e.g. `0` or `""`.
+    ///
+    /// The fill is computed when the view is created, so getting a row stays fast. Rows that
+    /// still have no value, such as leading nulls with "forward", error like the "error" policy.
+    #[darling(default)]
+    fill: Option<SpannedValue<String>>,
+
+    /// The time unit of a datetime column: one of "ns", "us" or "ms".
+    ///
+    /// This is only valid for datetime fields and defaults to "ms", which matches the unit that
+    /// polars uses when building a column from chrono values.
+    #[darling(default)]
+    time_unit: Option<SpannedValue<String>>,
+
+    /// The time zone of a datetime column, such as "UTC", "America/New_York" or "+01:00", which
+    /// is checked when the macro is expanded.
+    ///
+    /// This is only valid for datetime fields, which expect a column with no time zone if it
+    /// isn't set. Time zone aware columns hold UTC timestamps, so row values are in UTC. A column
+    /// with a different time zone errors with `TimezoneMismatch` unless `convert_time_zone` is
+    /// set.
+    #[darling(default)]
+    time_zone: Option<SpannedValue<String>>,
+
+    /// Accept datetime columns with a different time zone than `time_zone`, converting their
+    /// values to the field's time zone when rows are read.
+    ///
+    /// Time zone aware columns are read as UTC by fields with a time zone, and as local times in
+    /// the column's time zone by fields without one. Naive columns are read as local times in the
+    /// field's time zone, where local times that don't exist or are ambiguous because of daylight
+    /// saving time error with `ValueOutOfRange`.
+    #[darling(default)]
+    convert_time_zone: Flag,
+
+    /// Read an integer column of time units since the Unix epoch into a `DateTime<Utc>` field,
+    /// or of time units into a `Duration` field. Can be one of "ns", "us" or "ms".
+    ///
+    /// The column is an `i64` column, or a `u64` column with `cast_from = "u64"`. Values are
+    /// converted when a row is read, and values that the field's type can't represent error with
+    /// `ValueOutOfRange`.
+    #[darling(default)]
+    epoch: Option<SpannedValue<String>>,
+
+    /// Read a column of category names into a field whose type is an enum deriving
+    /// `ColbackCategory`.
+    ///
+    /// The column is a string column, or a categorical or enum column whose categories are
+    /// looked up by name. Rows are written as string columns.
+    #[darling(default)]
+    category: Flag,
+
+    /// How to handle values of a `category` column that the field's enum has no variant for. Can
+    /// be one of:
+    ///
+    /// - "error": Will raise an `UnknownCategory` error with the row index. This is the default.
+    /// - "other(Variant)": Unknown categories are read as the given variant of the enum.
+    /// - "skip": Rows with an unknown category are left out like rows skipped by the null
+    ///   policy, and getting one of them returns a `RowSkipped` error.
+    ///
+    /// Nulls are still handled by the null policy.
+    #[darling(default)]
+    unknown: Option<SpannedValue<String>>,
+}
+
+impl ColbackFieldOpts {
+    /// Parse and validate the attributes of a field.
+    ///
+    /// Errors point at the attribute value or type that caused them, rather than at the field or
+    /// the struct, so that rustc underlines the offending tokens.
+    fn parse(field: &syn::Field) -> Self {
+        let opts = match Self::from_field(field) {
+            Ok(v) => v,
+            Err(e) => abort!(e.span(), "invalid #[polars(...)] on field: {}", e),
+        };
+        let (is_option, _) = option_inner(&opts.ty);
+        if let Some(fill) = &opts.fill {
+            if !["forward", "backward", "mean", "zero"].contains(&fill.as_str()) {
+                abort!(
+                    fill.span(),
+                    "fill must be one of \"forward\", \"backward\", \"mean\" or \"zero\""
+                );
+            }
+            if opts.null.is_some() || is_option {
+                abort!(
+                    fill.span(),
+                    "fill replaces the null policy, so it can't be used with null or Option<T>"
+                );
+            }
+        }
+        match (&opts.null, is_option) {
+            (Some(null), _) if !["error", "option", "default", "skip"].contains(&null.as_str()) => {
+                abort!(
+                    null.span(),
+                    "null must be one of \"error\", \"option\", \"default\" or \"skip\""
+                )
+            }
+            (Some(null), false) if null.as_str() == "option" => abort!(
+                null.span(),
+                "null=\"option\" requires the field type to be Option<T>"
+            ),
+            (Some(null), true) if null.as_str() != "option" => {
+                abort!(null.span(), "Option<T> fields must use null='option'")
+            }
+            (None, true) => abort!(opts.ty, "Option<T> fields must use null='option'"),
+            _ => (),
+        }
+        if let Some(missing) = &opts.missing {
+            if !["error", "default"].contains(&missing.as_str()) {
+                abort!(
+                    missing.span(),
+                    "missing must be one of \"error\" or \"default\""
+                );
+            }
+            if missing.as_str() == "default" && opts.default.is_none() {
+                abort!(
+                    missing.span(),
+                    "missing='default' requires #[polars(default = ...)] to be set"
+                );
+            }
+        }
+        if let Some(nan) = &opts.nan {
+            if !["error", "null", "default"].contains(&nan.as_str()) {
+                abort!(
+                    nan.span(),
+                    "nan must be one of \"error\", \"null\" or \"default\""
+                );
+            }
+            if nan.as_str() == "default" && opts.default.is_none() {
+                abort!(
+                    nan.span(),
+                    "nan='default' requires #[polars(default = ...)] to be set"
+                );
+            }
+        }
+        if let Some(epoch) = &opts.epoch {
+            if time_unit(epoch).is_none() {
+                abort!(
+                    epoch.span(),
+                    "epoch must be one of \"ns\", \"us\" or \"ms\""
+                );
+            }
+            if let Some(overflow) = &opts.overflow {
+                abort!(
+                    overflow.span(),
+                    "overflow isn't supported for epoch fields, out of range values always error"
+                );
+            }
+        }
+        match (&opts.cast_from, &opts.epoch) {
+            (Some(src), Some(_))
+                if !matches!(src, syn::Type::Path(tp)
+                    if tp.path.is_ident("i64") || tp.path.is_ident("u64")) =>
+            {
+                abort!(src, "epoch fields can only be cast from i64 or u64");
+            }
+            (Some(src), None) => {
+                let (_, inner_ty) = option_inner(&opts.ty);
+                if !is_integer(src) || !is_integer(&inner_ty) {
+                    abort!(src, "cast_from is only supported between integer types");
+                }
+                if !widens(&inner_ty, src) {
+                    abort!(
+                        src,
+                        "cast_from must be a type that every value of the field fits in"
+                    );
+                }
+            }
+            _ => (),
+        }
+        if let Some(overflow) = &opts.overflow {
+            if !["error", "saturate"].contains(&overflow.as_str()) {
+                abort!(
+                    overflow.span(),
+                    "overflow must be one of \"error\" or \"saturate\""
+                );
+            }
+            if opts.cast_from.is_none() {
+                abort!(
+                    overflow.span(),
+                    "overflow is only used with #[polars(cast_from = ...)]"
+                );
+            }
+        }
+        match (&opts.null, &opts.default) {
+            (Some(null), None) if null.as_str() == "default" => abort!(
+                null.span(),
+                "null='default' requires #[polars(default = ...)] to be set"
+            ),
+            (_, Some(default))
+                if opts.policy() != "default"
+                    && !opts.default_if_missing()
+                    && opts.nan_policy() != Some("default") =>
+            {
+                abort!(
+                    default,
+                    "default is only used when null, missing or nan is \"default\""
+                )
+            }
+            _ => (),
+        }
+        if let Some(unit) = &opts.time_unit
+            && time_unit(unit).is_none()
+        {
+            abort!(
+                unit.span(),
+                "time_unit must be one of \"ns\", \"us\" or \"ms\""
+            );
+        }
+        if let Some(tz) = &opts.time_zone
+            && !valid_time_zone(tz)
+        {
+            abort!(
+                tz.span(),
+                "unknown time zone {:?}, expected a name from the time zone database such as \"America/New_York\" or an offset such as \"+01:00\"",
+                tz.as_str()
+            );
+        }
+        if let Some(unknown) = &opts.unknown {
+            if !opts.category.is_present() {
+                abort!(
+                    unknown.span(),
+                    "unknown is only used with #[polars(category)]"
+                );
+            }
+            if !["error", "skip"].contains(&unknown.as_str()) && opts.other_category().is_none() {
+                abort!(
+                    unknown.span(),
+                    "unknown must be one of \"error\", \"other(Variant)\" or \"skip\""
+                );
+            }
+        }
+        if opts.category.is_present()
+            && let Some(epoch) = &opts.epoch
+        {
+            abort!(epoch.span(), "epoch isn't supported for category fields");
+        }
+        opts
+    }
+
+    /// The variant that unknown categories are read as, for the "other(Variant)" unknown policy.
+    fn other_category(&self) -> Option<syn::Ident> {
+        let unknown = self.unknown.as_ref()?;
+        let variant = unknown.strip_prefix("other(")?.strip_suffix(')')?;
+        let variant = syn::parse_str::<syn::Ident>(variant.trim()).ok()?;
+        Some(syn::Ident::new(&variant.to_string(), unknown.span()))
+    }
+
+    /// Whether rows with an unknown category are skipped.
+    fn skips_unknown(&self) -> bool {
+        self.unknown
+            .as_ref()
+            .is_some_and(|unknown| unknown.as_str() == "skip")
+    }
+
+    /// The null handling policy of the field, which defaults to "error" and is "fill" for fields
+    /// with a fill strategy.
+    fn policy(&self) -> &str {
+        if self.fill.is_some() {
+            return "fill";
+        }
+        self.null.as_ref().map_or("error", |null| null.as_str())
+    }
+
+    /// Whether rows take the default value when the column is missing.
+    fn default_if_missing(&self) -> bool {
+        self.missing
+            .as_ref()
+            .is_some_and(|missing| missing.as_str() == "default")
+    }
+
+    /// The NaN handling policy of the field, if it has one.
+    fn nan_policy(&self) -> Option<&str> {
+        self.nan.as_ref().map(|nan| nan.as_str())
+    }
+
+    /// The `min` and `max` of a field with bounds, as values of its type `ty`, defaulting to the
+    /// type's smallest and largest values.
+    fn bounds(
+        &self,
+        ty: &syn::Type,
+    ) -> Option<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+        if self.min.is_none() && self.max.is_none() {
+            return None;
+        }
+        let bound = |bound: &Option<syn::Expr>, default: proc_macro2::TokenStream| match bound {
+            Some(bound) => bound_value(ty, bound),
+            None => quote!(<#ty>::#default),
+        };
+        Some((bound(&self.min, quote!(MIN)), bound(&self.max, quote!(MAX))))
+    }
+
+    /// The polars time unit of the field, which is the unit of `epoch` fields and defaults to
+    /// milliseconds.
+    fn unit(&self) -> proc_macro2::TokenStream {
+        let unit = self.epoch.as_ref().or(self.time_unit.as_ref());
+        time_unit(unit.map_or("ms", |unit| unit.as_str()))
+            .expect("time units are validated when parsing")
+    }
+
+    /// Whether values are narrowed from a wider `cast_from` column when a row is read.
+    ///
+    /// `epoch` fields also use `cast_from`, but they convert values from the column's type.
+    fn narrows(&self) -> bool {
+        self.cast_from.is_some() && self.epoch.is_none()
+    }
+
+    /// The type map of the field's type, along with the type map of its column if the column has
+    /// a different type.
+    ///
+    /// Fields with a `cast_from` type read the column as that type and narrow each value, while
+    /// `epoch` fields map both to the column's integer type.
+    fn type_maps(
+        &self,
+        rt: &proc_macro2::TokenStream,
+        ident: &syn::Ident,
+        col_name: &str,
+    ) -> (TypeMap, Option<TypeMap>) {
+        let (_, inner_ty) = option_inner(&self.ty);
+        let unit = self.unit();
+        if let Some(epoch) = &self.epoch {
+            let raw = self
+                .cast_from
+                .clone()
+                .unwrap_or_else(|| syn::parse_quote!(i64));
+            let Some(map) = map_epoch(rt, ident, col_name, &inner_ty, &raw, &unit) else {
+                abort!(
+                    epoch.span(),
+                    "epoch is only supported for DateTime<Utc> and Duration fields"
+                );
+            };
+            return (map, None);
+        }
+        if self.category.is_present() {
+            let other = self.other_category();
+            let map = map_category(
+                rt,
+                ident,
+                col_name,
+                &inner_ty,
+                other.as_ref(),
+                self.skips_unknown(),
+            );
+            return (map, None);
+        }
+        let zone = self.zone(rt);
+        let Some(map) = map_type(rt, ident, col_name, &inner_ty, &unit, &zone) else {
+            abort!(
+                inner_ty,
+                "unsupported field type for ColbackView; add a mapping for this type"
+            );
+        };
+        let col_map = self
+            .cast_from
+            .as_ref()
+            .and_then(|src| map_type(rt, ident, col_name, src, &unit, &zone));
+        (map, col_map)
+    }
+
+    /// The polars time zone of the field, which defaults to no time zone.
+    fn zone(&self, rt: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match &self.time_zone {
+            Some(tz) => {
+                let tz = tz.as_str();
+                quote!(Some(#rt::time_zone(#tz)))
+            }
+            None => quote!(None),
+        }
+    }
+
+    /// Abort if the field has a time unit or time zone but isn't a datetime.
+    fn check_temporal(&self, temporal: bool) {
+        if temporal {
+            return;
+        }
+        if let Some(unit) = &self.time_unit {
+            abort!(
+                unit.span(),
+                "time_unit is only supported for datetime fields"
+            );
+        }
+        if let Some(tz) = &self.time_zone {
+            abort!(tz.span(), "time_zone is only supported for datetime fields");
+        }
+        if self.convert_time_zone.is_present() {
+            abort!(
+                self.convert_time_zone.span(),
+                "convert_time_zone is only supported for datetime fields"
+            );
+        }
+    }
+}
+
+/// Generate a struct deriving `ColbackView` from the schema of a parquet, CSV, or IPC file.
+///
+/// This is invoked as `struct_from_schema!("path/to/file.parquet" as StructName)`, with the path
+/// relative to the crate's manifest directory. The schema is read when the macro is expanded, so
+/// the struct always has the file's columns. Fields are named in snake case, with a `name`
+/// attribute for columns whose names aren't valid field names, and nullable columns are wrapped
+/// in `Option`. Columns with dtypes that the derive macros don't support are left out.
+#[cfg(feature = "schema")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn struct_from_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as schema::SchemaInput);
+    schema::expand(&runtime_path(), input).into()
+}
+
+/// Generate a struct deriving `ColbackView` from an inline schema.
+///
+/// This is invoked as `define_row!(StructName { "column" => Dtype, ... })`, where each dtype is
+/// the name of a polars `DataType` variant that the derive macros support. Datetimes take a time
+/// unit and no time zone, as in `Datetime(ms, None)`, and nullable columns are written as
+/// `Option<Dtype>`. Fields are named as in `struct_from_schema!`.
+#[proc_macro_error]
+#[proc_macro]
+pub fn define_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as define_row::RowInput);
+    match define_row::expand(&runtime_path(), input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(ColbackView, attributes(polars))]
+pub fn derive_colback_view(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    if !struct_opts(&input).key_of.is_empty() {
+        abort!(
+            input.ident,
+            "#[polars(key_of(...))] is only supported by ColbackKey"
+        );
+    }
+    expand_view(&input).into()
+}
+
+/// Derive a struct that can be used as a key to group the rows of other views.
+///
+/// This generates everything that `ColbackView` does, along with an implementation of
+/// `ColbackKey`.
+#[proc_macro_error]
+#[proc_macro_derive(ColbackKey, attributes(polars))]
+pub fn derive_colback_key(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let rt = runtime_path();
+    let struct_name = &input.ident;
+    let opts = struct_opts(&input);
+    let view = expand_view(&input);
+
+    let (field_idents, field_tys): (Vec<_>, Vec<_>) = match input.data {
+        Data::Struct(ref s) => s
+            .fields
+            .iter()
+            .map(|f| (f.ident.clone(), f.ty.clone()))
+            .unzip(),
+        _ => unreachable!("expand_view only accepts structs"),
+    };
+    let key_of_impls = opts.key_of.iter().map(|target| {
+        quote! {
+            impl #rt::KeyOf<#struct_name> for #target {}
+
+            // Check that the target struct has all of the key's fields with the same types
+            const _: () = {
+                #[allow(dead_code)]
+                fn assert_key_fields(row: &#target) {
+                    #(let _: &#field_tys = &row.#field_idents;)*
+                }
+            };
+        }
+    });
+
+    quote! {
+        #view
+
+        impl #rt::ColbackKey for #struct_name {}
+
+        #(#key_of_impls)*
+    }
+    .into()
+}
+
+/// Derive an owned counterpart to the row references of a `ColbackView` struct.
+///
+/// This generates a `<StructName>Owned` struct with the struct's field types, a
+/// `<StructName>RowRef::to_owned` method, methods collecting a view into owned rows, and an
+/// implementation of `ColbackOwned`.
+#[proc_macro_error]
+#[proc_macro_derive(ColbackOwned, attributes(polars))]
+pub fn derive_colback_owned(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let rt = runtime_path();
+    let struct_name = &input.ident;
+    let owned_name = format_ident!("{}Owned", struct_name);
+    let rowref_name = format_ident!("{}RowRef", struct_name);
+    let view_name = format_ident!("{}View", struct_name);
+
+    let fields = match input.data {
+        Data::Struct(ref s) => match s.fields {
+            Fields::Named(ref named) => named.named.iter().collect::<Vec<_>>(),
+            _ => abort!(
+                struct_name,
+                "ColbackOwned only supports structs with named fields"
+            ),
+        },
+        _ => abort!(struct_name, "ColbackOwned can only be derived for structs"),
+    };
+
+    let mut members = Vec::new();
+    let mut conversions = Vec::new();
+    let mut idents = Vec::new();
+    let mut col_names = Vec::new();
+    let mut strategies = Vec::new();
+    let mut samples = Vec::new();
+    for f in fields {
+        let opts = ColbackFieldOpts::parse(f);
+        let unit = opts.unit();
+        let zone = opts.zone(&rt);
+        // Row values are borrowed or `Copy`, and convert into the declared field types
+        let (is_option, inner_ty) = option_inner(&opts.ty);
+        let bounds = opts.bounds(&inner_ty);
+        // NaNs are left out of fields that error on them, or that read them as nulls, since
+        // reading them could fail
+        let no_nans = matches!(opts.nan_policy(), Some("error" | "null"));
+        let ident = opts.ident.unwrap();
+        let ty = opts.ty;
+        let col_name = opts.name.unwrap_or_else(|| ident.to_string());
+        let map = map_type(&rt, &ident, &col_name, &inner_ty, &unit, &zone);
+        let temporal = map.as_ref().is_some_and(|map| map.temporal);
+        let non_nan = map
+            .filter(|map| map.float && no_nans)
+            .map(|map| map.row_value_ty);
+        // Epoch fields are limited to values that fit in their column, and fields with bounds to
+        // values within them
+        let strategy = match (&opts.epoch, &opts.cast_from) {
+            (Some(_), Some(raw)) => Some(quote!(#rt::arb_epoch::<#inner_ty, #raw>(#unit))),
+            (Some(_), None) => Some(quote!(#rt::arb_epoch::<#inner_ty, i64>(#unit))),
+            _ if opts.category.is_present() => Some(quote!(#rt::arb_category::<#inner_ty>())),
+            _ if temporal => Some(quote!(#rt::arb_naive_datetime(#unit))),
+            _ => match (&bounds, non_nan) {
+                (Some((min, max)), _) => Some(quote!(#min..=#max)),
+                (None, Some(float)) => Some(quote! {
+                    #rt::__proptest::num::#float::NORMAL
+                        | #rt::__proptest::num::#float::ZERO
+                        | #rt::__proptest::num::#float::SUBNORMAL
+                        | #rt::__proptest::num::#float::INFINITE
+                }),
+                (None, None) => None,
+            },
+        };
+        strategies.push(match (strategy, is_option) {
+            (Some(strategy), true) => quote!(#rt::__proptest::option::of(#strategy)),
+            (Some(strategy), false) => strategy,
+            (None, _) => quote!(#rt::__proptest::arbitrary::any::<#ty>()),
+        });
+        conversions.push(if is_option {
+            quote!(#ident: self.#ident.map(::std::convert::Into::into))
+        } else {
+            quote!(#ident: self.#ident.into())
+        });
+        members.push(if cfg!(feature = "serde") {
+            quote!(#[serde(rename = #col_name)] pub #ident: #ty)
+        } else {
+            quote!(pub #ident: #ty)
+        });
+        let sample = quote!(<#ty as #rt::SampleValue>::sample(#col_name, idx, rng));
+        samples.push(match (bounds, is_option) {
+            (Some((min, max)), true) => quote!(#ident: #sample.map(|v| v.clamp(#min, #max))),
+            (Some((min, max)), false) => quote!(#ident: #sample.clamp(#min, #max)),
+            (None, _) => quote!(#ident: #sample),
+        });
+        col_names.push(col_name);
+        idents.push(ident);
+    }
+    let serialize = serialize_impl(&rt, quote!(#owned_name), struct_name, &idents, &col_names);
+    let struct_opts = struct_opts(&input);
+    let mut owned_derives = vec![quote!(Debug), quote!(Clone), quote!(PartialEq)];
+    if struct_opts.derives("Eq") {
+        owned_derives.push(quote!(Eq));
+    }
+    if struct_opts.derives("Hash") {
+        owned_derives.push(quote!(Hash));
+    }
+    let arbitrary = arbitrary_impl(&rt, &owned_name, &idents, &strategies);
+    // Owned rows are deserialized with serde's derive, through the runtime's re-export
+    let deserialize = if cfg!(feature = "serde") {
+        // serde parses the string as a path, so the spacing of the tokens doesn't matter
+        let serde_path = quote!(#rt::__serde).to_string();
+        quote! {
+            #[derive(#rt::__serde::Deserialize)]
+            #[serde(crate = #serde_path)]
+        }
+    } else {
+        quote!()
+    };
+
+    quote! {
+        /// An owned copy of a row, which doesn't borrow the dataframe.
+        #[derive(#(#owned_derives),*)]
+        #deserialize
+        #[allow(dead_code)]
+        pub struct #owned_name {
+            #(#members),*
+        }
+
+        impl<'a> #rowref_name<'a> {
+            /// Copy the row's values into an owned row.
+            pub fn to_owned(&self) -> #owned_name {
+                #owned_name {
+                    #(#conversions),*
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl<'a> #view_name<'a> {
+            /// Copy every row of the view into a vector of owned rows.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first row error that is encountered, see `get`.
+            pub fn to_vec(&self) -> #rt::Result<Vec<#owned_name>> {
+                let mut rows = Vec::with_capacity(self.len());
+                for row in self.iter() {
+                    rows.push(row?.to_owned());
+                }
+                Ok(rows)
+            }
+
+            /// Copy the rows of the view into a vector of owned rows, skipping any rows with null
+            /// values that violate a field's null handling policy.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first row error that isn't a `ColbackError::InvalidNull`.
+            pub fn collect_valid(&self) -> #rt::Result<Vec<#owned_name>> {
+                let mut rows = Vec::with_capacity(self.len());
+                for row in self.iter() {
+                    match row {
+                        Ok(row) => rows.push(row.to_owned()),
+                        Err(#rt::ColbackError::InvalidNull { .. }) => (),
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(rows)
+            }
+        }
+
+        #serialize
+
+        #arbitrary
+
+        impl #rt::FieldDiffs for #owned_name {
+            fn field_diffs(&self, actual: &Self) -> Vec<#rt::FieldDiff> {
+                let mut diffs = Vec::new();
+                #(
+                    if self.#idents != actual.#idents {
+                        diffs.push(#rt::FieldDiff {
+                            field: #col_names,
+                            expected: format!("{:?}", self.#idents),
+                            actual: format!("{:?}", actual.#idents),
+                        });
+                    }
+                )*
+                diffs
+            }
+        }
+
+        impl #rt::ColbackOwned for #struct_name {
+            type Owned = #owned_name;
+
+            fn to_owned_row(row: &Self::RowRef<'_>) -> Self::Owned {
+                row.to_owned()
+            }
+
+            fn sample_row(idx: usize, rng: &mut #rt::SampleRng) -> Self::Owned {
+                #owned_name {
+                    #(#samples),*
+                }
+            }
+        }
+    }
+    .into()
+}
+
+/// Derive a builder that builds a dataframe with the columns of a `ColbackView` struct.
+///
+/// This generates a `<StructName>Builder` struct with a polars chunked array builder for each
+/// column, along with an implementation of `ColbackBuilder`. The struct must also derive
+/// `ColbackOwned`, since owned rows can be pushed onto the builder.
+#[proc_macro_error]
+#[proc_macro_derive(ColbackBuilder, attributes(polars))]
+pub fn derive_colback_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let rt = runtime_path();
+    let struct_name = &input.ident;
+    let owned_name = format_ident!("{}Owned", struct_name);
+    let builder_name = format_ident!("{}Builder", struct_name);
+
+    let fields = match input.data {
+        Data::Struct(ref s) => match s.fields {
+            Fields::Named(ref named) => named.named.iter().collect::<Vec<_>>(),
+            _ => abort!(
+                struct_name,
+                "ColbackBuilder only supports structs with named fields"
+            ),
+        },
+        _ => abort!(
+            struct_name,
+            "ColbackBuilder can only be derived for structs"
+        ),
+    };
+
+    let mut members = Vec::new();
+    let mut inits = Vec::new();
+    let mut params = Vec::new();
+    let mut convert_values = Vec::new();
+    let mut convert_refs = Vec::new();
+    let mut append_values = Vec::new();
+    let mut append_refs = Vec::new();
+    let mut checks_range = false;
+    let mut columns = Vec::new();
+    let mut idents = Vec::new();
+    for f in fields {
+        let opts = ColbackFieldOpts::parse(f);
+        let unit = opts.unit();
+        let ident = opts.ident.clone().unwrap();
+        let col_name = opts.name.clone().unwrap_or_else(|| ident.to_string());
+        // Columns of fields with a `cast_from` type are built with the field's type and widened
+        let (map, col_map) = opts.type_maps(&rt, &ident, &col_name);
+        let ty = opts.ty;
+        let (is_option, _) = option_inner(&ty);
+        let expected_dtype = &col_map.as_ref().unwrap_or(&map).expected_dtype;
+        let builder_ty = map.builder_ty(&rt);
+        // Every value is converted before anything is appended, so that a value that doesn't fit
+        // in its column leaves the builder as it was
+        let value = quote!(#ident);
+        let converted = map.physical_value(&rt, &unit, &col_name, is_option, &value);
+        let borrowed = map.borrow_value(is_option, &ident);
+        let converted_ref = map.physical_value(&rt, &unit, &col_name, is_option, &borrowed);
+        let builder = quote!(self.#ident);
+        checks_range |= map.checks_range();
+        if map.converts() {
+            convert_values.push(quote!(let #ident = #converted;));
+            convert_refs.push(quote!(let #ident = #converted_ref;));
+            append_values.push(map.append(is_option, &builder, &value));
+            append_refs.push(map.append(is_option, &builder, &value));
+        } else {
+            append_values.push(map.append(is_option, &builder, &value));
+            append_refs.push(map.append(is_option, &builder, &borrowed));
+        }
+
+        members.push(quote!(#ident: #builder_ty));
+        inits.push(quote!(#ident: <#builder_ty>::new(#col_name.into(), capacity)));
+        params.push(quote!(#ident: #ty));
+        columns.push(quote! {
+            ::polars::prelude::IntoSeries::into_series(self.#ident.finish())
+                .cast(&#expected_dtype)?
+                .into()
+        });
+        idents.push(ident);
+    }
+    // Values that don't fit in their column are reported with the index of their row
+    let row_idx = checks_range.then(|| quote!(let idx = self.height;));
+
+    quote! {
+        /// Builds a dataframe with the struct's columns, one row at a time.
+        #[allow(dead_code)]
+        pub struct #builder_name {
+            #(#members,)*
+            height: usize,
+        }
+
+        #[allow(dead_code, unused_imports)]
+        impl #builder_name {
+            /// Create a builder with room for `capacity` rows in every column.
+            pub fn with_capacity(capacity: usize) -> Self {
+                Self {
+                    #(#inits,)*
+                    height: 0,
+                }
+            }
+
+            /// Append an owned row.
+            ///
+            /// # Errors
+            ///
+            /// This errors with [ValueOutOfRange](#rt::ColbackError::ValueOutOfRange) if a
+            /// datetime or `epoch` value doesn't fit in its column, in which case nothing is
+            /// appended.
+            pub fn push(&mut self, row: &#owned_name) -> #rt::Result<()> {
+                use ::polars::prelude::ChunkedBuilder as _;
+                #row_idx
+                let #owned_name { #(#idents),* } = row;
+                #(#convert_refs)*
+                #(#append_refs;)*
+                self.height += 1;
+                Ok(())
+            }
+
+            /// Append a row from the values of each of its fields, in field order.
+            ///
+            /// # Errors
+            ///
+            /// This errors under the same conditions as [push](Self::push).
+            #[allow(clippy::too_many_arguments)]
+            pub fn push_fields(&mut self, #(#params),*) -> #rt::Result<()> {
+                use ::polars::prelude::ChunkedBuilder as _;
+                #row_idx
+                #(#convert_values)*
+                #(#append_values;)*
+                self.height += 1;
+                Ok(())
+            }
+
+            /// Append `n` rows where every column is null.
+            pub fn append_nulls(&mut self, n: usize) {
+                use ::polars::prelude::ChunkedBuilder as _;
+                for _ in 0..n {
+                    #(self.#idents.append_null();)*
+                }
+                self.height += n;
+            }
+
+            /// Build a dataframe from the appended rows.
+            ///
+            /// The columns have the dtypes that the struct's view expects.
+            ///
+            /// # Errors
+            ///
+            /// This errors if a column can't be cast to the dtype that the struct expects.
+            pub fn finish(self) -> #rt::Result<::polars::prelude::DataFrame> {
+                use ::polars::prelude::ChunkedBuilder as _;
+                Ok(::polars::prelude::DataFrame::new(vec![#(#columns),*])?)
+            }
+        }
+
+        impl #rt::ColbackBuilder for #struct_name {
+            type Builder = #builder_name;
+
+            fn builder(capacity: usize) -> Self::Builder {
+                #builder_name::with_capacity(capacity)
+            }
+
+            fn push_row(builder: &mut Self::Builder, row: &Self::Owned) -> #rt::Result<()> {
+                builder.push(row)
+            }
+
+            fn finish_builder(
+                builder: Self::Builder,
+            ) -> #rt::Result<::polars::prelude::DataFrame> {
+                builder.finish()
+            }
+        }
+    }
+    .into()
+}
+
+/// Derive the schema of a struct without generating a view for it.
+///
+/// This generates an implementation of `ColbackSchema` from the same field attributes that
+/// `ColbackView` reads, for crates that only need schema metadata and would rather not compile the
+/// view, rows, and builders.
+#[proc_macro_error]
+#[proc_macro_derive(ColbackSchema, attributes(polars))]
+pub fn derive_colback_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let rt = runtime_path();
+    let struct_name = &input.ident;
+
+    let fields = match input.data {
+        Data::Struct(ref s) => match s.fields {
+            Fields::Named(ref named) => named.named.iter().collect::<Vec<_>>(),
+            _ => abort!(
+                struct_name,
+                "ColbackSchema only supports structs with named fields"
+            ),
+        },
+        _ => abort!(struct_name, "ColbackSchema can only be derived for structs"),
+    };
+
+    let parsed = fields
+        .into_iter()
+        .map(ColbackFieldOpts::parse)
+        .collect::<Vec<_>>();
+    schema_impl(&rt, struct_name, &parsed).into()
+}
+
+/// Generate the `ColbackSchema` impl of a struct from its parsed fields.
+///
+/// This is shared by the `ColbackSchema` and `ColbackView` derives, so that a struct has the same
+/// schema whichever of them it derives.
+fn schema_impl(
+    rt: &proc_macro2::TokenStream,
+    struct_name: &syn::Ident,
+    fields: &[ColbackFieldOpts],
+) -> proc_macro2::TokenStream {
+    let mut col_names = Vec::new();
+    let mut dtypes = Vec::new();
+    let mut nullable_cols = Vec::new();
+    for opts in fields {
+        let ident = opts.ident.clone().unwrap();
+        let col_name = opts.name.clone().unwrap_or_else(|| ident.to_string());
+        let (map, col_map) = opts.type_maps(rt, &ident, &col_name);
+        opts.check_temporal(map.temporal);
+        dtypes.push(col_map.unwrap_or(map).expected_dtype);
+        if opts.policy() != "error" {
+            nullable_cols.push(col_name.clone());
+        }
+        col_names.push(col_name);
+    }
+    let n_fields = col_names.len();
+
+    quote! {
+        impl #rt::ColbackSchema for #struct_name {
+            fn column_names() -> &'static [&'static str] {
+                &[#(#col_names),*]
+            }
+
+            fn schema() -> ::polars::prelude::Schema {
+                let mut schema = ::polars::prelude::Schema::with_capacity(#n_fields);
+                #(schema.insert(#col_names.into(), #dtypes);)*
+                schema
+            }
+
+            fn nullable_columns() -> &'static [&'static str] {
+                &[#(#nullable_cols),*]
+            }
+        }
+    }
+}
+
+/// Variant attributes of an enum deriving `ColbackCategory`.
+#[derive(Debug, FromVariant)]
+#[darling(attributes(polars))]
+struct CategoryVariantOpts {
+    ident: syn::Ident,
+    fields: darling::ast::Fields<()>,
+
+    /// The name of the variant's category.
+    ///
+    /// If this is *not* supplied then the name will be set to the name of the variant.
+    #[darling(default)]
+    name: Option<String>,
+}
+
+/// Derive the categories of a fieldless enum, so that fields of the enum can be read from a
+/// string column with the `category` field attribute.
+///
+/// This generates an implementation of `ColbackCategory`, along with the traits that rows and
+/// filters need from field types, and with the `serde` feature, serialization as category names.
+/// The enum must also derive `Clone`, `Copy`, `Debug` and `PartialEq`.
+#[proc_macro_error]
+#[proc_macro_derive(ColbackCategory, attributes(polars))]
+pub fn derive_colback_category(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let rt = runtime_path();
+    let enum_name = &input.ident;
+
+    let variants = match input.data {
+        Data::Enum(ref e) => e.variants.iter().collect::<Vec<_>>(),
+        _ => abort!(enum_name, "ColbackCategory can only be derived for enums"),
+    };
+    if variants.is_empty() {
+        abort!(enum_name, "ColbackCategory requires at least one variant");
+    }
+
+    let mut idents = Vec::new();
+    let mut names = Vec::new();
+    for v in variants {
+        let opts = match CategoryVariantOpts::from_variant(v) {
+            Ok(v) => v,
+            Err(e) => abort!(e.span(), "invalid #[polars(...)] on variant: {}", e),
+        };
+        if !opts.fields.is_empty() {
+            abort!(v, "ColbackCategory only supports variants without fields");
+        }
+        names.push(opts.name.unwrap_or_else(|| opts.ident.to_string()));
+        idents.push(opts.ident);
+    }
+    if let Some((idx, name)) = names
+        .iter()
+        .enumerate()
+        .find(|(idx, name)| names[..*idx].contains(name))
+    {
+        abort!(
+            idents[idx],
+            "category {:?} is used by more than one variant",
+            name
+        );
+    }
+    let serde = category_serde_impl(&rt, enum_name);
+
+    quote! {
+        impl #rt::ColbackCategory for #enum_name {
+            const VARIANTS: &'static [Self] = &[#(Self::#idents),*];
+
+            fn category(&self) -> &'static str {
+                match self {
+                    #(Self::#idents => #names),*
+                }
+            }
+
+            fn from_category(name: &str) -> Option<Self> {
+                match name {
+                    #(#names => Some(Self::#idents),)*
+                    _ => None,
+                }
+            }
+        }
+
+        // Categories are hashed, sampled and compared as their names, like string fields
+        impl #rt::ContentHash for #enum_name {
+            fn content_hash(&self, hasher: &mut #rt::ContentHasher) {
+                #rt::ContentHash::content_hash(#rt::ColbackCategory::category(self), hasher)
+            }
+        }
+
+        impl #rt::SampleValue for #enum_name {
+            fn sample(_: &str, idx: usize, _: &mut #rt::SampleRng) -> Self {
+                let variants = <Self as #rt::ColbackCategory>::VARIANTS;
+                variants[idx % variants.len()]
+            }
+        }
+
+        impl #rt::FilterValue for #enum_name {
+            type Arg<'v> = Self;
+
+            fn literal(
+                value: Self::Arg<'_>,
+                _dtype: &::polars::prelude::DataType,
+            ) -> ::polars::prelude::Expr {
+                ::polars::prelude::lit(#rt::ColbackCategory::category(&value))
+            }
+        }
+
+        #serde
+    }
+    .into()
+}
+
+/// Field attributes binding a context field to a named dataframe.
+#[derive(Debug, FromField)]
+#[darling(attributes(polars))]
+struct ContextFieldOpts {
+    ident: Option<syn::Ident>,
+    ty: syn::Type,
+
+    /// The name of the dataframe backing the field.
+    ///
+    /// If this is *not* supplied then the name will be set to the name of the struct field.
+    #[darling(default)]
+    name: Option<String>,
+}
+
+/// Derive a context struct whose fields are `ColbackView` structs, each viewing a named
+/// dataframe.
+///
+/// This generates a `<StructName>View<'a>` struct with a view for each field, along with an
+/// implementation of `ColbackContext`.
+#[proc_macro_error]
+#[proc_macro_derive(ColbackContext, attributes(polars))]
+pub fn derive_colback_context(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let rt = runtime_path();
+    let struct_name = &input.ident;
+    let view_name = format_ident!("{}View", struct_name);
+
+    let fields = match input.data {
+        Data::Struct(ref s) => match s.fields {
+            Fields::Named(ref named) => named.named.iter().collect::<Vec<_>>(),
+            _ => abort!(
+                struct_name,
+                "ColbackContext only supports structs with named fields"
+            ),
+        },
+        _ => abort!(
+            struct_name,
+            "ColbackContext can only be derived for structs"
+        ),
+    };
+
+    let mut idents = Vec::new();
+    let mut tys = Vec::new();
+    let mut frame_names = Vec::new();
+    for f in fields {
+        let opts = match ContextFieldOpts::from_field(f) {
+            Ok(v) => v,
+            Err(e) => abort!(struct_name, "invalid #[polars(...)] on field: {}", e),
+        };
+        let ident = opts.ident.unwrap();
+        frame_names.push(opts.name.unwrap_or_else(|| ident.unraw().to_string()));
+        idents.push(ident);
+        tys.push(opts.ty);
+    }
+
+    quote! {
+        pub struct #view_name<'a> {
+            #(pub #idents: <#tys as #rt::ColbackView>::View<'a>),*
+        }
+
+        impl #rt::ColbackContext for #struct_name {
+            type View<'a> = #view_name<'a>;
+
+            fn frame_names() -> &'static [&'static str] {
+                &[#(#frame_names),*]
+            }
+
+            fn view<K>(
+                frames: &::std::collections::HashMap<K, ::polars::prelude::DataFrame>,
+            ) -> #rt::Result<Self::View<'_>>
+            where
+                K: ::std::borrow::Borrow<str> + ::std::cmp::Eq + ::std::hash::Hash,
+            {
+                let mut errors = Vec::new();
+                #(
+                    let #idents = #rt::view_frame::<#tys, K>(frames, #frame_names, &mut errors);
+                )*
+                if !errors.is_empty() {
+                    return Err(#rt::ColbackError::InvalidContext(errors));
+                }
+                Ok(#view_name {
+                    #(#idents: #idents.expect("errors checked above")),*
+                })
+            }
+        }
+    }
+    .into()
 }
 
-#[proc_macro_error]
-#[proc_macro_derive(ColbackView, attributes(polars))]
-pub fn derive_colback_view(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
+/// Generate the view and row reference types for a struct, along with its `ColbackView` impl.
+fn expand_view(input: &DeriveInput) -> proc_macro2::TokenStream {
     let rt = runtime_path();
-    let struct_name = input.ident;
+    let struct_name = &input.ident;
 
     let fields = match input.data {
         Data::Struct(ref s) => match s.fields {
@@ -73,166 +1682,1290 @@ pub fn derive_colback_view(input: TokenStream) -> TokenStream {
 
     let mut parsed = Vec::new();
     for f in fields {
-        let opts = match ColbackFieldOpts::from_field(f) {
-            Ok(v) => v,
-            Err(e) => abort!(struct_name, "invalid #[polars(...)] on field: {}", e),
-        };
-
-        let ident = opts.ident.clone().unwrap();
-        let col_name = opts.name.clone().unwrap_or_else(|| ident.to_string());
-
-        parsed.push((
-            ident,
-            opts.ty.clone(),
-            col_name,
-            opts.null.clone(),
-            opts.default.clone(),
-        ));
+        parsed.push(ColbackFieldOpts::parse(f));
     }
 
     // Generated types: <StructName>View<'a> and <StructName>RowRef<'a>
     let view_name = format_ident!("{}View", struct_name);
     let rowref_name = format_ident!("{}RowRef", struct_name);
+    let field_enum_name = format_ident!("{}Field", struct_name);
+    let filter_name = format_ident!("{}Filter", struct_name);
+    let cols_name = format_ident!("{}Col", struct_name);
+    let columns_name = format_ident!("{}Columns", struct_name);
+    let view_mut_name = format_ident!("{}ViewMut", struct_name);
+    let raw_name = format_ident!("{}Raw", struct_name);
 
     // For each field, generate:
-    // - a member in View<'a> holding a typed ChunkedArray reference
+    // - a member in View<'a> holding a typed ChunkedArray reference and its chunk layout
     // - validation + extraction in try_new
     // - row materialization in get_row (using get(idx))
     let mut view_members = Vec::new();
+    let mut raw_members = Vec::new();
     let mut extract_stmts = Vec::new();
     let mut row_members = Vec::new();
     let mut row_build = Vec::new();
+    // Checks of whether a row is skipped, for fields with the "skip" policy
+    let mut skip_checks = Vec::new();
     let mut view_ctor_idents: Vec<syn::Ident> = Vec::new();
     let mut row_ctor_idents: Vec<syn::Ident> = Vec::new();
+    let mut from_row = Vec::new();
+    let mut any_values = Vec::new();
+    let mut col_names: Vec<String> = Vec::new();
+    let mut field_variants: Vec<syn::Ident> = Vec::new();
+    let mut expr_fns = Vec::new();
+    let mut filter_fns = Vec::new();
+    let mut column_members = Vec::new();
+    let mut column_idents: Vec<syn::Ident> = Vec::new();
+    let mut column_series = Vec::new();
+    let mut edit_tys = Vec::new();
+    let mut required_cols = Vec::new();
+    let mut filled_cols = Vec::new();
+    let mut setters = Vec::new();
+    let mut commits = Vec::new();
+    let mut numeric_fields = Vec::new();
+    let mut other_fields = Vec::new();
+    let mut float_tys = Vec::new();
+    let mut constraints = Vec::new();
+    for check in &struct_opts(input).check {
+        let label = expr_label(quote!(#check));
+        constraints.push(quote! {
+            constraints.push((#label.to_string(), #check));
+        });
+    }
+
+    let schema = schema_impl(&rt, struct_name, &parsed);
+    for opts in parsed {
+        let ident = opts.ident.clone().unwrap();
+        let col_name = opts.name.clone().unwrap_or_else(|| ident.to_string());
+        let default_expr = opts.default.clone();
+        let policy = opts.policy();
 
-    for (ident, ty, col_name, null_policy, default_expr) in parsed {
         // Detect Option<T>
-        let (is_option, inner_ty) = option_inner(&ty);
+        let (is_option, inner_ty) = option_inner(&opts.ty);
+
+        let unit = opts.unit();
+        let zone = opts.zone(&rt);
 
         // Map Rust type to:
         // - Polars DataType for validation
         // - Series accessor (u32(), i64(), f64(), bool(), str())
-        // - ChunkedArray type in View
+        // - Polars data type of the ChunkedArray in View
         // - row getter expression
-        let Some(map) = map_type(&ident, &inner_ty) else {
-            abort!(
-                ident,
-                "unsupported field type for ColbackView; add a mapping for this type"
-            );
+        let (map, col_map) = opts.type_maps(&rt, &ident, &col_name);
+        opts.check_temporal(map.temporal);
+        if let Some(nan) = &opts.nan
+            && !map.float
+        {
+            abort!(nan.span(), "nan is only supported for f32 and f64 fields");
+        }
+        let col_map = col_map.as_ref().unwrap_or(&map);
+        // Dynamic values keep the column's dtype, which for datetimes includes the time unit
+        let narrowed_from = opts.cast_from.as_ref().filter(|_| opts.narrows());
+        any_values.push(match (map.temporal, is_option, narrowed_from) {
+            _ if map.category => {
+                let value = map.physical_value(&rt, &unit, &col_name, is_option, &quote!(self.#ident));
+                quote!(::polars::prelude::AnyValue::from(#value))
+            }
+            _ if map.epoch.is_some() => {
+                let value = map.epoch_value(&rt, &unit, is_option, &quote!(self.#ident));
+                quote!(::polars::prelude::AnyValue::from(#value))
+            }
+            _ if map.list.is_some() => {
+                let elem_ty = &map.list;
+                let value = if is_option { quote!(self.#ident) } else { quote!(Some(self.#ident)) };
+                quote!(#rt::list_any_value::<#elem_ty>(#value))
+            }
+            (true, true, _) => quote!(#rt::datetime_any_value(self.#ident, #unit, #zone)),
+            (true, false, _) => quote!(#rt::datetime_any_value(Some(self.#ident), #unit, #zone)),
+            (false, true, Some(src)) => quote! {
+                ::polars::prelude::AnyValue::from(self.#ident.map(<#src as ::std::convert::From<_>>::from))
+            },
+            (false, false, Some(src)) => quote! {
+                ::polars::prelude::AnyValue::from(<#src as ::std::convert::From<_>>::from(self.#ident))
+            },
+            (false, _, None) => quote!(::polars::prelude::AnyValue::from(self.#ident)),
+        });
+        let builder_ty = col_map.builder_ty(&rt);
+        let edit_value = map.borrow_value(is_option, &format_ident!("value"));
+        let edit_value = match (opts.narrows(), is_option) {
+            (true, true) => quote!((#edit_value).map(::std::convert::Into::into)),
+            (true, false) => quote!(::std::convert::Into::into(#edit_value)),
+            (false, _) => edit_value,
         };
+        let edit_value = col_map.physical_value(&rt, &unit, &col_name, is_option, &edit_value);
+        let append_edit = col_map.append(is_option, &quote!(builder), &edit_value);
 
-        let view_field_ty = map.chunked_ty;
-        let expected_dtype = map.expected_dtype;
-        let accessor = map.accessor;
-        let row_value_ty = map.row_value_ty;
-        let get_value = map.get_value_expr;
-
-        let policy = null_policy.as_deref().unwrap_or("error");
-        match (policy, is_option, &default_expr) {
-            ("option", false, _) => abort!(
-                ident,
-                "null=\"option\" requires the field type to be Option<T>"
-            ),
-            ("error", true, _) => abort!(ident, "Option<T> fields must use null='option'"),
-            ("default", _, None) => abort!(
-                ident,
-                "null='default' requires #[polars(default = ...)] to be set"
-            ),
-            _ => (),
-        }
+        let polars_ty = col_map.polars_ty.clone();
+        let expected_dtype = col_map.expected_dtype.clone();
+        let extract_chunked = col_map.extract_chunked.clone();
+        let row_value_ty = map.row_value_ty.clone();
+        let get_value = col_map.get_value_expr.clone();
+        let get_value = match opts.overflow.as_ref().map(|overflow| overflow.as_str()) {
+            _ if !opts.narrows() => get_value,
+            Some("saturate") => quote! {
+                #get_value.map(|v| {
+                    <#row_value_ty as ::std::convert::TryFrom<_>>::try_from(v).unwrap_or(
+                        if v > 0 { <#row_value_ty>::MAX } else { <#row_value_ty>::MIN },
+                    )
+                })
+            },
+            _ => quote! {
+                match #get_value {
+                    Some(v) => Some(
+                        <#row_value_ty as ::std::convert::TryFrom<_>>::try_from(v).map_err(|_| {
+                            #rt::ColbackError::ValueOutOfRange {
+                                col: #col_name.to_string(),
+                                idx,
+                                value: v.to_string(),
+                            }
+                        })?,
+                    ),
+                    None => None,
+                }
+            },
+        };
+        // NaNs treated as nulls are dropped from lookups, so they go through the null policy
+        let get_value = match opts.nan_policy() {
+            Some("null") => quote!(#get_value.filter(|v| !v.is_nan())),
+            _ => get_value,
+        };
 
-        // View member
+        // View member. Category fields read string, categorical and enum columns, so they index
+        // the column's category names rather than its chunks of one type.
+        let index_ty = if map.category {
+            quote!(#rt::CategoryIndex<'a>)
+        } else {
+            quote!(#rt::ChunkIndex<'a, #polars_ty>)
+        };
         view_members.push(quote! {
-            #ident: &'a #view_field_ty
+            #ident: #index_ty
+        });
+        let raw_doc = format!("The chunks of the `{col_name}` column.");
+        raw_members.push(quote! {
+            #[doc = #raw_doc]
+            pub #ident: &'v #index_ty
         });
 
-        let col_var_name = format_ident!("{}_col", col_name);
+        let col_var_name = format_ident!("{}_col", ident);
 
-        // Extraction + dtype check
-        // TODO: allow type casting here, with warnings
-        extract_stmts.push(quote! {
-            let #col_var_name = df.column(#col_name)
-                .map_err(|_| #rt::ColbackError::MissingColumn(#col_name.to_string()))?;
-            if #col_var_name.dtype() != &#expected_dtype {
-                return Err(#rt::ColbackError::WrongDtype {
-                    col: #col_name.to_string(),
-                    expected: #expected_dtype.clone(),
-                    actual: #col_var_name.dtype().clone(),
-                });
+        // Extraction + dtype check. A column that falls back to the default when it's missing is
+        // swapped for an empty column, so every lookup into it is null.
+        let missing_ident = format_ident!("{}_missing", ident);
+        let lookup = if opts.default_if_missing() {
+            view_members.push(quote!(#missing_ident: bool));
+            view_ctor_idents.push(missing_ident.clone());
+            quote! {
+                let #missing_ident = df.column(#col_name).is_err();
+                let #col_var_name = match df.column(#col_name) {
+                    Ok(col) => col,
+                    Err(_) => {
+                        static EMPTY: ::std::sync::LazyLock<::polars::prelude::Column> =
+                            ::std::sync::LazyLock::new(|| {
+                                ::polars::prelude::Column::new_empty(#col_name.into(), &#expected_dtype)
+                            });
+                        &*EMPTY
+                    }
+                };
+            }
+        } else {
+            quote! {
+                let #col_var_name = df.column(#col_name)
+                    .map_err(|_| #rt::ColbackError::MissingColumn(#col_name.to_string()))?;
+            }
+        };
+        let wrong_dtype = quote! {
+            return Err(#rt::ColbackError::WrongDtype {
+                col: #col_name.to_string(),
+                expected: #expected_dtype.clone(),
+                actual: #col_var_name.dtype().clone(),
+            })
+        };
+        // Datetimes that only differ by their time zone get a more specific error, or are
+        // converted to the field's time zone when rows are read
+        let check_dtype = if map.temporal {
+            let convert = opts.convert_time_zone.is_present();
+            let tz_ident = time_zone_ident(&ident);
+            view_members.push(quote!(#tz_ident: #rt::TimeZoneConversion));
+            view_ctor_idents.push(tz_ident.clone());
+            quote! {
+                let Some(#tz_ident) = #rt::check_time_zone(
+                    #col_name,
+                    #col_var_name.dtype(),
+                    &#expected_dtype,
+                    #convert,
+                )? else {
+                    #wrong_dtype
+                };
+            }
+        } else if map.category {
+            // Category columns are checked by indexing them, see below
+            quote!()
+        } else {
+            quote! {
+                if #col_var_name.dtype() != &#expected_dtype {
+                    #wrong_dtype
+                }
+            }
+        };
+        let index = if map.category {
+            quote! {
+                let Some(#ident) = #rt::CategoryIndex::new(#col_var_name) else {
+                    #wrong_dtype
+                };
             }
-            let #ident = #col_var_name.#accessor().expect("dtype checked above");
+        } else {
+            quote!(let #ident = #rt::ChunkIndex::new(#col_var_name #extract_chunked);)
+        };
+        extract_stmts.push(quote! {
+            #lookup
+            #check_dtype
+            #index
         });
 
+        // Check the default against the field's type at the attribute, so that a mismatch isn't
+        // reported inside the generated row
+        let def = default_expr.map(
+            |def| quote_spanned!(def.span()=> ::std::convert::identity::<#row_value_ty>(#def)),
+        );
+
         // RowRef member type (borrowed)
-        if is_option {
-            row_members.push(quote! { pub #ident: Option<#row_value_ty> });
-            row_build.push(quote! {
-                let #ident = #get_value;
-            });
+        row_members.push(if is_option {
+            quote! { pub #ident: Option<#row_value_ty> }
+        } else {
+            quote! { pub #ident: #row_value_ty }
+        });
+        let value = if is_option {
+            get_value
         } else if policy == "default" {
-            let def = default_expr.unwrap();
-            row_members.push(quote! { pub #ident: #row_value_ty });
-            row_build.push(quote! {
-                let #ident = match #get_value {
+            quote! {
+                match #get_value {
                     Some(v) => v,
                     None => #def,
-                };
+                }
+            }
+        } else if let Some(fill) = &opts.fill {
+            let fill_ident = format_ident!("{}_fill", ident);
+            let invalid_null =
+                quote!(#rt::ColbackError::InvalidNull { col: #col_name.to_string(), idx });
+            // Forward and backward fills map each row to the row whose value it takes, so values
+            // are still borrowed from the dataframe
+            if matches!(fill.as_str(), "forward" | "backward" | "mean") {
+                filled_cols.push(col_name.clone());
+            }
+            let (member, init, value) = match fill.as_str() {
+                "forward" | "backward" => {
+                    let forward = fill.as_str() == "forward";
+                    (
+                        Some(quote!(::std::sync::Arc<[::std::option::Option<usize>]>)),
+                        quote! {
+                            ::std::sync::Arc::<[::std::option::Option<usize>]>::from(
+                                #rt::fill_indices(#col_var_name, #forward),
+                            )
+                        },
+                        quote! {
+                            match self.#fill_ident[idx] {
+                                Some(idx) => #get_value,
+                                None => None,
+                            }
+                            .ok_or_else(|| #invalid_null)?
+                        },
+                    )
+                }
+                "mean" => {
+                    if !map.numeric() {
+                        abort!(
+                            fill.span(),
+                            "fill=\"mean\" is only supported for numeric fields"
+                        );
+                    }
+                    // The mean of an integer column is rounded rather than truncated
+                    let mean = if map.float {
+                        quote!(m as #row_value_ty)
+                    } else {
+                        quote!(m.round() as #row_value_ty)
+                    };
+                    (
+                        Some(quote!(::std::option::Option<#row_value_ty>)),
+                        quote!(#col_var_name.as_materialized_series().mean().map(|m| #mean)),
+                        quote! {
+                            match #get_value {
+                                Some(v) => v,
+                                None => self.#fill_ident.ok_or_else(|| #invalid_null)?,
+                            }
+                        },
+                    )
+                }
+                _ => (None, quote!(), quote!(#get_value.unwrap_or_default())),
+            };
+            if let Some(member) = member {
+                view_members.push(quote!(#fill_ident: #member));
+                extract_stmts.push(quote!(let #fill_ident = #init;));
+                view_ctor_idents.push(fill_ident);
+            }
+            value
+        } else if policy == "skip" {
+            // Rows are checked for skipped values without building them, so this only looks at
+            // whether the column's value is null, or a NaN treated as null
+            let lookup = match opts.nan_policy() {
+                Some("null") => quote!(self.#ident.get(idx).filter(|v| !v.is_nan())),
+                _ => quote!(self.#ident.get(idx)),
+            };
+            let present = if opts.default_if_missing() {
+                quote!(self.#missing_ident || #lookup.is_some())
+            } else {
+                quote!(#lookup.is_some())
+            };
+            skip_checks.push(quote! {
+                if !(#present) {
+                    return Some(#col_name);
+                }
             });
+            quote!(#get_value.ok_or_else(|| #rt::ColbackError::RowSkipped { col: #col_name.to_string(), idx })?)
         } else {
             // error on null
-            row_members.push(quote! { pub #ident: #row_value_ty });
-            row_build.push(quote! {
-                let #ident = #get_value.ok_or_else(|| #rt::ColbackError::InvalidNull{ col: #col_name.to_string(), idx })?;
+            quote!(#get_value.ok_or_else(|| #rt::ColbackError::InvalidNull{ col: #col_name.to_string(), idx })?)
+        };
+        // Unknown categories are skipped independently of the null policy
+        if opts.skips_unknown() {
+            skip_checks.push(quote! {
+                if self.#ident.get(idx).is_some_and(|v| {
+                    <#inner_ty as #rt::ColbackCategory>::from_category(v).is_none()
+                }) {
+                    return Some(#col_name);
+                }
             });
         }
+        // Values that don't come from a lookup, i.e. replaced NaNs and missing columns
+        let (nan_pat, def_value) = if is_option {
+            (quote!(Some(v)), quote!(Some(#def)))
+        } else {
+            (quote!(v), quote!(#def))
+        };
+        let value = match opts.nan_policy() {
+            Some("error") => quote! {
+                match #value {
+                    #nan_pat if v.is_nan() => {
+                        return Err(#rt::ColbackError::InvalidNan { col: #col_name.to_string(), idx });
+                    }
+                    v => v,
+                }
+            },
+            Some("default") => quote! {
+                match #value {
+                    #nan_pat if v.is_nan() => #def_value,
+                    v => v,
+                }
+            },
+            _ => value,
+        };
+        let value = if opts.default_if_missing() {
+            quote!(if self.#missing_ident { #def_value } else { #value })
+        } else {
+            value
+        };
+        row_build.push(quote! {
+            let #ident = #value;
+        });
+        // Column buffer for collecting owned rows into a dataframe
+        let field_ty = &opts.ty;
+        column_members.push(quote! {
+            #ident: Vec<#field_ty>
+        });
+        column_idents.push(ident.clone());
+        // Datetime and epoch fields are collected as the integers of their column, and category
+        // fields as their names
+        let value = map.physical_value(&rt, &unit, &col_name, is_option, &quote!(v));
+        // Lists are built with the list builder, since series can't be created from nested vectors
+        let values = if map.list.is_some() {
+            let append = map.append(is_option, &quote!(builder), &quote!(v));
+            quote! {{
+                let mut builder = <#builder_ty>::new(#col_name.into(), self.#ident.len());
+                for v in self.#ident {
+                    #append;
+                }
+                ::polars::prelude::IntoSeries::into_series(builder.finish())
+            }}
+        } else if map.checks_range() {
+            quote! {{
+                let mut values = Vec::with_capacity(self.#ident.len());
+                for (idx, v) in self.#ident.into_iter().enumerate() {
+                    values.push(#value);
+                }
+                values
+            }}
+        } else if map.converts() {
+            quote!(self.#ident.into_iter().map(|v| #value).collect::<Vec<_>>())
+        } else {
+            quote!(self.#ident)
+        };
+        column_series.push(quote! {
+            <::polars::prelude::Series as ::polars::prelude::NamedFrom<_, _>>::new(
+                #col_name.into(),
+                #values,
+            )
+            .cast(&#expected_dtype)?
+            .into()
+        });
+
+        // Staged edits for the mutable view, which rebuilds edited columns when committed
+        let edit_idx = syn::Index::from(edit_tys.len());
+        edit_tys.push(field_ty.clone());
+        let setter = format_ident!("set_{}", ident.unraw());
+        let setter_doc = format!("Stage a new value for the `{col_name}` column at a row.");
+        setters.push(quote! {
+            #[doc = #setter_doc]
+            pub fn #setter(&mut self, idx: usize, value: #field_ty) -> #rt::Result<()> {
+                if idx >= self.view.len() {
+                    return Err(#rt::ColbackError::IndexOutOfBounds { idx, len: self.view.len() });
+                }
+                self.edits.#edit_idx.insert(idx, value);
+                Ok(())
+            }
+        });
+        // The column is rebuilt with the expected dtype, so values that aren't edited are
+        // converted to the field's time zone as well
+        let original = if map.temporal {
+            let tz_ident = time_zone_ident(&ident);
+            quote! {
+                match self.view.#ident.get(idx) {
+                    Some(v) => Some(self.view.#tz_ident.apply_physical(v, #unit).ok_or_else(|| {
+                        #rt::ColbackError::ValueOutOfRange {
+                            col: #col_name.to_string(),
+                            idx,
+                            value: v.to_string(),
+                        }
+                    })?),
+                    None => None,
+                }
+            }
+        } else if let Some(elem_ty) = &map.list {
+            quote! {
+                match self.view.#ident.get_slice::<#elem_ty>(idx) {
+                    Some(Some(v)) => Some(v),
+                    Some(None) => {
+                        return Err(#rt::ColbackError::InvalidNull { col: #col_name.to_string(), idx });
+                    }
+                    None => None,
+                }
+            }
+        } else {
+            quote!(self.view.#ident.get(idx))
+        };
+        commits.push(quote! {
+            if !self.edits.#edit_idx.is_empty() {
+                let mut builder = <#builder_ty>::new(#col_name.into(), len);
+                for idx in 0..len {
+                    match self.edits.#edit_idx.get(&idx) {
+                        Some(value) => #append_edit,
+                        None => builder.append_option(#original),
+                    }
+                }
+                let series = ::polars::prelude::IntoSeries::into_series(builder.finish())
+                    .cast(&#expected_dtype)?;
+                df.with_column(series)?;
+            }
+        });
+
         view_ctor_idents.push(ident.clone());
         row_ctor_idents.push(ident.clone());
+        // Row values convert into the declared field types, allocating strings
+        from_row.push(if is_option {
+            quote!(#ident: row.#ident.map(::std::convert::Into::into))
+        } else {
+            quote!(#ident: row.#ident.into())
+        });
+        // Integer and float fields are packed into matrices as f64, with nulls as NaN
+        if map.numeric() {
+            let value = if is_option {
+                quote!(row.#ident.map_or(f64::NAN, |v| v as f64))
+            } else {
+                quote!(row.#ident as f64)
+            };
+            numeric_fields.push((variant_ident(&ident), value));
+        } else {
+            other_fields.push(variant_ident(&ident));
+        }
+        float_tys.push((map.float && !is_option).then(|| map.row_value_ty.to_string()));
+        // Bounds are only checked on columns that are present, see `missing`
+        for (bound, op, method) in [
+            (&opts.min, ">=", quote!(gt_eq)),
+            (&opts.max, "<=", quote!(lt_eq)),
+        ] {
+            let Some(bound) = bound else {
+                continue;
+            };
+            if !map.numeric() {
+                abort!(bound, "min and max are only supported for numeric fields");
+            }
+            let label = format!("{col_name} {op} {}", expr_label(quote!(#bound)));
+            let bound = bound_value(&inner_ty, bound);
+            constraints.push(quote! {
+                if df.column(#col_name).is_ok() {
+                    constraints.push((
+                        #label.to_string(),
+                        ::polars::prelude::col(#col_name).#method(::polars::prelude::lit(#bound)),
+                    ));
+                }
+            });
+        }
+        field_variants.push(variant_ident(&ident));
+        let expr_doc = format!("A column expression for the `{col_name}` column.");
+        expr_fns.push(quote! {
+            #[doc = #expr_doc]
+            pub fn #ident() -> ::polars::prelude::Expr {
+                ::polars::prelude::col(#col_name)
+            }
+        });
+        // Epoch fields are compared against the integers of their column, while lists can't be
+        // compared against a value
+        let filter_ty = map.epoch.clone().unwrap_or_else(|| quote!(#inner_ty));
+        let filter_doc = format!("Typed comparisons against the `{col_name}` column.");
+        if map.list.is_none() {
+            filter_fns.push(quote! {
+                #[doc = #filter_doc]
+                pub fn #ident() -> #rt::FilterColumn<#filter_ty> {
+                    #rt::FilterColumn::new(#col_name, #expected_dtype)
+                }
+            });
+        }
+        if !opts.default_if_missing() {
+            required_cols.push(col_name.clone());
+        }
+        col_names.push(col_name);
+    }
+
+    let view_label = view_name.to_string();
+    let struct_opts = struct_opts(input);
+    let mut row_traits = quote!();
+    if struct_opts.derives("PartialEq") {
+        let eq = row_ctor_idents
+            .iter()
+            .map(|ident| quote!(self.#ident == other.#ident))
+            .reduce(|acc, eq| quote!(#acc && #eq))
+            .unwrap_or_else(|| quote!(true));
+        row_traits.extend(quote! {
+            impl ::std::cmp::PartialEq for #rowref_name<'_> {
+                fn eq(&self, other: &Self) -> bool {
+                    #eq
+                }
+            }
+        });
+    }
+    if struct_opts.derives("Eq") {
+        row_traits.extend(quote!(impl ::std::cmp::Eq for #rowref_name<'_> {}));
+    }
+    if struct_opts.derives("Hash") {
+        row_traits.extend(quote! {
+            impl ::std::hash::Hash for #rowref_name<'_> {
+                fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                    #(::std::hash::Hash::hash(&self.#row_ctor_idents, state);)*
+                }
+            }
+        });
     }
+    let rowref_label = rowref_name.to_string();
+    let serialize = serialize_impl(
+        &rt,
+        quote!(#rowref_name<'_>),
+        struct_name,
+        &row_ctor_idents,
+        &col_names,
+    );
+    let ndarray = ndarray_impl(
+        &rt,
+        &view_name,
+        &field_enum_name,
+        &numeric_fields,
+        &other_fields,
+    );
+    let rayon = rayon_impl(&rt, struct_name, &view_name);
+    let check_constraints = (!constraints.is_empty()).then(|| {
+        quote! {
+            let mut constraints = Vec::new();
+            #(#constraints)*
+            #rt::check_constraints(df, constraints)?;
+        }
+    });
+    let view_body = quote! {
+        #rt::check_columns(df, &[#(#required_cols),*])?;
+        #(#extract_stmts)*
+        #check_constraints
 
+        Ok(#view_name {
+            df,
+            offset: 0,
+            height: df.height(),
+            #(#view_ctor_idents),*
+        })
+    };
+    // With the `metrics` feature, views are created by a separate function so that failures can
+    // be recorded along with successes
+    let (view_body, recorded_view) = if cfg!(feature = "metrics") {
+        let label = struct_name.to_string();
+        let recorded_view = quote! {
+            impl #struct_name {
+                #[doc(hidden)]
+                fn __colback_view(
+                    df: &::polars::prelude::DataFrame,
+                ) -> #rt::Result<#view_name<'_>> {
+                    #view_body
+                }
+            }
+        };
+        let view_body = quote! {
+            let start = ::std::time::Instant::now();
+            let view = Self::__colback_view(df);
+            #rt::record_view(#label, df, <Self as #rt::ColbackSchema>::column_names(), view.is_ok(), start.elapsed());
+            view
+        };
+        (view_body, recorded_view)
+    } else {
+        (view_body, quote!())
+    };
+    let nalgebra = nalgebra_impl(&rt, &view_name, &rowref_name, &row_ctor_idents, &float_tys);
+    let skipped_column = (!skip_checks.is_empty()).then(|| {
+        quote! {
+            fn skipped_column(&self, idx: usize) -> Option<&'static str> {
+                if idx >= self.len() {
+                    return None;
+                }
+                let idx = idx + self.offset;
+                #(#skip_checks)*
+                None
+            }
+        }
+    });
     let expanded: proc_macro2::TokenStream = quote! {
+        #[derive(Clone)]
         pub struct #view_name<'a> {
             df: &'a ::polars::prelude::DataFrame,
+            /// The index of the view's first row in `df`, which is only non-zero for pages.
+            offset: usize,
+            /// The number of rows in the view.
+            height: usize,
             #(#view_members),*
         }
 
+        // Every row value is a primitive, a borrowed string or list, a datetime, or a category, so
+        // rows are `Copy`
+        #[derive(Clone, Copy)]
         pub struct #rowref_name<'a> {
             pub _data: ::std::marker::PhantomData<&'a ()>,
             #(#row_members),*
         }
 
+        impl ::std::fmt::Debug for #view_name<'_> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                #rt::fmt_view(
+                    f,
+                    #view_label,
+                    &self.frame(),
+                    <#struct_name as #rt::ColbackSchema>::column_names(),
+                )
+            }
+        }
+
+        #row_traits
+
+        #[allow(dead_code)]
+        impl<'a> #rowref_name<'a> {
+            /// The column names of the row's fields, in field order.
+            ///
+            /// These are the names accepted by `get_field`.
+            pub const FIELD_NAMES: &'static [&'static str] = &[#(#col_names),*];
+
+            /// The value of the field backed by the column `name`, or `None` if the struct has no
+            /// such column.
+            ///
+            /// This allows fields to be chosen at runtime, e.g. from configuration. Null values
+            /// are `AnyValue::Null`.
+            pub fn get_field(&self, name: &str) -> Option<::polars::prelude::AnyValue<'a>> {
+                match name {
+                    #(#col_names => Some(#any_values),)*
+                    _ => None,
+                }
+            }
+
+            /// The row's values keyed by column name, in field order.
+            ///
+            /// This lets generic consumers handle rows without depending on the row type. Null
+            /// values are `AnyValue::Null`.
+            pub fn to_any_map(
+                &self,
+            ) -> ::polars::prelude::PlIndexMap<&'static str, ::polars::prelude::AnyValue<'a>> {
+                [#((#col_names, #any_values)),*].into_iter().collect()
+            }
+
+            /// A hash of the row's values, in field order.
+            ///
+            /// The hash is stable across runs and platforms, so it can be stored to detect which
+            /// rows changed. It only covers the values, so renaming a column doesn't change it.
+            pub fn content_hash(&self) -> u64 {
+                let mut hasher = #rt::ContentHasher::new();
+                #(#rt::ContentHash::content_hash(&self.#row_ctor_idents, &mut hasher);)*
+                hasher.finish()
+            }
+        }
+
+        impl #rt::RowAccess for #rowref_name<'_> {
+            fn field_names(&self) -> &'static [&'static str] {
+                Self::FIELD_NAMES
+            }
+
+            fn get_field(&self, name: &str) -> Option<::polars::prelude::AnyValue<'_>> {
+                Self::get_field(self, name)
+            }
+        }
+
+        impl ::std::convert::From<#rowref_name<'_>> for #struct_name {
+            fn from(row: #rowref_name<'_>) -> Self {
+                Self {
+                    #(#from_row),*
+                }
+            }
+        }
+
+        impl ::std::fmt::Debug for #rowref_name<'_> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(#rowref_label)
+                    #(.field(#col_names, &self.#row_ctor_idents))*
+                    .finish()
+            }
+        }
+
+        #serialize
+
+        #ndarray
+
+        #rayon
+
+        #nalgebra
+
+        #recorded_view
+
+        /// The fields of the struct, each identifying the column that backs it.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[allow(dead_code)]
+        pub enum #field_enum_name {
+            #(#field_variants),*
+        }
+
+        impl #rt::ColumnField for #field_enum_name {
+            fn column_name(&self) -> &'static str {
+                match self {
+                    #(Self::#field_variants => #col_names),*
+                }
+            }
+
+            fn all() -> &'static [Self] {
+                &[#(Self::#field_variants),*]
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #struct_name {
+            /// Column expressions for all of the struct's columns, in field order.
+            pub fn cols() -> Vec<::polars::prelude::Expr> {
+                <Self as #rt::ColbackSchema>::projection()
+            }
+
+            /// Create a view that can stage edits to the values of the dataframe's rows.
+            ///
+            /// # Errors
+            ///
+            /// This errors under the same conditions as `view`.
+            pub fn view_mut(
+                df: &::polars::prelude::DataFrame,
+            ) -> #rt::Result<#view_mut_name<'_>> {
+                Ok(#view_mut_name {
+                    view: <Self as #rt::ColbackView>::view(df)?,
+                    edits: Default::default(),
+                })
+            }
+        }
+
+        // Mutable views only hold a view and staged edits of owned values, so they can be shared
+        // across threads like views
+        const _: () = {
+            fn assert_send_sync<T: Send + Sync>() {}
+            #[allow(dead_code)]
+            fn assert_view_mut() {
+                assert_send_sync::<#view_mut_name<'static>>();
+            }
+        };
+
+        /// A view that stages edits to the values of a dataframe's rows.
+        ///
+        /// The dataframe isn't modified, `commit` produces an updated copy of it instead.
+        pub struct #view_mut_name<'a> {
+            view: #view_name<'a>,
+            edits: (#(::std::collections::HashMap<usize, #edit_tys>,)*),
+        }
+
+        #[allow(dead_code, unused_imports)]
+        impl<'a> #view_mut_name<'a> {
+            /// The view of the dataframe's original values.
+            pub fn view(&self) -> &#view_name<'a> {
+                &self.view
+            }
+
+            #(#setters)*
+
+            /// Produce a copy of the dataframe with the staged edits applied.
+            ///
+            /// Only the columns with staged edits are rebuilt, the rest are shared with the
+            /// original dataframe.
+            ///
+            /// # Errors
+            ///
+            /// This errors if an edited column can't be rebuilt with the dtype that the struct
+            /// expects.
+            pub fn commit(self) -> #rt::Result<::polars::prelude::DataFrame> {
+                use ::polars::prelude::ChunkedBuilder as _;
+                let len = self.view.len();
+                let mut df = self.view.df.clone();
+                #(#commits)*
+                Ok(df)
+            }
+        }
+
+        /// Builds column expressions for the struct's columns, under their column names.
+        #[allow(dead_code)]
+        pub struct #cols_name;
+
+        #[allow(dead_code)]
+        impl #cols_name {
+            #(#expr_fns)*
+        }
+
+        /// Builds filter expressions that compare the struct's columns against values of the
+        /// fields' types.
+        #[allow(dead_code)]
+        pub struct #filter_name;
+
+        /// Buffers for the columns of owned rows that are being collected into a dataframe.
+        #[doc(hidden)]
+        #[derive(Default)]
+        pub struct #columns_name {
+            #(#column_members),*
+        }
+
+        impl #rt::ColumnBuffers<#struct_name> for #columns_name {
+            fn reserve(&mut self, additional: usize) {
+                #(self.#column_idents.reserve(additional);)*
+            }
+
+            fn push(&mut self, row: #struct_name) {
+                #(self.#column_idents.push(row.#column_idents);)*
+            }
+
+            fn finish(self) -> #rt::Result<::polars::prelude::DataFrame> {
+                Ok(::polars::prelude::DataFrame::new(vec![#(#column_series),*])?)
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #filter_name {
+            #(#filter_fns)*
+        }
+
+        /// The typed chunks backing each column of a view, for reading their arrow arrays
+        /// directly.
+        pub struct #raw_name<'v, 'a> {
+            #(#raw_members),*
+        }
+
+        // Row errors are returned by value like everywhere else in the crate, which clippy flags
+        // in closures of user crates
+        #[allow(clippy::result_large_err)]
         impl<'a> #view_name<'a> {
             pub fn df(&self) -> &'a ::polars::prelude::DataFrame {
                 self.df
             }
 
+            /// The rows of the view as a dataframe.
+            ///
+            /// This is `df` itself, unless the view is a page of it, in which case it's a
+            /// zero-copy slice of the page's rows.
+            pub fn frame(&self) -> ::std::borrow::Cow<'a, ::polars::prelude::DataFrame> {
+                if self.offset == 0 && self.height == self.df.height() {
+                    ::std::borrow::Cow::Borrowed(self.df)
+                } else {
+                    ::std::borrow::Cow::Owned(self.df.slice(self.offset as i64, self.height))
+                }
+            }
+
             pub fn len(&self) -> usize {
-                self.df.height()
+                self.height
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.height == 0
+            }
+
+            /// A hash of the content hashes of every row, in row order, leaving out skipped rows.
+            ///
+            /// Like the content hashes of rows, this is stable across runs and platforms, so it
+            /// can be stored to detect whether a frame changed.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first row error that is encountered.
+            pub fn fingerprint(&self) -> #rt::Result<u64> {
+                let mut hasher = #rt::ContentHasher::new();
+                for idx in 0..self.len() {
+                    if let Some(row) = self.unskipped_row(idx).transpose()? {
+                        #rt::ContentHash::content_hash(&row.content_hash(), &mut hasher);
+                    }
+                }
+                Ok(hasher.finish())
+            }
+
+            /// Split the view into pages of up to `page_size` rows, in row order.
+            ///
+            /// Pages share the view's columns, so they're neither copied nor validated again, and
+            /// their rows are indexed from zero. Fill policies still see the whole dataframe, so
+            /// rows read the same values from a page as from the view.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `page_size` is zero.
+            pub fn pages(&self, page_size: usize) -> impl Iterator<Item = #view_name<'a>> + '_ {
+                assert!(page_size > 0, "page size must be greater than zero");
+                (0..self.len())
+                    .step_by(page_size)
+                    .map(move |start| self.window(start, page_size))
+            }
+
+            /// The page at index `n` when the view is split into pages of `page_size` rows, as in
+            /// `pages`.
+            ///
+            /// Pages past the end of the view are empty.
+            pub fn page(&self, n: usize, page_size: usize) -> #view_name<'a> {
+                self.window(n.saturating_mul(page_size), page_size)
+            }
+
+            /// A view of up to `len` rows starting at row `start`, clamped to the view's rows.
+            fn window(&self, start: usize, len: usize) -> Self {
+                let start = start.min(self.height);
+                Self {
+                    df: self.df,
+                    offset: self.offset + start,
+                    height: len.min(self.height - start),
+                    #(#view_ctor_idents: self.#view_ctor_idents.clone()),*
+                }
+            }
+
+            /// The typed chunks backing each column, for reading their arrow arrays directly.
+            ///
+            /// This skips the conversions and null policies of rows, so e.g. datetimes are read as
+            /// their physical `i64` values. Pages share the chunks of the whole dataframe.
+            pub fn raw(&self) -> #raw_name<'_, 'a> {
+                #raw_name {
+                    #(#row_ctor_idents: &self.#row_ctor_idents),*
+                }
             }
 
-            pub fn get(&'a self, idx: usize) -> #rt::Result<#rowref_name<'a>> {
+            pub fn get(&self, idx: usize) -> #rt::Result<#rowref_name<'a>> {
+                if idx >= self.len() {
+                    return Err(#rt::ColbackError::IndexOutOfBounds { idx, len: self.len() });
+                }
+                // Pages index their rows from zero, but errors refer to rows of the dataframe
+                let idx = idx + self.offset;
                 #(#row_build)*
                 Ok(#rowref_name { _data: Default::default(), #(#row_ctor_idents),* })
             }
 
+            /// Iterate over the rows, leaving out skipped rows.
             pub fn iter(&'a self) -> impl Iterator<Item = #rt::Result<#rowref_name<'a>>> + 'a {
-                (0..self.len()).map(|i| self.get(i))
+                (0..self.len()).filter_map(|i| self.unskipped_row(i))
+            }
+
+            /// Get the row at a given index, or `None` if it's skipped because a field with the
+            /// "skip" null handling policy is null.
+            fn unskipped_row(&self, idx: usize) -> Option<#rt::Result<#rowref_name<'a>>> {
+                #rt::RowViewOps::get_unskipped(self, idx).transpose()
+            }
+
+            /// Iterate over the rows where `mask` is `true`.
+            ///
+            /// Only the set bits of the mask are visited. Null mask values are treated as `false`.
+            pub fn iter_filtered(
+                &'a self,
+                mask: &'a ::polars::prelude::BooleanChunked,
+            ) -> #rt::Result<impl Iterator<Item = #rt::Result<#rowref_name<'a>>> + 'a> {
+                if mask.len() != self.len() {
+                    return Err(#rt::ColbackError::LengthMismatch {
+                        expected: self.len(),
+                        actual: mask.len(),
+                    });
+                }
+                Ok(#rt::mask_indices(mask).filter_map(|i| self.unskipped_row(i)))
+            }
+
+            /// Iterate over the rows ordered by a key computed from each row.
+            ///
+            /// The sort is stable. Every row is materialized up front to compute its key, so any
+            /// row errors are returned before iteration starts.
+            pub fn iter_sorted_by<K, F>(
+                &'a self,
+                mut key: F,
+            ) -> #rt::Result<::std::vec::IntoIter<#rowref_name<'a>>>
+            where
+                K: Ord,
+                F: FnMut(&#rowref_name<'a>) -> K,
+            {
+                let mut keyed = (0..self.len())
+                    .filter_map(|i| self.unskipped_row(i))
+                    .map(|row| {
+                        let row = row?;
+                        Ok((key(&row), row))
+                    })
+                    .collect::<#rt::Result<Vec<_>>>()?;
+                keyed.sort_by(|a, b| a.0.cmp(&b.0));
+                let rows: Vec<_> = keyed.into_iter().map(|(_, row)| row).collect();
+                Ok(rows.into_iter())
+            }
+
+            /// Iterate over the rows ordered by a column of the dataframe.
+            ///
+            /// This argsorts the column rather than sorting the dataframe, so only an index
+            /// permutation is materialized.
+            pub fn iter_sorted_by_column(
+                &'a self,
+                col: &str,
+                options: ::polars::prelude::SortOptions,
+            ) -> #rt::Result<impl Iterator<Item = #rt::Result<#rowref_name<'a>>> + use<'a>> {
+                let order: Vec<_> = self
+                    .frame()
+                    .column(col)
+                    .map_err(|_| #rt::ColbackError::MissingColumn(col.to_string()))?
+                    .arg_sort(options)
+                    .into_no_null_iter()
+                    .map(|i| i as usize)
+                    .collect();
+                Ok(order.into_iter().filter_map(|i| self.unskipped_row(i)))
+            }
+
+            /// Group the rows by the fields of a key struct.
+            ///
+            /// The returned groups can be iterated to get the typed key and a typed view of the
+            /// rows for each group.
+            pub fn group_by<K: #rt::ColbackKey>(&self) -> #rt::Result<#rt::Groups<K, #struct_name>> {
+                #rt::Groups::new(&self.frame())
+            }
+
+            /// Filter the dataframe with a row-wise predicate.
+            ///
+            /// The predicate is evaluated for every row to build a boolean mask which is then
+            /// applied to the underlying dataframe. Skipped rows are filtered out without
+            /// evaluating the predicate.
+            pub fn filter<F>(&'a self, mut predicate: F) -> #rt::Result<::polars::prelude::DataFrame>
+            where
+                F: FnMut(&#rowref_name<'a>) -> bool,
+            {
+                let mask = (0..self.len())
+                    .map(|i| {
+                        #rt::RowViewOps::get_unskipped(self, i)
+                            .map(|row| row.is_some_and(|row| predicate(&row)))
+                    })
+                    .collect::<#rt::Result<::polars::prelude::BooleanChunked>>()?;
+                Ok(self.frame().filter(&mask)?)
+            }
+
+            /// Compute a new series with a value for every row.
+            ///
+            /// The dtype of the series is inferred from the type returned by `f`. `f` isn't
+            /// called for skipped rows, which are null in the series so it still lines up with
+            /// the dataframe.
+            pub fn map_to_series<T, F>(
+                &'a self,
+                name: impl Into<::polars::prelude::PlSmallStr>,
+                mut f: F,
+            ) -> #rt::Result<::polars::prelude::Series>
+            where
+                F: FnMut(&#rowref_name<'a>) -> T,
+                ::polars::prelude::Series: ::polars::prelude::NamedFrom<Vec<T>, [T]>,
+            {
+                #rt::map_rows(self, name.into(), |row| f(&row))
+            }
+
+            /// Compute a new column for every row and return a copy of the dataframe with that
+            /// column added.
+            ///
+            /// If a column with the same name already exists it is replaced. Skipped rows are
+            /// null in the new column, see `map_to_series`.
+            pub fn with_column<T, F>(
+                &'a self,
+                name: impl Into<::polars::prelude::PlSmallStr>,
+                f: F,
+            ) -> #rt::Result<::polars::prelude::DataFrame>
+            where
+                F: FnMut(&#rowref_name<'a>) -> T,
+                ::polars::prelude::Series: ::polars::prelude::NamedFrom<Vec<T>, [T]>,
+            {
+                let series = self.map_to_series(name, f)?;
+                let mut df = self.frame().into_owned();
+                df.with_column(series)?;
+                Ok(df)
+            }
+
+            /// Compute a value from the rows of the rolling window ending at every row.
+            ///
+            /// The window either spans a number of rows or a period of one of the struct's
+            /// datetime fields, see `colback::rolling` for details.
+            pub fn rolling<T, F>(
+                &'a self,
+                name: impl Into<::polars::prelude::PlSmallStr>,
+                window: #rt::RollingWindow<#field_enum_name>,
+                f: F,
+            ) -> #rt::Result<::polars::prelude::Series>
+            where
+                F: FnMut(&[#rowref_name<'a>]) -> T,
+                ::polars::prelude::Series: ::polars::prelude::NamedFrom<Vec<T>, [T]>,
+            {
+                #rt::rolling(self, name, window, f)
+            }
+
+            /// Recompute one of the struct's columns from every row, returning a copy of the
+            /// dataframe with that column replaced.
+            ///
+            /// The new values must have the column's dtype, and skipped rows keep their value,
+            /// see `colback::replace_column`.
+            pub fn replace_column<T, F>(
+                &'a self,
+                field: #field_enum_name,
+                f: F,
+            ) -> #rt::Result<::polars::prelude::DataFrame>
+            where
+                F: FnMut(&#rowref_name<'a>) -> T,
+                ::polars::prelude::Series: ::polars::prelude::NamedFrom<Vec<T>, [T]>,
+            {
+                #rt::replace_column(self, field, f)
+            }
+
+            /// Explode a `Vec` field into one row per list element, joined with the struct's other
+            /// columns and viewable as the child struct `C`.
+            ///
+            /// See `colback::explode` for how elements are turned into columns.
+            pub fn explode<C: #rt::ColbackView>(
+                &self,
+                field: #field_enum_name,
+            ) -> #rt::Result<::polars::prelude::DataFrame> {
+                #rt::explode::<_, _, C>(self, field)
+            }
+
+            /// Split the rows into a training and a test dataframe, with roughly `frac` of the
+            /// rows in the training frame.
+            ///
+            /// The split is random but reproducible with `seed`, see `colback::split`.
+            pub fn split(
+                &self,
+                frac: f64,
+                seed: u64,
+            ) -> #rt::Result<(::polars::prelude::DataFrame, ::polars::prelude::DataFrame)> {
+                #rt::split(self, frac, seed, None::<#field_enum_name>)
+            }
+
+            /// Split the rows into a training and a test dataframe, splitting the rows of each
+            /// value of a field separately so both frames have about the same share of it.
+            pub fn split_stratified(
+                &self,
+                by: #field_enum_name,
+                frac: f64,
+                seed: u64,
+            ) -> #rt::Result<(::polars::prelude::DataFrame, ::polars::prelude::DataFrame)> {
+                #rt::split(self, frac, seed, Some(by))
+            }
+
+            /// Split the rows into `k` folds, returning the training and test dataframe of each
+            /// fold.
+            ///
+            /// The folds are random but reproducible with `seed`, see `colback::kfold`.
+            pub fn kfold(
+                &self,
+                k: usize,
+                seed: u64,
+            ) -> #rt::Result<Vec<(::polars::prelude::DataFrame, ::polars::prelude::DataFrame)>> {
+                #rt::kfold(self, k, seed, None::<#field_enum_name>)
+            }
+
+            /// Split the rows into `k` folds, dealing the rows of each value of a field across
+            /// the folds separately so every fold has about the same share of it.
+            pub fn kfold_stratified(
+                &self,
+                by: #field_enum_name,
+                k: usize,
+                seed: u64,
+            ) -> #rt::Result<Vec<(::polars::prelude::DataFrame, ::polars::prelude::DataFrame)>> {
+                #rt::kfold(self, k, seed, Some(by))
+            }
+
+            /// Sample up to `n_per_group` rows for each value of a field, see
+            /// `colback::sample_stratified`.
+            pub fn sample_stratified(
+                &self,
+                by: #field_enum_name,
+                n_per_group: usize,
+                seed: u64,
+            ) -> #rt::Result<::polars::prelude::DataFrame> {
+                #rt::sample_stratified(self, by, n_per_group, seed)
+            }
+
+            /// Sample up to `n` rows with chances proportional to a numeric field, see
+            /// `colback::sample_weighted`.
+            pub fn sample_weighted(
+                &self,
+                weight: #field_enum_name,
+                n: usize,
+                seed: u64,
+            ) -> #rt::Result<::polars::prelude::DataFrame> {
+                #rt::sample_weighted(self, weight, n, seed)
+            }
+        }
+
+        impl<'a> #rt::RowViewOps<'a> for #view_name<'a> {
+            type Row = #rowref_name<'a>;
+
+            fn df(&self) -> &'a ::polars::prelude::DataFrame {
+                self.df
+            }
+
+            fn frame(&self) -> ::std::borrow::Cow<'a, ::polars::prelude::DataFrame> {
+                self.frame()
+            }
+
+            fn len(&self) -> usize {
+                self.len()
+            }
+
+            fn get(&self, idx: usize) -> #rt::Result<Self::Row> {
+                self.get(idx)
+            }
+
+            #skipped_column
+
+            fn column_names(&self) -> &'static [&'static str] {
+                <#struct_name as #rt::ColbackSchema>::column_names()
             }
         }
 
         impl #rt::ColbackView for #struct_name {
             type View<'a> = #view_name<'a> where Self: 'a;
             type RowRef<'a> = #rowref_name<'a> where Self: 'a;
+            type Field = #field_enum_name;
+            type Columns = #columns_name;
 
             fn view<'a>(df: &'a ::polars::prelude::DataFrame) -> #rt::Result<Self::View<'a>> {
-                #(#extract_stmts)*
+                #view_body
+            }
 
-                Ok(#view_name {
-                    df,
-                    #(#view_ctor_idents),*
-                })
+            fn filled_columns() -> &'static [&'static str] {
+                &[#(#filled_cols),*]
             }
         }
 
+        #schema
     };
-    expanded.into()
+    expanded
 }
@@ -0,0 +1,61 @@
+//! Generation of structs from the schemas of data files at expansion time.
+
+use crate::emit::{ColumnSpec, emit_struct};
+use colback_codegen::{field_type, file_columns};
+use proc_macro_error::abort;
+use quote::quote;
+use std::path::Path;
+use syn::{
+    Token,
+    parse::{Parse, ParseStream},
+};
+
+/// The input of `struct_from_schema!`: `"path/to/file.parquet" as StructName`.
+pub struct SchemaInput {
+    path: syn::LitStr,
+    name: syn::Ident,
+}
+
+impl Parse for SchemaInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        input.parse::<Token![as]>()?;
+        let name = input.parse()?;
+        Ok(Self { path, name })
+    }
+}
+
+/// Expand `struct_from_schema!` into a struct deriving `ColbackView` with the file's columns.
+pub fn expand(rt: &proc_macro2::TokenStream, input: SchemaInput) -> proc_macro2::TokenStream {
+    let SchemaInput { path, name } = input;
+    // Paths are relative to the manifest of the crate being compiled, like `include_str!`
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = Path::new(&manifest_dir).join(path.value());
+    let columns = match file_columns(&full_path) {
+        Ok(columns) => columns,
+        Err(e) => abort!(path, "failed to read the schema of {}: {}", path.value(), e),
+    };
+
+    let columns = columns
+        .iter()
+        .filter_map(|(column, dtype, nullable)| {
+            // Columns that the derive macros can't represent are left out of the struct
+            let (ty, time_unit) = field_type(dtype)?;
+            let ty = match ty {
+                "NaiveDateTime" => quote!(#rt::__chrono::NaiveDateTime),
+                _ => {
+                    let ty: syn::Type = syn::parse_str(ty).expect("field types are valid types");
+                    quote!(#ty)
+                }
+            };
+            Some(ColumnSpec {
+                name: column.clone(),
+                ty,
+                time_unit,
+                nullable: *nullable,
+            })
+        })
+        .collect();
+    let doc = format!("The columns of `{}`.", path.value());
+    emit_struct(rt, &name, &doc, columns)
+}
@@ -1,11 +1,161 @@
-use quote::quote;
+use quote::{format_ident, quote};
+
+/// The kind of polars chunked array builder that builds a column of a type.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BuilderKind {
+    Primitive,
+    Boolean,
+    String,
+    List,
+}
 
 pub struct TypeMap {
     pub expected_dtype: proc_macro2::TokenStream,
-    pub accessor: syn::Ident,
-    pub chunked_ty: proc_macro2::TokenStream,
+    /// Method calls that extract the chunked array from a column with the expected dtype.
+    pub extract_chunked: proc_macro2::TokenStream,
+    pub polars_ty: proc_macro2::TokenStream,
     pub row_value_ty: proc_macro2::TokenStream,
     pub get_value_expr: proc_macro2::TokenStream,
+    /// Whether this is a temporal type that accepts a `time_unit` attribute.
+    pub temporal: bool,
+    /// Whether this is a float type that accepts a `nan` attribute.
+    pub float: bool,
+    /// The integer type of the column of an `epoch` field, whose values are converted from and
+    /// to counts of time units.
+    pub epoch: Option<proc_macro2::TokenStream>,
+    /// Whether this is an enum deriving `ColbackCategory`, which is backed by a string column of
+    /// category names.
+    pub category: bool,
+    /// The builder used to build a column of this type, with values of `polars_ty`.
+    pub builder: BuilderKind,
+    /// The polars type of the elements of a `Vec` field's list column.
+    pub list: Option<proc_macro2::TokenStream>,
+}
+
+impl TypeMap {
+    /// The type of the chunked array builder for a column of this type.
+    pub fn builder_ty(&self, rt: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let polars_ty = &self.polars_ty;
+        let elem_ty = &self.list;
+        match self.builder {
+            BuilderKind::Primitive => {
+                quote!(::polars::prelude::PrimitiveChunkedBuilder<#polars_ty>)
+            }
+            BuilderKind::Boolean => quote!(::polars::prelude::BooleanChunkedBuilder),
+            BuilderKind::String => quote!(::polars::prelude::StringChunkedBuilder),
+            BuilderKind::List => quote!(#rt::ListBuilder<#elem_ty>),
+        }
+    }
+
+    /// Whether this is an integer or float type, which can be compared with numeric bounds.
+    pub fn numeric(&self) -> bool {
+        self.builder == BuilderKind::Primitive && !self.temporal && self.epoch.is_none()
+    }
+
+    /// An expression converting an owned value of an `epoch` field to an optional integer of the
+    /// column's type, which is `None` if the value is null or doesn't fit.
+    pub fn epoch_value(
+        &self,
+        rt: &proc_macro2::TokenStream,
+        time_unit: &proc_macro2::TokenStream,
+        is_option: bool,
+        value: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let raw_ty = &self.epoch;
+        if is_option {
+            quote! {
+                ::std::option::Option::and_then(#value, |v| #rt::to_epoch::<_, #raw_ty>(v, #time_unit))
+            }
+        } else {
+            quote!(#rt::to_epoch::<_, #raw_ty>(#value, #time_unit))
+        }
+    }
+
+    /// Whether the column holds a different value than the field, which
+    /// [TypeMap::physical_value] converts.
+    pub fn converts(&self) -> bool {
+        self.checks_range() || self.category
+    }
+
+    /// Whether converting a value to the column's value can fail, see [TypeMap::physical_value].
+    pub fn checks_range(&self) -> bool {
+        self.temporal || self.epoch.is_some()
+    }
+
+    /// An expression converting an owned field value to the value that [TypeMap::append] appends.
+    ///
+    /// This is the value itself, except for categories, which are appended as their names, and
+    /// for datetimes and `epoch` fields whose columns hold integers. Those are converted where
+    /// `idx` is the index of the row and `?` returns the runtime's errors, since values that don't
+    /// fit in the column error with `ValueOutOfRange`.
+    pub fn physical_value(
+        &self,
+        rt: &proc_macro2::TokenStream,
+        time_unit: &proc_macro2::TokenStream,
+        col_name: &str,
+        is_option: bool,
+        value: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let in_range = |convert| {
+            quote! {
+                #convert.ok_or_else(|| #rt::ColbackError::ValueOutOfRange {
+                    col: #col_name.to_string(),
+                    idx,
+                    value: format!("{v:?}"),
+                })?
+            }
+        };
+        let convert = match &self.epoch {
+            Some(raw_ty) => in_range(quote!(#rt::to_epoch::<_, #raw_ty>(v, #time_unit))),
+            None if self.temporal => in_range(quote!(#rt::datetime_in_unit(v, #time_unit))),
+            None if self.category => quote!(#rt::ColbackCategory::category(&v)),
+            None => return value.clone(),
+        };
+        if is_option {
+            quote! {
+                match #value {
+                    Some(v) => Some(#convert),
+                    None => None,
+                }
+            }
+        } else {
+            quote!({
+                let v = #value;
+                #convert
+            })
+        }
+    }
+
+    /// An expression appending a value from [TypeMap::physical_value] to a builder of
+    /// [TypeMap::builder_ty].
+    ///
+    /// Datetimes are appended as their physical values, so the finished column has to be cast to
+    /// `expected_dtype`.
+    pub fn append(
+        &self,
+        is_option: bool,
+        builder: &proc_macro2::TokenStream,
+        value: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        if is_option {
+            quote!(#builder.append_option(#value))
+        } else {
+            quote!(#builder.append_value(#value))
+        }
+    }
+
+    /// Get a value that can be passed to [TypeMap::append] from a variable holding a reference to
+    /// a field value, without cloning it.
+    pub fn borrow_value(&self, is_option: bool, var: &syn::Ident) -> proc_macro2::TokenStream {
+        match (self.builder, is_option) {
+            _ if self.category => quote!(*#var),
+            (BuilderKind::String, false) => quote!(#var.as_str()),
+            (BuilderKind::String, true) => quote!(#var.as_deref()),
+            (BuilderKind::List, false) => quote!(#var.as_slice()),
+            (BuilderKind::List, true) => quote!(#var.as_deref()),
+            _ => quote!(*#var),
+        }
+    }
 }
 
 pub fn option_inner(ty: &syn::Type) -> (bool, syn::Type) {
@@ -22,6 +172,40 @@ pub fn option_inner(ty: &syn::Type) -> (bool, syn::Type) {
     (false, ty.clone())
 }
 
+/// Whether a type is one of the integer types that fields support.
+pub fn is_integer(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(tp) if tp.qself.is_none()
+        && ["u8", "u16", "u32", "u64", "i32", "i64"].iter().any(|int| tp.path.is_ident(int)))
+}
+
+/// The number of bits and the signedness of an integer type that fields support.
+fn integer_bits(ty: &syn::Type) -> Option<(u32, bool)> {
+    let syn::Type::Path(tp) = ty else {
+        return None;
+    };
+    let ident = tp.path.get_ident()?.to_string();
+    let bits = ident[1..].parse().ok()?;
+    match &ident[..1] {
+        "u" => Some((bits, false)),
+        "i" => Some((bits, true)),
+        _ => None,
+    }
+}
+
+/// Whether every value of the integer type `from` can be losslessly converted into `to`.
+pub fn widens(from: &syn::Type, to: &syn::Type) -> bool {
+    match (integer_bits(from), integer_bits(to)) {
+        (Some((from_bits, from_signed)), Some((to_bits, to_signed))) => {
+            match (from_signed, to_signed) {
+                (true, false) => false,
+                (false, true) => to_bits > from_bits,
+                _ => to_bits >= from_bits,
+            }
+        }
+        _ => false,
+    }
+}
+
 /// Map primitive types to polars dtypes
 ///
 /// This handles boilerplate for a match statement that constructs a type map definition based on
@@ -33,7 +217,7 @@ macro_rules! map_prim {
         $( $rust:literal => {
             dtype: $dtype:ident,
             accessor: $accessor:literal,
-            chunked: $chunked:ident,
+            polars_ty: $polars_ty:ident,
             row_ty: $row_ty:tt $( $row_ty_tail:tt )*
         } ),* $(,)?
     ) => {{
@@ -41,10 +225,23 @@ macro_rules! map_prim {
             $(
                 $rust => Some(TypeMap {
                     expected_dtype: quote!(::polars::prelude::DataType::$dtype),
-                    accessor: syn::Ident::new($accessor, proc_macro2::Span::call_site()),
-                    chunked_ty: quote!(::polars::prelude::$chunked),
+                    extract_chunked: {
+                        let accessor = syn::Ident::new($accessor, proc_macro2::Span::call_site());
+                        quote!(.#accessor().expect("dtype checked above"))
+                    },
+                    polars_ty: quote!(::polars::prelude::$polars_ty),
                     row_value_ty: quote!($row_ty $( $row_ty_tail )*),
                     get_value_expr: $get_value_expr,
+                    temporal: false,
+                    float: matches!($accessor, "f32" | "f64"),
+                    epoch: None,
+                    category: false,
+                    builder: match $accessor {
+                        "bool" => BuilderKind::Boolean,
+                        "str" => BuilderKind::String,
+                        _ => BuilderKind::Primitive,
+                    },
+                    list: None,
                 }),
             )*
             _ => None,
@@ -52,10 +249,281 @@ macro_rules! map_prim {
     }};
 }
 
+/// Map the time unit of a `time_unit` attribute to a polars time unit.
+pub fn time_unit(unit: &str) -> Option<proc_macro2::TokenStream> {
+    let variant = match unit {
+        "ns" => quote!(Nanoseconds),
+        "us" => quote!(Microseconds),
+        "ms" => quote!(Milliseconds),
+        _ => return None,
+    };
+    Some(quote!(::polars::prelude::TimeUnit::#variant))
+}
+
+/// Whether polars recognizes the time zone of a `time_zone` attribute.
+///
+/// This accepts the same time zones as polars: names from the time zone database, and fixed
+/// offsets of whole hours such as "+01:00".
+pub fn valid_time_zone(tz: &str) -> bool {
+    if tz.parse::<chrono_tz::Tz>().is_ok() || ["utc", "00:00", "+00:00"].contains(&tz) {
+        return true;
+    }
+    let offset = tz.strip_prefix(['+', '-']).unwrap_or(tz);
+    let offset = offset.strip_suffix("00").unwrap_or_default();
+    let hour = offset.strip_suffix(':').unwrap_or(offset);
+    hour.len() == 2 && hour.parse::<u8>().is_ok_and(|hour| hour <= 14)
+}
+
+/// The view member holding how the values of a datetime field's column are converted to the
+/// field's time zone.
+pub fn time_zone_ident(col_ident: &syn::Ident) -> syn::Ident {
+    format_ident!("{}_tz", col_ident)
+}
+
+/// Map temporal Rust types to polars dtypes for fields of a struct.
+///
+/// Values are converted to the field's time zone with the view's [time_zone_ident] member.
+///
+/// Temporal types are identified by the last segment of their path so that they can be written
+/// with or without the `chrono::` prefix.
+fn map_temporal(
+    rt: &proc_macro2::TokenStream,
+    col_ident: &syn::Ident,
+    col_name: &str,
+    ty: &syn::Type,
+    time_unit: &proc_macro2::TokenStream,
+    time_zone: &proc_macro2::TokenStream,
+) -> Option<TypeMap> {
+    let syn::Type::Path(tp) = ty else {
+        return None;
+    };
+    let last = tp.path.segments.last()?;
+    let tz_ident = time_zone_ident(col_ident);
+    match last.ident.to_string().as_str() {
+        "NaiveDateTime" if last.arguments.is_none() => Some(TypeMap {
+            expected_dtype: quote!(::polars::prelude::DataType::Datetime(#time_unit, #time_zone)),
+            extract_chunked: quote!(.datetime().expect("dtype checked above").physical()),
+            polars_ty: quote!(::polars::prelude::Int64Type),
+            row_value_ty: quote!(#ty),
+            get_value_expr: quote! {
+                match self.#col_ident.get(idx) {
+                    Some(v) => Some(#rt::naive_datetime(v, #time_unit)
+                        .and_then(|d| self.#tz_ident.apply(d))
+                        .ok_or_else(|| {
+                        #rt::ColbackError::ValueOutOfRange {
+                            col: #col_name.to_string(),
+                            idx,
+                            value: v.to_string(),
+                        }
+                    })?),
+                    None => None,
+                }
+            },
+            temporal: true,
+            float: false,
+            epoch: None,
+            category: false,
+            builder: BuilderKind::Primitive,
+            list: None,
+        }),
+        _ => None,
+    }
+}
+
+/// Map the datetime and duration types of `epoch` fields to integer columns.
+///
+/// The column has the integer type `raw`, which is `i64` or `u64`, and values that chrono or the
+/// field type can't represent error with `ValueOutOfRange` when a row is read. Types are
+/// identified by the last segment of their path, as in [map_temporal].
+pub fn map_epoch(
+    rt: &proc_macro2::TokenStream,
+    col_ident: &syn::Ident,
+    col_name: &str,
+    ty: &syn::Type,
+    raw: &syn::Type,
+    time_unit: &proc_macro2::TokenStream,
+) -> Option<TypeMap> {
+    let syn::Type::Path(tp) = ty else {
+        return None;
+    };
+    let last = tp.path.segments.last()?;
+    let supported = match last.ident.to_string().as_str() {
+        "DateTime" => matches!(
+            &last.arguments,
+            syn::PathArguments::AngleBracketed(args) if matches!(
+                args.args.first(),
+                Some(syn::GenericArgument::Type(syn::Type::Path(tz)))
+                    if tz.path.segments.last().is_some_and(|s| s.ident == "Utc")
+            )
+        ),
+        "Duration" | "TimeDelta" => last.arguments.is_none(),
+        _ => false,
+    };
+    if !supported {
+        return None;
+    }
+    let (dtype, accessor, polars_ty) = match quote!(#raw).to_string().as_str() {
+        "i64" => (quote!(Int64), quote!(i64), quote!(Int64Type)),
+        "u64" => (quote!(UInt64), quote!(u64), quote!(UInt64Type)),
+        _ => return None,
+    };
+    Some(TypeMap {
+        expected_dtype: quote!(::polars::prelude::DataType::#dtype),
+        extract_chunked: quote!(.#accessor().expect("dtype checked above")),
+        polars_ty: quote!(::polars::prelude::#polars_ty),
+        row_value_ty: quote!(#ty),
+        get_value_expr: quote! {
+            match self.#col_ident.get(idx) {
+                Some(v) => Some(#rt::from_epoch::<#ty, _>(v, #time_unit).ok_or_else(|| {
+                    #rt::ColbackError::ValueOutOfRange {
+                        col: #col_name.to_string(),
+                        idx,
+                        value: v.to_string(),
+                    }
+                })?),
+                None => None,
+            }
+        },
+        temporal: false,
+        float: false,
+        epoch: Some(quote!(#raw)),
+        category: false,
+        builder: BuilderKind::Primitive,
+        list: None,
+    })
+}
+
+/// Map an enum deriving `ColbackCategory` to a column of category names.
+///
+/// The column's dtype is `String`, which rows are written as, but views also read categorical and
+/// enum columns through `CategoryIndex`, so `extract_chunked` only applies to string columns.
+/// `other` is the variant that values which aren't one of the enum's categories are read as.
+/// Without it, they error with `UnknownCategory`, or with `RowSkipped` if `skip` is set.
+pub fn map_category(
+    rt: &proc_macro2::TokenStream,
+    col_ident: &syn::Ident,
+    col_name: &str,
+    ty: &syn::Type,
+    other: Option<&syn::Ident>,
+    skip: bool,
+) -> TypeMap {
+    let from_category = quote!(<#ty as #rt::ColbackCategory>::from_category(v));
+    let unknown = if skip {
+        quote!(#rt::ColbackError::RowSkipped { col: #col_name.to_string(), idx })
+    } else {
+        quote! {
+            #rt::ColbackError::UnknownCategory {
+                col: #col_name.to_string(),
+                idx,
+                value: v.to_string(),
+            }
+        }
+    };
+    let get_value_expr = match other {
+        Some(other) => quote! {
+            self.#col_ident.get(idx).map(|v| #from_category.unwrap_or(<#ty>::#other))
+        },
+        None => quote! {
+            match self.#col_ident.get(idx) {
+                Some(v) => Some(#from_category.ok_or_else(|| #unknown)?),
+                None => None,
+            }
+        },
+    };
+    TypeMap {
+        expected_dtype: quote!(::polars::prelude::DataType::String),
+        extract_chunked: quote!(.str().expect("dtype checked above")),
+        polars_ty: quote!(::polars::prelude::StringType),
+        row_value_ty: quote!(#ty),
+        get_value_expr,
+        temporal: false,
+        float: false,
+        epoch: None,
+        category: true,
+        builder: BuilderKind::String,
+        list: None,
+    }
+}
+
+/// Map `Vec` fields of integers and floats to list columns.
+///
+/// Rows borrow the elements of their list as a slice, so lists with null elements error with
+/// `InvalidNull` when a row is read.
+fn map_list(
+    rt: &proc_macro2::TokenStream,
+    col_ident: &syn::Ident,
+    col_name: &str,
+    ty: &syn::Type,
+) -> Option<TypeMap> {
+    let syn::Type::Path(tp) = ty else {
+        return None;
+    };
+    let last = tp.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    let Some(syn::GenericArgument::Type(elem)) = args.args.first() else {
+        return None;
+    };
+    if last.ident != "Vec" {
+        return None;
+    }
+    let (dtype, polars_ty) = match quote!(#elem).to_string().as_str() {
+        "u8" => (quote!(UInt8), quote!(UInt8Type)),
+        "u16" => (quote!(UInt16), quote!(UInt16Type)),
+        "u32" => (quote!(UInt32), quote!(UInt32Type)),
+        "u64" => (quote!(UInt64), quote!(UInt64Type)),
+        "i32" => (quote!(Int32), quote!(Int32Type)),
+        "i64" => (quote!(Int64), quote!(Int64Type)),
+        "f32" => (quote!(Float32), quote!(Float32Type)),
+        "f64" => (quote!(Float64), quote!(Float64Type)),
+        _ => return None,
+    };
+    let elem_ty = quote!(::polars::prelude::#polars_ty);
+    Some(TypeMap {
+        expected_dtype: quote! {
+            ::polars::prelude::DataType::List(::std::boxed::Box::new(
+                ::polars::prelude::DataType::#dtype,
+            ))
+        },
+        extract_chunked: quote!(.list().expect("dtype checked above")),
+        polars_ty: quote!(::polars::prelude::ListType),
+        row_value_ty: quote!(&'a [#elem]),
+        get_value_expr: quote! {
+            match self.#col_ident.get_slice::<#elem_ty>(idx) {
+                Some(Some(v)) => Some(v),
+                Some(None) => {
+                    return Err(#rt::ColbackError::InvalidNull { col: #col_name.to_string(), idx });
+                }
+                None => None,
+            }
+        },
+        temporal: false,
+        float: false,
+        epoch: None,
+        category: false,
+        builder: BuilderKind::List,
+        list: Some(elem_ty),
+    })
+}
+
 /// Map primitive Rust types to polars dtypes for fields of a struct.
 ///
 /// This *does not* handle `Option<T>` types, this is only meant for the inner types.
-pub fn map_type(col_ident: &syn::Ident, ty: &syn::Type) -> Option<TypeMap> {
+pub fn map_type(
+    rt: &proc_macro2::TokenStream,
+    col_ident: &syn::Ident,
+    col_name: &str,
+    ty: &syn::Type,
+    time_unit: &proc_macro2::TokenStream,
+    time_zone: &proc_macro2::TokenStream,
+) -> Option<TypeMap> {
+    if let Some(map) = map_temporal(rt, col_ident, col_name, ty, time_unit, time_zone) {
+        return Some(map);
+    }
+    if let Some(map) = map_list(rt, col_ident, col_name, ty) {
+        return Some(map);
+    }
     let ident = match ty {
         syn::Type::Path(tp) if tp.qself.is_none() && tp.path.segments.len() == 1 => {
             tp.path.segments[0].ident.to_string()
@@ -63,20 +531,20 @@ pub fn map_type(col_ident: &syn::Ident, ty: &syn::Type) -> Option<TypeMap> {
         _ => return None,
     };
 
+    // Categorical columns are read by category fields, see `map_category`
     let get_value_expr = quote!(self.#col_ident.get(idx));
-    // TODO: determine best way to handle categoricals
     map_prim!(
         ident.as_str(),
         get_value_expr,
-        "u8" => { dtype: UInt8, accessor: "u8", chunked: UInt8Chunked, row_ty: u8 },
-        "u16" => { dtype: UInt16, accessor: "u16", chunked: UInt16Chunked, row_ty: u16 },
-        "u32" => { dtype: UInt32, accessor: "u32", chunked: UInt32Chunked, row_ty: u32 },
-        "u64" => { dtype: UInt64, accessor: "u64", chunked: UInt63Chunked, row_ty: u64 },
-        "i32" => { dtype: Int32,  accessor: "i32", chunked: Int32Chunked,  row_ty: i32 },
-        "i64" => { dtype: Int64,  accessor: "i64", chunked: Int64Chunked,  row_ty: i64 },
-        "f32" => { dtype: Float32, accessor: "f32", chunked: Float32Chunked, row_ty: f32 },
-        "f64" => { dtype: Float64, accessor: "f64", chunked: Float64Chunked, row_ty: f64 },
-        "bool" => { dtype: Boolean, accessor: "bool", chunked: BooleanChunked, row_ty: bool },
-        "String" => { dtype: String, accessor: "str", chunked: StringChunked, row_ty: &'a str },
+        "u8" => { dtype: UInt8, accessor: "u8", polars_ty: UInt8Type, row_ty: u8 },
+        "u16" => { dtype: UInt16, accessor: "u16", polars_ty: UInt16Type, row_ty: u16 },
+        "u32" => { dtype: UInt32, accessor: "u32", polars_ty: UInt32Type, row_ty: u32 },
+        "u64" => { dtype: UInt64, accessor: "u64", polars_ty: UInt64Type, row_ty: u64 },
+        "i32" => { dtype: Int32,  accessor: "i32", polars_ty: Int32Type,  row_ty: i32 },
+        "i64" => { dtype: Int64,  accessor: "i64", polars_ty: Int64Type,  row_ty: i64 },
+        "f32" => { dtype: Float32, accessor: "f32", polars_ty: Float32Type, row_ty: f32 },
+        "f64" => { dtype: Float64, accessor: "f64", polars_ty: Float64Type, row_ty: f64 },
+        "bool" => { dtype: Boolean, accessor: "bool", polars_ty: BooleanType, row_ty: bool },
+        "String" => { dtype: String, accessor: "str", polars_ty: StringType, row_ty: &'a str },
     )
 }
@@ -0,0 +1,158 @@
+//! Random row access through a view compared with `ChunkedArray::get` on every column.
+//!
+//! Views cache the downcast chunks of each column along with their row offsets, so getting a row
+//! is an index into the chunk's values plus a validity check. The baseline resolves the chunk of
+//! every value through polars instead, which is what views did before the cache.
+//!
+//! Run with `cargo bench --bench random_access`.
+
+use colback::ColbackView;
+use polars::prelude::*;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+#[derive(ColbackView)]
+#[allow(dead_code)]
+struct Wide {
+    a: u32,
+    b: i64,
+    c: f64,
+    d: bool,
+    e: String,
+    f: u8,
+    g: i32,
+    h: f32,
+    #[polars(null = "option")]
+    i: Option<u64>,
+}
+
+const ROWS: usize = 1_000_000;
+const LOOKUPS: usize = 1_000_000;
+const ITERATIONS: u32 = 5;
+
+/// A frame with the columns of [Wide], split into `chunks` chunks.
+fn frame(chunks: usize) -> DataFrame {
+    let per_chunk = ROWS / chunks;
+    let part = |chunk: usize| {
+        let idx = (chunk * per_chunk..(chunk + 1) * per_chunk).collect::<Vec<_>>();
+        df! [
+            "a" => idx.iter().map(|&i| i as u32).collect::<Vec<_>>(),
+            "b" => idx.iter().map(|&i| i as i64 * 3).collect::<Vec<_>>(),
+            "c" => idx.iter().map(|&i| i as f64 / 2.0).collect::<Vec<_>>(),
+            "d" => idx.iter().map(|&i| i % 2 == 0).collect::<Vec<_>>(),
+            "e" => idx.iter().map(|&i| format!("row_{}", i % 100)).collect::<Vec<_>>(),
+            "f" => idx.iter().map(|&i| (i % 256) as u8).collect::<Vec<_>>(),
+            "g" => idx.iter().map(|&i| -(i as i32)).collect::<Vec<_>>(),
+            "h" => idx.iter().map(|&i| i as f32).collect::<Vec<_>>(),
+            "i" => idx.iter().map(|&i| (i % 7 != 0).then_some(i as u64)).collect::<Vec<_>>(),
+        ]
+        .unwrap()
+    };
+    let mut df = part(0);
+    for chunk in 1..chunks {
+        df.vstack_mut(&part(chunk)).unwrap();
+    }
+    df
+}
+
+/// Row indices in a fixed pseudo random order, so every run reads the same rows.
+fn lookups(len: usize) -> Vec<usize> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    (0..LOOKUPS)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % len as u64) as usize
+        })
+        .collect()
+}
+
+/// Fold the fields of a row into a checksum, so the reads can't be optimized away.
+fn checksum(acc: u64, fields: [u64; 9]) -> u64 {
+    fields.into_iter().fold(acc, u64::wrapping_add)
+}
+
+/// Read every field of the rows through a view.
+fn view_rows(df: &DataFrame, idx: &[usize]) -> u64 {
+    let view = Wide::view(df).unwrap();
+    idx.iter().fold(0, |acc, &i| {
+        let row = view.get(i).unwrap();
+        checksum(
+            acc,
+            [
+                row.a as u64,
+                row.b as u64,
+                row.c as u64,
+                row.d as u64,
+                row.e.len() as u64,
+                row.f as u64,
+                row.g as u64,
+                row.h as u64,
+                row.i.unwrap_or_default(),
+            ],
+        )
+    })
+}
+
+/// Read every field of the rows with `ChunkedArray::get`, resolving the chunk of each value.
+fn chunked_rows(df: &DataFrame, idx: &[usize]) -> u64 {
+    let column = |name| df.column(name).unwrap();
+    let a = column("a").u32().unwrap();
+    let b = column("b").i64().unwrap();
+    let c = column("c").f64().unwrap();
+    let d = column("d").bool().unwrap();
+    let e = column("e").str().unwrap();
+    let f = column("f").u8().unwrap();
+    let g = column("g").i32().unwrap();
+    let h = column("h").f32().unwrap();
+    let i = column("i").u64().unwrap();
+    idx.iter().fold(0, |acc, &idx| {
+        checksum(
+            acc,
+            [
+                a.get(idx).unwrap() as u64,
+                b.get(idx).unwrap() as u64,
+                c.get(idx).unwrap() as u64,
+                d.get(idx).unwrap() as u64,
+                e.get(idx).unwrap().len() as u64,
+                f.get(idx).unwrap() as u64,
+                g.get(idx).unwrap() as u64,
+                h.get(idx).unwrap() as u64,
+                i.get(idx).unwrap_or_default(),
+            ],
+        )
+    })
+}
+
+/// The fastest of a few runs of `f`.
+fn time(f: impl Fn() -> u64) -> Duration {
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    for chunks in [1, 16, 256] {
+        let df = frame(chunks);
+        let idx = lookups(df.height());
+        assert_eq!(view_rows(&df, &idx), chunked_rows(&df, &idx));
+
+        let view = time(|| view_rows(&df, &idx));
+        let chunked = time(|| chunked_rows(&df, &idx));
+        let per_row = |d: Duration| d.as_nanos() as f64 / LOOKUPS as f64;
+        println!(
+            "{chunks:>3} chunk(s): view {:>6.1} ns/row, ChunkedArray::get {:>6.1} ns/row ({:.2}x)",
+            per_row(view),
+            per_row(chunked),
+            chunked.as_secs_f64() / view.as_secs_f64(),
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Reading string fields through a view compared with polars' own iterators and with copying.
+//!
+//! Views return `&str` values that borrow from the string view buffers of each column without
+//! validating UTF-8 again, so reading rows should stay close to iterating over the columns with
+//! polars, and well ahead of copying each string into a `String`.
+//!
+//! Run with `cargo bench --bench string_access`.
+
+use colback::ColbackView;
+use polars::prelude::*;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+#[derive(ColbackView)]
+#[allow(dead_code)]
+struct Strings {
+    id: u32,
+    short: String,
+    long: String,
+    mixed: String,
+    #[polars(null = "option")]
+    sparse: Option<String>,
+}
+
+const ROWS: usize = 1_000_000;
+const ITERATIONS: u32 = 5;
+
+/// A frame with the columns of [Strings], mixing strings that are inlined in their views with
+/// ones that live in data buffers.
+fn frame() -> DataFrame {
+    let idx = (0..ROWS).collect::<Vec<_>>();
+    df! [
+        "id" => idx.iter().map(|&i| i as u32).collect::<Vec<_>>(),
+        "short" => idx.iter().map(|&i| format!("s{}", i % 1000)).collect::<Vec<_>>(),
+        "long" => idx.iter().map(|&i| format!("a longer string value number {i}")).collect::<Vec<_>>(),
+        "mixed" => idx.iter().map(|&i| "x".repeat(i % 24)).collect::<Vec<_>>(),
+        "sparse" => idx.iter().map(|&i| (i % 3 == 0).then(|| format!("sparse {i}"))).collect::<Vec<_>>(),
+    ]
+    .unwrap()
+}
+
+/// Sum the lengths of the string fields of every row of a view.
+fn view_rows(df: &DataFrame) -> usize {
+    let view = Strings::view(df).unwrap();
+    view.iter()
+        .map(|row| {
+            let row = row.unwrap();
+            row.short.len() + row.long.len() + row.mixed.len() + row.sparse.map_or(0, str::len)
+        })
+        .sum()
+}
+
+/// Sum the lengths of the string columns with polars' iterators.
+fn chunked_rows(df: &DataFrame) -> usize {
+    let column = |name| df.column(name).unwrap().str().unwrap();
+    let (short, long) = (column("short"), column("long"));
+    let (mixed, sparse) = (column("mixed"), column("sparse"));
+    short
+        .iter()
+        .zip(long.iter())
+        .zip(mixed.iter())
+        .zip(sparse.iter())
+        .map(|(((short, long), mixed), sparse)| {
+            short.unwrap().len()
+                + long.unwrap().len()
+                + mixed.unwrap().len()
+                + sparse.map_or(0, str::len)
+        })
+        .sum()
+}
+
+/// Sum the lengths of the string fields after copying each of them, as owned rows would.
+fn copied_rows(df: &DataFrame) -> usize {
+    let view = Strings::view(df).unwrap();
+    view.iter()
+        .map(|row| {
+            let row = row.unwrap();
+            let fields = [
+                row.short.to_string(),
+                row.long.to_string(),
+                row.mixed.to_string(),
+                row.sparse.map(str::to_string).unwrap_or_default(),
+            ];
+            black_box(fields).iter().map(String::len).sum::<usize>()
+        })
+        .sum()
+}
+
+/// The fastest of a few runs of `f`.
+fn time(f: impl Fn() -> usize) -> Duration {
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let df = frame();
+    assert_eq!(view_rows(&df), chunked_rows(&df));
+    assert_eq!(view_rows(&df), copied_rows(&df));
+
+    let per_row = |d: Duration| d.as_nanos() as f64 / ROWS as f64;
+    for (name, duration) in [
+        ("view", time(|| view_rows(&df))),
+        ("polars iterators", time(|| chunked_rows(&df))),
+        ("copied strings", time(|| copied_rows(&df))),
+    ] {
+        println!("{name:>16}: {:>6.1} ns/row", per_row(duration));
+    }
+}
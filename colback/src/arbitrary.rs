@@ -0,0 +1,72 @@
+//! Strategies for property tests over generated rows and dataframes.
+
+use crate::{ColbackBuilder, ColbackCategory, EpochValue, naive_datetime, to_epoch};
+use chrono::NaiveDateTime;
+use polars::prelude::{DataFrame, TimeUnit};
+use proptest::{
+    arbitrary::{Arbitrary, any},
+    collection::vec,
+    sample::select,
+    strategy::Strategy,
+};
+use std::{fmt::Debug, ops::Range};
+
+/// A strategy for the variants of a category field.
+#[doc(hidden)]
+pub fn arb_category<T: ColbackCategory>() -> impl Strategy<Value = T> {
+    select(T::VARIANTS)
+}
+
+/// A strategy for datetimes that can be stored in a column with the given time unit.
+///
+/// Datetimes are generated from nanosecond timestamps truncated to the unit, so they are within
+/// the range that every unit can represent and survive a round trip through a column unchanged.
+#[doc(hidden)]
+pub fn arb_naive_datetime(unit: TimeUnit) -> impl Strategy<Value = NaiveDateTime> {
+    let per_unit = nanos_per_unit(unit);
+    any::<i64>().prop_map(move |ns| {
+        naive_datetime(ns / per_unit, unit).expect("nanosecond timestamps are in range")
+    })
+}
+
+/// A strategy for values of `epoch` fields that can be stored in a column of `V` integers with
+/// the given time unit.
+///
+/// Values are generated from nanosecond counts truncated to the unit, as in
+/// [arb_naive_datetime], and values that the field's type or the column can't hold are rejected.
+#[doc(hidden)]
+pub fn arb_epoch<T, V>(unit: TimeUnit) -> impl Strategy<Value = T>
+where
+    T: EpochValue + Copy + Debug,
+    V: TryFrom<i64>,
+{
+    let per_unit = nanos_per_unit(unit);
+    any::<i64>().prop_filter_map("out of range for the column", move |ns| {
+        let value = T::from_epoch(ns / per_unit, unit)?;
+        to_epoch::<T, V>(value, unit).map(|_| value)
+    })
+}
+
+/// The number of nanoseconds in a time unit.
+fn nanos_per_unit(unit: TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Nanoseconds => 1,
+        TimeUnit::Microseconds => 1_000,
+        TimeUnit::Milliseconds => 1_000_000,
+    }
+}
+
+/// A strategy for dataframes with a struct's columns, built from arbitrary owned rows.
+pub(crate) fn arb_df<T>(rows: Range<usize>) -> impl Strategy<Value = DataFrame>
+where
+    T: ColbackBuilder,
+    T::Owned: Arbitrary,
+{
+    vec(any::<T::Owned>(), rows).prop_map(|rows| {
+        let mut builder = T::builder(rows.len());
+        for row in &rows {
+            T::push_row(&mut builder, row).expect("generated rows fit in the struct's columns");
+        }
+        T::finish_builder(builder).expect("generated rows have the struct's dtypes")
+    })
+}
@@ -0,0 +1,43 @@
+//! Interop with serde_arrow's schemas and record conversion.
+
+use crate::Result;
+use arrow::{datatypes::FieldRef, ffi::FFI_ArrowSchema, record_batch::RecordBatch};
+use polars::prelude::{CompatLevel, PolarsError, Schema};
+use serde::Serialize;
+use std::{mem::ManuallyDrop, ptr, sync::Arc};
+
+// Both crates implement the same C struct, so they must agree on its layout
+const _: () = assert!(size_of::<FFI_ArrowSchema>() == size_of::<polars_arrow::ffi::ArrowSchema>());
+
+/// Convert a schema into arrow-rs fields, as used by serde_arrow.
+///
+/// The fields have the arrow types that polars uses for the schema's dtypes, so arrays built for
+/// them convert back into columns with the same dtypes.
+pub(crate) fn fields(schema: &Schema) -> Result<Vec<FieldRef>> {
+    schema
+        .iter()
+        .map(|(name, dtype)| {
+            let field = dtype.to_arrow_field(name.clone(), CompatLevel::newest());
+            // The struct is moved to arrow-rs, so polars must not release it
+            let ffi_schema = ManuallyDrop::new(polars_arrow::ffi::export_field_to_c(&field));
+            // SAFETY: polars' `ArrowSchema` and `FFI_ArrowSchema` are both the `#[repr(C)]`
+            // `ArrowSchema` struct of the Arrow C data interface, so reading one as the other is
+            // sound. The struct is read exactly once, and since polars never drops it, arrow-rs
+            // is the only owner and releases it when dropped.
+            let ffi_schema =
+                unsafe { ptr::read(ptr::from_ref(&*ffi_schema).cast::<FFI_ArrowSchema>()) };
+            let field = arrow::datatypes::Field::try_from(&ffi_schema)
+                .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+            Ok(Arc::new(field))
+        })
+        .collect()
+}
+
+/// Convert serializable records into a record batch with the given fields using serde_arrow.
+pub(crate) fn to_record_batch<R: Serialize>(
+    fields: &[FieldRef],
+    rows: &[R],
+) -> Result<RecordBatch> {
+    serde_arrow::to_record_batch(fields, &rows)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()).into())
+}
@@ -0,0 +1,156 @@
+//! Typed as-of joins for aligning time series.
+
+use crate::{ColbackKey, ColbackView, ColumnField, KeyOf, Result, temporal::duration_in_unit};
+use chrono::TimeDelta;
+use polars::prelude::{
+    AnyValue, AsOfOptions, AsofStrategy, DataFrame, DataFrameJoinOps, DataType, JoinArgs, JoinType,
+    PolarsError, Scalar,
+};
+
+/// An as-of join between the dataframes backing two structs.
+///
+/// Each row of the left dataframe is matched with the nearest row of the right dataframe by the
+/// `on` fields, which are typically datetime fields. Both dataframes must be sorted by their `on`
+/// fields. The joined frame contains the columns of both sides and can be viewed with a struct
+/// that combines their fields.
+///
+/// ```rust
+/// use colback::{AsofJoin, ColbackKey, ColbackView};
+/// use chrono::{NaiveDateTime, TimeDelta};
+/// # use polars::{df, prelude::*};
+///
+/// #[derive(ColbackView)]
+/// struct Trade {
+///     symbol: String,
+///     ts: NaiveDateTime,
+/// }
+///
+/// #[derive(ColbackView)]
+/// struct Quote {
+///     symbol: String,
+///     #[polars(name = "quote_ts")]
+///     ts: NaiveDateTime,
+///     bid: f64,
+/// }
+///
+/// #[derive(ColbackKey)]
+/// #[polars(key_of(Trade, Quote))]
+/// struct Symbol {
+///     symbol: String,
+/// }
+///
+/// # let ts = |s: i64| chrono::DateTime::from_timestamp(s, 0).unwrap().naive_utc();
+/// # let trades = df!["symbol" => ["a"], "ts" => [ts(10)]].unwrap();
+/// # let quotes = df!["symbol" => ["a"], "quote_ts" => [ts(8)], "bid" => [1.0]].unwrap();
+/// let joined = AsofJoin::<Trade, Quote>::new(TradeField::Ts, QuoteField::Ts)
+///     .by::<Symbol>()
+///     .tolerance(TimeDelta::seconds(5))
+///     .join(&trades, &quotes)
+///     .unwrap();
+/// assert_eq!(joined.column("bid").unwrap().f64().unwrap().get(0), Some(1.0));
+/// ```
+pub struct AsofJoin<L: ColbackView, R: ColbackView> {
+    left_on: L::Field,
+    right_on: R::Field,
+    by: Option<&'static [&'static str]>,
+    validate_by: Option<fn(&DataFrame) -> Result<()>>,
+    strategy: AsofStrategy,
+    tolerance: Option<TimeDelta>,
+    allow_exact_matches: bool,
+}
+
+impl<L: ColbackView, R: ColbackView> AsofJoin<L, R> {
+    /// Create an as-of join matching rows by the given fields of each side.
+    ///
+    /// This defaults to a backward search (the last right row whose key is less than or equal to
+    /// the left key) without any tolerance.
+    pub fn new(left_on: L::Field, right_on: R::Field) -> Self {
+        Self {
+            left_on,
+            right_on,
+            by: None,
+            validate_by: None,
+            strategy: AsofStrategy::Backward,
+            tolerance: None,
+            allow_exact_matches: true,
+        }
+    }
+
+    /// Only match rows that have equal values for the fields of a key struct.
+    pub fn by<K>(mut self) -> Self
+    where
+        K: ColbackKey,
+        L: KeyOf<K>,
+        R: KeyOf<K>,
+    {
+        self.by = Some(K::column_names());
+        self.validate_by = Some(|df| K::view(df).map(|_| ()));
+        self
+    }
+
+    /// Set the direction used to search for the nearest match.
+    pub fn strategy(mut self, strategy: AsofStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Only match rows whose datetime keys are at most this far apart.
+    pub fn tolerance(mut self, tolerance: TimeDelta) -> Self {
+        self.tolerance = Some(tolerance);
+        self
+    }
+
+    /// Whether rows with exactly equal keys can be matched. Defaults to `true`.
+    pub fn allow_exact_matches(mut self, allow: bool) -> Self {
+        self.allow_exact_matches = allow;
+        self
+    }
+
+    /// Join the dataframes.
+    ///
+    /// # Errors
+    ///
+    /// This errors if either dataframe fails validation (see [ColbackView::view]), if a tolerance
+    /// is set but the left `on` field isn't a datetime, or if the join itself fails (for example
+    /// because a dataframe isn't sorted by its `on` field).
+    pub fn join(&self, left: &DataFrame, right: &DataFrame) -> Result<DataFrame> {
+        L::view(left)?;
+        R::view(right)?;
+        if let Some(validate_by) = self.validate_by {
+            validate_by(left)?;
+            validate_by(right)?;
+        }
+
+        let left_on = self.left_on.column_name();
+        let tolerance = self
+            .tolerance
+            .map(|tolerance| match left.column(left_on)?.dtype() {
+                DataType::Datetime(unit, _) => duration_in_unit(tolerance, *unit)
+                    .map(|v| Scalar::new(DataType::Int64, AnyValue::Int64(v)))
+                    .ok_or_else(|| PolarsError::InvalidOperation("tolerance overflows".into())),
+                dtype => Err(PolarsError::InvalidOperation(
+                    format!("a tolerance requires a datetime key, got {dtype}").into(),
+                )),
+            })
+            .transpose()?;
+        let by = self
+            .by
+            .map(|names| names.iter().map(|&name| name.into()).collect());
+        let options = AsOfOptions {
+            strategy: self.strategy,
+            tolerance,
+            tolerance_str: None,
+            left_by: by.clone(),
+            right_by: by,
+            allow_eq: self.allow_exact_matches,
+            check_sortedness: true,
+        };
+        Ok(left.join(
+            right,
+            [left_on],
+            [self.right_on.column_name()],
+            JoinArgs::new(JoinType::AsOf(Box::new(options))),
+            None,
+        )?)
+    }
+}
@@ -0,0 +1,24 @@
+//! Typed batches of DataFusion record batch streams.
+
+use crate::{ColbackView, Result, record_batch};
+use arrow::record_batch::RecordBatch;
+use datafusion::execution::SendableRecordBatchStream;
+use futures::{Stream, StreamExt};
+use polars::prelude::{DataFrame, PolarsError};
+
+/// Convert a DataFusion record batch stream into dataframes projected to a struct's columns.
+///
+/// The stream's schema is validated against the struct once, before any batch is read.
+pub(crate) fn view_stream<T: ColbackView>(
+    stream: SendableRecordBatchStream,
+) -> Result<impl Stream<Item = Result<DataFrame>> + Send + 'static> {
+    let empty = record_batch::to_dataframe(&RecordBatch::new_empty(stream.schema()))?;
+    T::view(&empty)?;
+
+    let columns = T::column_names();
+    Ok(stream.map(move |batch| {
+        let batch = batch.map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+        let df = record_batch::to_dataframe(&batch)?;
+        Ok(df.select(columns.iter().copied())?)
+    }))
+}
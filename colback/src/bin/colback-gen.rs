@@ -0,0 +1,30 @@
+//! Print a `ColbackView` struct definition for the schema of a parquet, CSV, or IPC file.
+//!
+//! ```text
+//! colback-gen <path> [StructName]
+//! ```
+
+use colback::struct_definition;
+use colback_codegen::file_columns;
+use std::{path::Path, process::ExitCode};
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(path) = args.next() else {
+        eprintln!("usage: colback-gen <path> [StructName]");
+        return ExitCode::FAILURE;
+    };
+    let name = args.next().unwrap_or_else(|| "Row".to_string());
+
+    match file_columns(Path::new(&path)) {
+        Ok(columns) => {
+            let columns = columns.iter().map(|(c, d, n)| (c.as_str(), d, *n));
+            print!("{}", struct_definition(&name, columns));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("failed to read the schema of {path}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
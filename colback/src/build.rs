@@ -0,0 +1,195 @@
+//! Building dataframes from owned rows.
+
+use crate::{ColbackView, Result};
+use polars::frame::DataFrame;
+
+/// Conversion of a collection of owned rows into a dataframe.
+///
+/// This is implemented for anything that iterates over structs deriving [ColbackView], such as a
+/// `Vec` of rows or a mapped iterator. Each field is collected into its own column using
+/// [ColbackView::to_dataframe], so the output frame has the dtypes that the struct's view
+/// expects.
+///
+/// ```rust
+/// use colback::{ColbackView, IntoDataFrame};
+///
+/// #[derive(ColbackView)]
+/// struct MyRow {
+///     col_a: u32,
+///     col_b: String,
+/// }
+///
+/// let rows = vec![
+///     MyRow { col_a: 0, col_b: "x".to_string() },
+///     MyRow { col_a: 1, col_b: "y".to_string() },
+/// ];
+/// let df = rows.into_dataframe().unwrap();
+/// assert_eq!(df.get_column_names(), vec!["col_a", "col_b"]);
+/// assert_eq!(df.height(), 2);
+/// ```
+pub trait IntoDataFrame {
+    /// Collect the rows into a dataframe.
+    ///
+    /// # Errors
+    ///
+    /// This errors if a column can't be built with the dtype that the struct expects.
+    fn into_dataframe(self) -> Result<DataFrame>;
+}
+
+impl<I> IntoDataFrame for I
+where
+    I: IntoIterator,
+    I::Item: ColbackView,
+{
+    fn into_dataframe(self) -> Result<DataFrame> {
+        <I::Item as ColbackView>::to_dataframe(self)
+    }
+}
+
+/// Build a dataframe from struct literals of a struct deriving [ColbackView].
+///
+/// This is a typed alternative to polars' `df!` macro for declaring fixtures: the field names
+/// are checked by the compiler, and the columns have the names and dtypes that the struct's view
+/// expects. Like `df!`, this evaluates to a [Result](crate::Result).
+///
+/// ```rust
+/// use colback::{ColbackView, df_of};
+///
+/// #[derive(ColbackView)]
+/// struct MyRow {
+///     col_a: u32,
+///     col_b: bool,
+/// }
+///
+/// let df = df_of![
+///     MyRow { col_a: 1, col_b: true },
+///     MyRow { col_a: 2, col_b: false },
+/// ]
+/// .unwrap();
+/// let view = MyRow::view(&df).unwrap();
+/// assert_eq!(view.get(1).unwrap().col_a, 2);
+/// ```
+#[macro_export]
+macro_rules! df_of {
+    ($($row:expr),+ $(,)?) => {
+        $crate::IntoDataFrame::into_dataframe([$($row),+])
+    };
+}
+
+/// Buffers for the columns of a struct deriving [ColbackView], which owned rows are pushed into
+/// field by field.
+///
+/// This is implemented by the buffer type generated by the derive macro, see
+/// [ColbackView::Columns].
+pub trait ColumnBuffers<T>: Default {
+    /// Reserve capacity for at least `additional` more rows in every column.
+    fn reserve(&mut self, additional: usize);
+
+    /// Push the fields of a row onto the end of their columns.
+    fn push(&mut self, row: T);
+
+    /// Build a dataframe from the buffered columns.
+    ///
+    /// # Errors
+    ///
+    /// This errors if a column can't be built with the dtype that the struct expects.
+    fn finish(self) -> Result<DataFrame>;
+}
+
+/// An accumulator of owned rows that are stored column by column.
+///
+/// Rows are split into their fields as they're pushed, so producers can stream rows into a frame
+/// without collecting them into an intermediate `Vec` of structs first. This implements
+/// [FromIterator] and [Extend], and each column grows with amortized reallocation.
+///
+/// ```rust
+/// use colback::{ColbackFrame, ColbackView};
+///
+/// #[derive(ColbackView)]
+/// struct MyRow {
+///     col_a: u32,
+/// }
+///
+/// let mut frame: ColbackFrame<MyRow> = (0..2).map(|col_a| MyRow { col_a }).collect();
+/// frame.push(MyRow { col_a: 2 });
+/// let df = frame.finish().unwrap();
+/// assert_eq!(df.height(), 3);
+/// ```
+pub struct ColbackFrame<T: ColbackView> {
+    columns: T::Columns,
+    len: usize,
+}
+
+impl<T: ColbackView> ColbackFrame<T> {
+    /// Create an empty accumulator.
+    pub fn new() -> Self {
+        Self {
+            columns: T::Columns::default(),
+            len: 0,
+        }
+    }
+
+    /// Create an empty accumulator with room for `capacity` rows in every column.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut frame = Self::new();
+        frame.reserve(capacity);
+        frame
+    }
+
+    /// Reserve capacity for at least `additional` more rows.
+    pub fn reserve(&mut self, additional: usize) {
+        self.columns.reserve(additional);
+    }
+
+    /// Push a row onto the end of the frame.
+    pub fn push(&mut self, row: T) {
+        self.columns.push(row);
+        self.len += 1;
+    }
+
+    /// The number of rows that have been pushed.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no rows have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Build a dataframe from the accumulated rows.
+    ///
+    /// The columns have the dtypes that [ColbackView::view] expects, so the result can be viewed
+    /// as the struct.
+    ///
+    /// # Errors
+    ///
+    /// This errors if a column can't be built with the dtype that the struct expects.
+    pub fn finish(self) -> Result<DataFrame> {
+        self.columns.finish()
+    }
+}
+
+impl<T: ColbackView> Default for ColbackFrame<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ColbackView> Extend<T> for ColbackFrame<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for row in iter {
+            self.push(row);
+        }
+    }
+}
+
+impl<T: ColbackView> FromIterator<T> for ColbackFrame<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut frame = Self::new();
+        frame.extend(iter);
+        frame
+    }
+}
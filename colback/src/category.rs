@@ -0,0 +1,114 @@
+//! Fields whose values are one of a fixed set of categories.
+
+use crate::ChunkIndex;
+use polars::prelude::{
+    CategoricalMapping, CategoricalPhysical, Column, DataType, StringType, UInt8Type, UInt16Type,
+    UInt32Type,
+};
+use std::fmt::Debug;
+
+/// A fieldless enum whose variants are the categories of a string, categorical or enum column.
+///
+/// This is implemented with the `ColbackCategory` derive macro, which names each category after
+/// its variant unless the variant has a `#[polars(name = "...")]` attribute. Fields of the enum's
+/// type are read from the column with the `category` field attribute, whose `unknown` policy
+/// decides what happens to values that aren't one of the categories. Categorical and enum
+/// columns are read through their mapping from categories to names, and rows are written as
+/// string columns of category names.
+///
+/// ```rust
+/// use colback::{ColbackCategory, ColbackError, ColbackView};
+/// use polars::df;
+///
+/// #[derive(ColbackCategory, Clone, Copy, Debug, PartialEq)]
+/// enum Color {
+///     Red,
+///     #[polars(name = "blue")]
+///     Blue,
+///     Other,
+/// }
+///
+/// #[derive(ColbackView)]
+/// struct MyRow {
+///     #[polars(category)]
+///     strict: Color,
+///     #[polars(category, unknown = "other(Other)")]
+///     tolerant: Color,
+/// }
+///
+/// let df = df!["strict" => ["Red", "green"], "tolerant" => ["blue", "green"]].unwrap();
+/// let view = MyRow::view(&df).unwrap();
+/// assert_eq!(view.get(0).unwrap().tolerant, Color::Blue);
+/// assert!(matches!(view.get(1), Err(ColbackError::UnknownCategory { .. })));
+/// ```
+pub trait ColbackCategory: Copy + Debug + PartialEq + 'static {
+    /// Every variant of the enum, in declaration order.
+    const VARIANTS: &'static [Self];
+
+    /// The name of the variant's category.
+    fn category(&self) -> &'static str;
+
+    /// The variant of a category, or `None` if the enum has no variant for it.
+    fn from_category(name: &str) -> Option<Self> {
+        Self::VARIANTS
+            .iter()
+            .copied()
+            .find(|v| v.category() == name)
+    }
+}
+
+/// The category names of the column backing a category field, along with its cached chunk layout.
+///
+/// String columns hold the names themselves, while the rows of categorical and enum columns are
+/// categories that are looked up in the column's mapping to their names.
+#[derive(Clone)]
+pub enum CategoryIndex<'a> {
+    /// A string column of category names.
+    String(ChunkIndex<'a, StringType>),
+    /// The categories of a categorical or enum column with up to 2^8 categories.
+    Cat8(ChunkIndex<'a, UInt8Type>, &'a CategoricalMapping),
+    /// The categories of a categorical or enum column with up to 2^16 categories.
+    Cat16(ChunkIndex<'a, UInt16Type>, &'a CategoricalMapping),
+    /// The categories of a categorical or enum column with up to 2^32 categories.
+    Cat32(ChunkIndex<'a, UInt32Type>, &'a CategoricalMapping),
+}
+
+impl<'a> CategoryIndex<'a> {
+    /// Cache the chunk layout of a column of category names.
+    ///
+    /// Returns `None` if the column isn't a string, categorical or enum column.
+    pub fn new(column: &'a Column) -> Option<Self> {
+        let physical = match column.dtype() {
+            DataType::String => return Some(Self::String(ChunkIndex::new(column.str().ok()?))),
+            DataType::Categorical(..) | DataType::Enum(..) => column.dtype().cat_physical().ok()?,
+            _ => return None,
+        };
+        Some(match physical {
+            CategoricalPhysical::U8 => {
+                let ca = column.cat8().ok()?;
+                Self::Cat8(ChunkIndex::new(ca.physical()), ca.get_mapping())
+            }
+            CategoricalPhysical::U16 => {
+                let ca = column.cat16().ok()?;
+                Self::Cat16(ChunkIndex::new(ca.physical()), ca.get_mapping())
+            }
+            CategoricalPhysical::U32 => {
+                let ca = column.cat32().ok()?;
+                Self::Cat32(ChunkIndex::new(ca.physical()), ca.get_mapping())
+            }
+        })
+    }
+
+    /// Get the category name at a given row.
+    ///
+    /// Returns `None` if the value is null or if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, idx: usize) -> Option<&'a str> {
+        match self {
+            Self::String(index) => index.get(idx),
+            Self::Cat8(index, mapping) => mapping.cat_to_str(index.get(idx)?.into()),
+            Self::Cat16(index, mapping) => mapping.cat_to_str(index.get(idx)?.into()),
+            Self::Cat32(index, mapping) => mapping.cat_to_str(index.get(idx)?),
+        }
+    }
+}
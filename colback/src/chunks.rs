@@ -0,0 +1,127 @@
+//! Cached chunk layouts for fast random access into columns.
+
+use polars::prelude::{ChunkedArray, PolarsDataType, StaticArray};
+
+/// A typed column along with its cached chunk layout.
+///
+/// [ChunkedArray::get] has to resolve which chunk an index belongs to on every call, which gets
+/// slow for frames with many chunks. This caches the downcast chunk arrays and their cumulative
+/// row offsets when a view is constructed, so a lookup is O(1) for single chunk columns and
+/// O(log chunks) otherwise.
+pub struct ChunkIndex<'a, T: PolarsDataType> {
+    ca: &'a ChunkedArray<T>,
+    chunks: Vec<&'a T::Array>,
+    /// The row index of the first element of each chunk.
+    offsets: Vec<usize>,
+}
+
+impl<'a, T: PolarsDataType> ChunkIndex<'a, T> {
+    /// Cache the chunk layout of a chunked array.
+    pub fn new(ca: &'a ChunkedArray<T>) -> Self {
+        let mut offsets = Vec::with_capacity(ca.chunks().len());
+        let mut total = 0;
+        for len in ca.chunk_lengths() {
+            offsets.push(total);
+            total += len;
+        }
+        let chunks = ca.downcast_iter().collect();
+        Self {
+            ca,
+            chunks,
+            offsets,
+        }
+    }
+
+    /// The chunked array backing this column.
+    pub fn chunked(&self) -> &'a ChunkedArray<T> {
+        self.ca
+    }
+
+    /// The downcast arrow arrays of each chunk, in row order.
+    ///
+    /// These can be read directly, e.g. through the values and validity of a primitive array.
+    pub fn chunks(&self) -> &[&'a T::Array] {
+        &self.chunks
+    }
+
+    /// Get the chunk that a row lives in, along with the row's index within that chunk.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    #[inline]
+    pub fn chunk_at(&self, idx: usize) -> Option<(&'a T::Array, usize)> {
+        if idx >= self.ca.len() {
+            return None;
+        }
+        self.locate(idx)
+    }
+
+    /// Get the value at a given row.
+    ///
+    /// Returns `None` if the value is null or if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, idx: usize) -> Option<T::Physical<'a>> {
+        let (chunk, local_idx) = self.locate(idx)?;
+        chunk.get(local_idx)
+    }
+
+    /// Find the chunk that a row belongs to along with the row's index within that chunk.
+    ///
+    /// The index within the chunk isn't bounds checked.
+    #[inline]
+    fn locate(&self, idx: usize) -> Option<(&'a T::Array, usize)> {
+        if let [chunk] = self.chunks.as_slice() {
+            return Some((*chunk, idx));
+        }
+        let chunk_idx = self.offsets.partition_point(|&o| o <= idx).checked_sub(1)?;
+        Some((self.chunks[chunk_idx], idx - self.offsets[chunk_idx]))
+    }
+}
+
+// Deriving `Clone` would require `T: Clone`, but only the references are cloned
+impl<T: PolarsDataType> Clone for ChunkIndex<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            ca: self.ca,
+            chunks: self.chunks.clone(),
+            offsets: self.offsets.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::{NewChunkedArray, UInt32Chunked};
+
+    #[test]
+    fn test_multi_chunk_get() {
+        let mut ca = UInt32Chunked::from_slice("a".into(), &[0, 1]);
+        ca.append(&UInt32Chunked::from_slice("a".into(), &[]))
+            .unwrap();
+        ca.append(&UInt32Chunked::from_slice_options(
+            "a".into(),
+            &[Some(2), None],
+        ))
+        .unwrap();
+        let index = ChunkIndex::new(&ca);
+
+        let values: Vec<_> = (0..5).map(|i| index.get(i)).collect();
+        assert_eq!(values, vec![Some(0), Some(1), Some(2), None, None]);
+    }
+
+    #[test]
+    fn test_chunk_at() {
+        let mut ca = UInt32Chunked::from_slice("a".into(), &[0, 1]);
+        ca.append(&UInt32Chunked::from_slice("a".into(), &[2, 3, 4]))
+            .unwrap();
+        let index = ChunkIndex::new(&ca);
+        assert_eq!(index.chunks().len(), 2);
+
+        let (chunk, local_idx) = index.chunk_at(3).unwrap();
+        assert_eq!((chunk.values()[local_idx], local_idx), (3, 1));
+        assert!(index.chunk_at(5).is_none());
+
+        let single = UInt32Chunked::from_slice("a".into(), &[0]);
+        assert!(ChunkIndex::new(&single).chunk_at(1).is_none());
+    }
+}
@@ -0,0 +1,72 @@
+//! Vectorized checks of column constraints when views are created.
+
+use crate::{ColbackError, Result};
+use polars::prelude::{DataFrame, Expr, IntoLazy};
+use std::fmt;
+
+/// The number of violating row indices kept as examples for each constraint.
+const MAX_EXAMPLES: usize = 5;
+
+/// A constraint of a struct that some rows of a dataframe don't satisfy.
+///
+/// Constraints are declared with the `check`, `min` and `max` attributes of `ColbackView`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    /// A description of the constraint, such as `price >= 0`.
+    pub constraint: String,
+    /// The number of rows that violate the constraint.
+    pub count: usize,
+    /// The indices of the first few rows that violate the constraint, in row order.
+    pub examples: Vec<usize>,
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} failed for {} row(s), e.g. rows {:?}",
+            self.constraint, self.count, self.examples
+        )
+    }
+}
+
+/// Evaluate the constraints of a struct over a dataframe, reporting every violated constraint.
+///
+/// Each constraint is a description along with an expression that evaluates to a boolean
+/// column. The expressions are evaluated together in a single query, and rows where they're null
+/// pass.
+#[doc(hidden)]
+pub fn check_constraints(df: &DataFrame, constraints: Vec<(String, Expr)>) -> Result<()> {
+    let exprs: Vec<_> = constraints
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, expr))| expr.clone().alias(format!("constraint_{idx}")))
+        .collect();
+    let results = df.clone().lazy().select(exprs).collect()?;
+
+    let mut violations = Vec::new();
+    for ((constraint, _), column) in constraints.into_iter().zip(results.get_columns()) {
+        let mut count = 0;
+        let mut examples = Vec::new();
+        for (idx, passed) in column.bool()?.iter().enumerate() {
+            if passed == Some(false) {
+                count += 1;
+                if examples.len() < MAX_EXAMPLES {
+                    examples.push(idx);
+                }
+            }
+        }
+        if count > 0 {
+            violations.push(ConstraintViolation {
+                constraint,
+                count,
+                examples,
+            });
+        }
+    }
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(ColbackError::ConstraintViolations(violations))
+    }
+}
@@ -0,0 +1,48 @@
+//! Views over several related dataframes at once.
+
+use crate::{ColbackError, ColbackView, Result};
+use polars::frame::DataFrame;
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+/// Trait for a struct whose fields are [ColbackView] structs, each bound to a named dataframe.
+///
+/// This is implemented by the `ColbackContext` derive macro, which generates a struct holding a
+/// view for each field.
+pub trait ColbackContext {
+    /// A struct with a view for each of the context's fields.
+    type View<'a>;
+
+    /// The names of the dataframes backing the context, in field order.
+    fn frame_names() -> &'static [&'static str];
+
+    /// Look up and view every dataframe of the context.
+    ///
+    /// # Errors
+    ///
+    /// Every dataframe is validated before returning, and all of the failures are collected into
+    /// a [ColbackError::InvalidContext]. Dataframes that aren't in `frames` fail with
+    /// [ColbackError::MissingFrame].
+    fn view<K>(frames: &HashMap<K, DataFrame>) -> Result<Self::View<'_>>
+    where
+        K: Borrow<str> + Eq + Hash;
+}
+
+/// View a named dataframe as part of a context, recording any failure.
+///
+/// This is used by the code generated for `ColbackContext`.
+#[doc(hidden)]
+pub fn view_frame<'a, T, K>(
+    frames: &'a HashMap<K, DataFrame>,
+    name: &str,
+    errors: &mut Vec<(String, ColbackError)>,
+) -> Option<T::View<'a>>
+where
+    T: ColbackView + 'a,
+    K: Borrow<str> + Eq + Hash,
+{
+    let result = match frames.get(name) {
+        Some(df) => T::view(df),
+        None => Err(ColbackError::MissingFrame(name.to_string())),
+    };
+    result.map_err(|e| errors.push((name.to_string(), e))).ok()
+}
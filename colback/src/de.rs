@@ -0,0 +1,48 @@
+//! Deserialization of owned rows into dataframes.
+
+use crate::{ColbackBuilder, ColbackError, Result};
+use polars::prelude::DataFrame;
+use serde::de::DeserializeOwned;
+use std::{fmt::Display, io::Read};
+
+/// Build a dataframe from deserialized owned rows.
+///
+/// Each row is pushed onto the struct's builder as soon as it's deserialized, so the rows are
+/// never collected in between. This works with any serde format whose deserializer can be
+/// iterated over, such as `serde_json::StreamDeserializer`.
+///
+/// # Errors
+///
+/// This errors with [ColbackError::Deserialize] for the first row that fails to deserialize, or
+/// if a column can't be built (see [ColbackBuilder::finish_builder]).
+pub fn from_serde_iter<T, I, E>(rows: I) -> Result<DataFrame>
+where
+    T: ColbackBuilder,
+    I: IntoIterator<Item = std::result::Result<T::Owned, E>>,
+    E: Display,
+{
+    let rows = rows.into_iter();
+    let mut builder = T::builder(rows.size_hint().0);
+    for (idx, row) in rows.enumerate() {
+        let row = row.map_err(|e| ColbackError::Deserialize {
+            idx,
+            msg: e.to_string(),
+        })?;
+        T::push_row(&mut builder, &row)?;
+    }
+    T::finish_builder(builder)
+}
+
+/// Build a dataframe from newline delimited JSON, with one object per row keyed by the struct's
+/// column names.
+///
+/// # Errors
+///
+/// This errors under the same conditions as [from_serde_iter].
+pub fn from_json_lines<T>(reader: impl Read) -> Result<DataFrame>
+where
+    T: ColbackBuilder,
+    T::Owned: DeserializeOwned,
+{
+    from_serde_iter::<T, _, _>(serde_json::Deserializer::from_reader(reader).into_iter())
+}
@@ -0,0 +1,38 @@
+//! Debug output for generated views.
+
+use polars::prelude::DataFrame;
+use std::fmt;
+
+/// The dtypes of a view's columns, keyed by column name.
+struct Dtypes<'a> {
+    df: &'a DataFrame,
+    columns: &'a [&'static str],
+}
+
+impl fmt::Debug for Dtypes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.columns.iter().filter_map(|&name| {
+                self.df
+                    .column(name)
+                    .ok()
+                    .map(|column| (name, column.dtype()))
+            }))
+            .finish()
+    }
+}
+
+/// Format a view as the shape of its dataframe and the dtypes of the struct's columns, rather
+/// than the values of its arrays.
+#[doc(hidden)]
+pub fn fmt_view(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    df: &DataFrame,
+    columns: &'static [&'static str],
+) -> fmt::Result {
+    f.debug_struct(name)
+        .field("shape", &df.shape())
+        .field("dtypes", &Dtypes { df, columns })
+        .finish()
+}
@@ -0,0 +1,85 @@
+//! Summary statistics of the columns of a struct.
+
+use crate::Result;
+use polars::prelude::{DataFrame, DataType};
+
+/// Summary statistics of the columns that a struct declares, in field order.
+///
+/// This is created by [ColbackView::describe](crate::ColbackView::describe), and holds plain
+/// values rather than a dataframe so that it's easy to assert on in tests or to emit as metrics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DescribeReport {
+    /// The statistics of each of the struct's columns that the dataframe has, in field order.
+    pub columns: Vec<ColumnStats>,
+}
+
+impl DescribeReport {
+    /// The statistics of the column `name`, or `None` if the report doesn't have the column.
+    pub fn get(&self, name: &str) -> Option<&ColumnStats> {
+        self.columns.iter().find(|stats| stats.name == name)
+    }
+}
+
+/// Summary statistics of a single column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    /// The name of the column.
+    pub name: String,
+    /// The number of non-null values.
+    pub count: usize,
+    /// The number of null values.
+    pub null_count: usize,
+    /// Statistics that depend on the column's dtype.
+    pub summary: ColumnSummary,
+}
+
+/// Statistics of a column that depend on its dtype.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnSummary {
+    /// The statistics of an integer or float column, as floats.
+    ///
+    /// These are `None` if the column has no non-null values, and `std` is the sample standard
+    /// deviation, so it's also `None` for a single value.
+    Numeric {
+        min: Option<f64>,
+        max: Option<f64>,
+        mean: Option<f64>,
+        std: Option<f64>,
+    },
+    /// The number of distinct non-null values of a string column.
+    Cardinality(usize),
+    /// Columns of other dtypes, such as booleans and datetimes, only have counts.
+    Other,
+}
+
+/// Compute the statistics of the given columns of a dataframe, leaving out missing columns.
+pub(crate) fn describe(df: &DataFrame, columns: &[&str]) -> Result<DescribeReport> {
+    let columns = columns
+        .iter()
+        .filter_map(|&name| df.column(name).ok())
+        .map(|column| -> Result<ColumnStats> {
+            let series = column.as_materialized_series();
+            let null_count = series.null_count();
+            let summary = match series.dtype() {
+                dtype if dtype.is_primitive_numeric() => ColumnSummary::Numeric {
+                    min: series.min::<f64>()?,
+                    max: series.max::<f64>()?,
+                    mean: series.mean(),
+                    std: series.std(1),
+                },
+                DataType::String => {
+                    let distinct = series.drop_nulls().n_unique()?;
+                    ColumnSummary::Cardinality(distinct)
+                }
+                _ => ColumnSummary::Other,
+            };
+            Ok(ColumnStats {
+                name: column.name().to_string(),
+                count: series.len() - null_count,
+                null_count,
+                summary,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(DescribeReport { columns })
+}
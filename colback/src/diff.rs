@@ -0,0 +1,149 @@
+//! Typed diffs between dataframes that share a struct's columns.
+
+use crate::{ColbackError, ColbackView, ColumnField, Result};
+use polars::prelude::{AnyValue, Column, DataFrame};
+use std::collections::{HashMap, HashSet};
+
+/// A row whose key occurs in both dataframes of a diff, but whose other fields differ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowChange<F> {
+    /// The index of the row in the old dataframe.
+    pub old_idx: usize,
+    /// The index of the row in the new dataframe.
+    pub new_idx: usize,
+    /// The fields whose values differ between the two rows, in field order.
+    pub fields: Vec<F>,
+}
+
+/// The rows that were added, removed, or changed between two dataframes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffReport<F> {
+    /// The indices of rows in the new dataframe whose keys don't occur in the old dataframe.
+    pub added: Vec<usize>,
+    /// The indices of rows in the old dataframe whose keys don't occur in the new dataframe.
+    pub removed: Vec<usize>,
+    /// The rows whose keys occur in both dataframes but have different values, ordered by their
+    /// position in the new dataframe.
+    pub changed: Vec<RowChange<F>>,
+}
+
+impl<F> DiffReport<F> {
+    /// Whether the two dataframes have the same rows, ignoring row order.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The key columns of a dataframe.
+fn key_columns<'a, F: ColumnField>(df: &'a DataFrame, key: &[F]) -> Result<Vec<&'a Column>> {
+    Ok(key
+        .iter()
+        .map(|f| df.column(f.column_name()))
+        .collect::<std::result::Result<_, _>>()?)
+}
+
+/// The key of every row of a dataframe.
+fn row_keys<'a>(columns: &[&'a Column], len: usize) -> Result<Vec<Vec<AnyValue<'a>>>> {
+    (0..len)
+        .map(|idx| {
+            Ok(columns
+                .iter()
+                .map(|c| c.get(idx))
+                .collect::<std::result::Result<_, _>>()?)
+        })
+        .collect()
+}
+
+/// Whether two values of a field are the same, where NaN is the same as NaN.
+fn same_value(old: &AnyValue, new: &AnyValue) -> bool {
+    match (old, new) {
+        (AnyValue::Float32(old), AnyValue::Float32(new)) => {
+            old == new || (old.is_nan() && new.is_nan())
+        }
+        (AnyValue::Float64(old), AnyValue::Float64(new)) => {
+            old == new || (old.is_nan() && new.is_nan())
+        }
+        _ => old == new,
+    }
+}
+
+/// Compare two dataframes with the struct's columns, matching rows by the values of `key`.
+///
+/// Rows are matched by key regardless of their position, and a matched pair is reported as
+/// changed if any field outside of the key differs. Null values are considered equal to each
+/// other, as are NaN values.
+///
+/// # Errors
+///
+/// Both dataframes are validated against the struct, see [ColbackView::view]. This also errors
+/// with [ColbackError::DuplicateKey] if a key occurs more than once in either dataframe.
+// Keys are hashed as `AnyValue`s, which clippy flags because categorical values hold a mapping
+// with interior mutability. The mapping isn't part of the hash, and struct fields can't be
+// categorical anyway.
+#[allow(clippy::mutable_key_type)]
+pub fn diff<T: ColbackView>(
+    old: &DataFrame,
+    new: &DataFrame,
+    key: &[T::Field],
+) -> Result<DiffReport<T::Field>> {
+    T::view(old)?;
+    T::view(new)?;
+
+    let old_keys = row_keys(&key_columns(old, key)?, old.height())?;
+    let mut old_rows = HashMap::with_capacity(old_keys.len());
+    for (idx, row_key) in old_keys.into_iter().enumerate() {
+        if old_rows.insert(row_key, idx).is_some() {
+            return Err(ColbackError::DuplicateKey { idx });
+        }
+    }
+
+    let compared: Vec<_> = <T::Field as ColumnField>::all()
+        .iter()
+        .filter(|f| !key.iter().any(|k| k.column_name() == f.column_name()))
+        .map(|f| {
+            Ok((
+                *f,
+                old.column(f.column_name())?,
+                new.column(f.column_name())?,
+            ))
+        })
+        .collect::<Result<_>>()?;
+
+    let new_keys = row_keys(&key_columns(new, key)?, new.height())?;
+    let mut matched = vec![false; old.height()];
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    let mut seen = HashSet::with_capacity(new_keys.len());
+    for (new_idx, row_key) in new_keys.into_iter().enumerate() {
+        let old_idx = old_rows.get(&row_key).copied();
+        if !seen.insert(row_key) {
+            return Err(ColbackError::DuplicateKey { idx: new_idx });
+        }
+        let Some(old_idx) = old_idx else {
+            added.push(new_idx);
+            continue;
+        };
+        matched[old_idx] = true;
+
+        let mut fields = Vec::new();
+        for (field, old_col, new_col) in &compared {
+            if !same_value(&old_col.get(old_idx)?, &new_col.get(new_idx)?) {
+                fields.push(*field);
+            }
+        }
+        if !fields.is_empty() {
+            changed.push(RowChange {
+                old_idx,
+                new_idx,
+                fields,
+            });
+        }
+    }
+
+    let removed = (0..old.height()).filter(|&idx| !matched[idx]).collect();
+    Ok(DiffReport {
+        added,
+        removed,
+        changed,
+    })
+}
@@ -0,0 +1,24 @@
+//! Typed results of DuckDB queries.
+
+use crate::{ColbackView, Result, record_batch};
+use arrow::record_batch::RecordBatch;
+use duckdb::{Connection, Params};
+use polars::prelude::{DataFrame, PolarsError};
+
+/// Run a DuckDB query, and convert its Arrow results into a dataframe projected to a struct's
+/// columns.
+pub(crate) fn query<T: ColbackView>(
+    conn: &Connection,
+    sql: &str,
+    params: impl Params,
+) -> Result<DataFrame> {
+    let to_polars = |e: duckdb::Error| PolarsError::ComputeError(e.to_string().into());
+    let mut stmt = conn.prepare(sql).map_err(to_polars)?;
+    let batches: Vec<RecordBatch> = stmt.query_arrow(params).map_err(to_polars)?.collect();
+
+    let mut df = record_batch::to_dataframe(&RecordBatch::new_empty(stmt.schema()))?;
+    for batch in &batches {
+        df.vstack_mut_owned(record_batch::to_dataframe(batch)?)?;
+    }
+    T::select(&df)
+}
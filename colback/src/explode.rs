@@ -0,0 +1,49 @@
+//! Exploding list columns of views into rows of a child struct.
+
+use crate::{ColbackError, ColbackView, ColumnField, Result, RowViewOps};
+use polars::prelude::{DataFrame, PolarsError};
+
+/// Explode a list field of a view's dataframe into one row per list element, viewed as a child
+/// struct.
+///
+/// Each element is joined with the values of the parent struct's other columns in its row, and
+/// the result is projected down to the child's columns. The elements are in a column with the
+/// list field's name, so the child struct declares them as a field of the list's element type.
+/// Empty and null lists produce a row with a null element, as in polars' explode.
+///
+/// # Errors
+///
+/// This errors if the field isn't a list field, if the dataframe doesn't have its column, which
+/// can happen for fields that fall back to their default when their column is missing, or if the
+/// exploded rows can't be viewed as the child struct (see [ColbackView::view]).
+pub fn explode<'a, V, F, C>(view: &V, field: F) -> Result<DataFrame>
+where
+    V: RowViewOps<'a>,
+    F: ColumnField,
+    C: ColbackView,
+{
+    let column = field.column_name();
+    let df = view.frame();
+    let list = df
+        .column(column)
+        .map_err(|_| ColbackError::MissingColumn(column.to_string()))?;
+    if !list.dtype().is_list() {
+        return Err(PolarsError::InvalidOperation(
+            format!(
+                "column {column} must be a list to explode, got {}",
+                list.dtype()
+            )
+            .into(),
+        )
+        .into());
+    }
+
+    let parent = view
+        .column_names()
+        .iter()
+        .copied()
+        .filter(|&name| name != column && df.column(name).is_ok());
+    let exploded = df.select(parent.chain([column]))?.explode([column])?;
+    C::view(&exploded)?;
+    Ok(exploded.select(C::column_names().iter().copied())?)
+}
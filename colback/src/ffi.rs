@@ -0,0 +1,59 @@
+//! Import and export of dataframes over the Arrow C stream interface.
+
+use crate::Result;
+use polars::prelude::{
+    CompatLevel, DataFrame, DataType, IntoSeries, PlSmallStr, PolarsError, Series,
+};
+use polars_arrow::ffi::{ArrowArrayStream, ArrowArrayStreamReader, export_iterator};
+
+/// Import a stream of struct arrays over the C stream interface into a dataframe.
+///
+/// The stream is moved out of `stream`, which is left released. The dataframe takes ownership of
+/// the imported buffers, and releases them once its columns are dropped.
+///
+/// # Safety
+///
+/// `stream` must be a valid C stream, whose arrays are valid according to the C data interface.
+pub(crate) unsafe fn import_stream(stream: &mut ArrowArrayStream) -> Result<DataFrame> {
+    let stream = Box::new(std::mem::replace(stream, ArrowArrayStream::empty()));
+    // SAFETY: upheld by the caller
+    let mut reader = unsafe { ArrowArrayStreamReader::try_new(stream)? };
+    let field = reader.field().clone();
+    let dtype = DataType::from_arrow_field(&field);
+    if !matches!(dtype, DataType::Struct(_)) {
+        return Err(PolarsError::InvalidOperation(
+            format!("an imported stream must hold struct arrays, got {dtype}").into(),
+        )
+        .into());
+    }
+
+    let mut arrays = Vec::new();
+    // SAFETY: upheld by the caller
+    while let Some(array) = unsafe { reader.next() } {
+        arrays.push(array?);
+    }
+    let series = if arrays.is_empty() {
+        Series::new_empty(field.name, &dtype)
+    } else {
+        Series::try_from((field.name, arrays))?
+    };
+    Ok(series.struct_()?.clone().unnest())
+}
+
+/// Export the given columns of a dataframe over the C stream interface.
+///
+/// Every chunk of the columns is exported as a struct array whose fields are the columns, in the
+/// given order. The arrays share their buffers with the dataframe.
+pub(crate) fn export_stream(df: &DataFrame, columns: &[&str]) -> Result<ArrowArrayStream> {
+    let series = df
+        .select(columns.iter().copied())?
+        .into_struct(PlSmallStr::EMPTY)
+        .into_series();
+    let field = series
+        .dtype()
+        .to_arrow_field(PlSmallStr::EMPTY, CompatLevel::newest());
+    let arrays = (0..series.n_chunks())
+        .map(|idx| Ok(series.to_arrow(idx, CompatLevel::newest())))
+        .collect::<Vec<_>>();
+    Ok(export_iterator(Box::new(arrays.into_iter()), field))
+}
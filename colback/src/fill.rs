@@ -0,0 +1,27 @@
+//! Helpers for views of fields that fill nulls from other rows.
+
+use polars::prelude::Column;
+
+/// The row that each row of a column takes its value from, when nulls are filled with the closest
+/// non-null value before (`forward`) or after the row.
+///
+/// Non-null rows take their own value, and rows with no non-null value to fill from are `None`.
+#[doc(hidden)]
+pub fn fill_indices(column: &Column, forward: bool) -> Vec<Option<usize>> {
+    let valid: Vec<bool> = column.is_not_null().into_no_null_iter().collect();
+    let mut indices = Vec::with_capacity(valid.len());
+    let mut last = None;
+    let mut fill = |idx: usize| {
+        if valid[idx] {
+            last = Some(idx);
+        }
+        last
+    };
+    if forward {
+        indices.extend((0..valid.len()).map(&mut fill));
+    } else {
+        indices.extend((0..valid.len()).rev().map(&mut fill));
+        indices.reverse();
+    }
+    indices
+}
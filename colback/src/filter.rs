@@ -0,0 +1,127 @@
+//! Typed comparisons for building filter expressions.
+
+use crate::temporal::datetime_in_unit;
+use chrono::NaiveDateTime;
+use polars::prelude::{DataType, Expr, col, lit};
+use std::marker::PhantomData;
+
+/// A Rust type that can be compared against a column in a filter expression.
+pub trait FilterValue {
+    /// The type of the values that the column is compared against.
+    type Arg<'v>;
+
+    /// Build a literal expression for a value, cast to the dtype of the column it is compared
+    /// against.
+    fn literal(value: Self::Arg<'_>, dtype: &DataType) -> Expr;
+}
+
+macro_rules! impl_filter_value {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl FilterValue for $ty {
+                type Arg<'v> = $ty;
+
+                fn literal(value: Self::Arg<'_>, dtype: &DataType) -> Expr {
+                    lit(value).cast(dtype.clone())
+                }
+            }
+        )*
+    };
+}
+
+impl_filter_value!(u8, u16, u32, u64, i32, i64, f32, f64, bool);
+
+impl FilterValue for String {
+    type Arg<'v> = &'v str;
+
+    fn literal(value: Self::Arg<'_>, _dtype: &DataType) -> Expr {
+        lit(value)
+    }
+}
+
+impl FilterValue for NaiveDateTime {
+    type Arg<'v> = NaiveDateTime;
+
+    /// # Panics
+    ///
+    /// Panics if the datetime can't be represented in the time unit of the column.
+    fn literal(value: Self::Arg<'_>, dtype: &DataType) -> Expr {
+        let DataType::Datetime(unit, _) = dtype else {
+            unreachable!("datetime fields are backed by datetime columns");
+        };
+        let value = datetime_in_unit(value, *unit)
+            .unwrap_or_else(|| panic!("{value} is out of range for a {unit} datetime column"));
+        lit(value).cast(dtype.clone())
+    }
+}
+
+/// A column of a struct deriving [ColbackView](crate::ColbackView) that can be compared against
+/// values of the field's Rust type.
+///
+/// These are constructed by the `<Struct>Filter` type generated by the derive, so comparisons
+/// only accept literals that match the dtype of the column.
+pub struct FilterColumn<T> {
+    name: &'static str,
+    dtype: DataType,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T: FilterValue> FilterColumn<T> {
+    /// Create a filter column. This is called by the generated filter builders.
+    pub fn new(name: &'static str, dtype: DataType) -> Self {
+        Self {
+            name,
+            dtype,
+            _type: PhantomData,
+        }
+    }
+
+    fn literal(&self, value: T::Arg<'_>) -> Expr {
+        T::literal(value, &self.dtype)
+    }
+
+    /// The column expression, for operations that aren't covered by the typed comparisons.
+    pub fn expr(&self) -> Expr {
+        col(self.name)
+    }
+
+    /// Values equal to `value`.
+    pub fn eq(&self, value: T::Arg<'_>) -> Expr {
+        self.expr().eq(self.literal(value))
+    }
+
+    /// Values not equal to `value`.
+    pub fn neq(&self, value: T::Arg<'_>) -> Expr {
+        self.expr().neq(self.literal(value))
+    }
+
+    /// Values greater than `value`.
+    pub fn gt(&self, value: T::Arg<'_>) -> Expr {
+        self.expr().gt(self.literal(value))
+    }
+
+    /// Values greater than or equal to `value`.
+    pub fn gt_eq(&self, value: T::Arg<'_>) -> Expr {
+        self.expr().gt_eq(self.literal(value))
+    }
+
+    /// Values less than `value`.
+    pub fn lt(&self, value: T::Arg<'_>) -> Expr {
+        self.expr().lt(self.literal(value))
+    }
+
+    /// Values less than or equal to `value`.
+    pub fn lt_eq(&self, value: T::Arg<'_>) -> Expr {
+        self.expr().lt_eq(self.literal(value))
+    }
+
+    /// Null values.
+    pub fn is_null(&self) -> Expr {
+        self.expr().is_null()
+    }
+
+    /// Non-null values.
+    pub fn is_not_null(&self) -> Expr {
+        self.expr().is_not_null()
+    }
+}
@@ -0,0 +1,76 @@
+//! Foreign key checks between dataframes.
+
+use crate::{ColbackError, ColbackView, ColumnField, Result};
+use polars::prelude::{AnyValue, DataFrame, IdxCa};
+use std::collections::{HashMap, HashSet};
+
+/// The keys of a child dataframe that don't occur in its parent, along with the rows where they
+/// occur.
+#[derive(Debug, Clone)]
+pub struct FkViolations {
+    /// One row for every missing key, containing only the child's key column.
+    ///
+    /// Keys are ordered by their first occurrence in the child dataframe.
+    pub keys: DataFrame,
+
+    /// The row indices in the child dataframe of each missing key, in the same order as `keys`.
+    pub indices: Vec<Vec<usize>>,
+}
+
+/// Check that every key of a child dataframe occurs in a parent dataframe.
+///
+/// Null child keys aren't references to the parent, so they are never violations.
+///
+/// # Errors
+///
+/// Both dataframes are validated against their structs, see [ColbackView::view], and the key
+/// columns must have the same dtype. Keys that are missing from the parent are reported with
+/// [ColbackError::ForeignKey].
+// Keys are hashed as `AnyValue`s, see `diff` for why clippy's lint doesn't apply.
+#[allow(clippy::mutable_key_type)]
+pub fn validate_fk<Child: ColbackView, Parent: ColbackView>(
+    child: &DataFrame,
+    parent: &DataFrame,
+    child_field: Child::Field,
+    parent_field: Parent::Field,
+) -> Result<()> {
+    Child::view(child)?;
+    Parent::view(parent)?;
+
+    let child_col = child.column(child_field.column_name())?;
+    let parent_col = parent.column(parent_field.column_name())?;
+    if child_col.dtype() != parent_col.dtype() {
+        return Err(ColbackError::WrongDtype {
+            col: child_field.column_name().to_string(),
+            expected: parent_col.dtype().clone(),
+            actual: child_col.dtype().clone(),
+        });
+    }
+
+    let parent_keys = (0..parent.height())
+        .map(|idx| parent_col.get(idx))
+        .collect::<std::result::Result<HashSet<_>, _>>()?;
+
+    let mut missing: HashMap<AnyValue<'_>, usize> = HashMap::new();
+    let mut indices: Vec<Vec<usize>> = Vec::new();
+    for idx in 0..child.height() {
+        let key = child_col.get(idx)?;
+        if key.is_null() || parent_keys.contains(&key) {
+            continue;
+        }
+        let group = *missing.entry(key).or_insert_with(|| {
+            indices.push(Vec::new());
+            indices.len() - 1
+        });
+        indices[group].push(idx);
+    }
+    if indices.is_empty() {
+        return Ok(());
+    }
+
+    let firsts = indices.iter().map(|idx| idx[0] as _).collect();
+    let keys = child
+        .select([child_field.column_name()])?
+        .take(&IdxCa::from_vec("".into(), firsts))?;
+    Err(ColbackError::ForeignKey(FkViolations { keys, indices }))
+}
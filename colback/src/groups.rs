@@ -0,0 +1,141 @@
+//! Helpers for working with polars group positions.
+
+use crate::{ColbackBuilder, ColbackKey, ColbackView, Result, RowViewOps};
+use polars::prelude::{DataFrame, GroupsIndicator, IdxCa, PolarsResult};
+use std::marker::PhantomData;
+
+/// The row indices belonging to a group.
+pub(crate) fn group_indices(group: GroupsIndicator<'_>) -> Vec<usize> {
+    match group {
+        GroupsIndicator::Idx((_, idx)) => idx.iter().map(|&i| i as usize).collect(),
+        GroupsIndicator::Slice([first, len]) => (first as usize..(first + len) as usize).collect(),
+    }
+}
+
+/// The rows of a dataframe belonging to a group.
+///
+/// Groups made of contiguous rows are zero-copy slices of the dataframe, otherwise the rows are
+/// gathered into a new dataframe.
+pub(crate) fn group_frame(df: &DataFrame, group: GroupsIndicator<'_>) -> Result<DataFrame> {
+    Ok(match group {
+        GroupsIndicator::Idx((_, idx)) => {
+            df.take(&IdxCa::from_vec("".into(), idx.iter().copied().collect()))?
+        }
+        GroupsIndicator::Slice([first, len]) => df.slice(first as i64, len as usize),
+    })
+}
+
+/// Adapt a typed aggregation of a group into a closure for polars' `GroupBy::apply`.
+///
+/// The rows of each group are viewed as `T` and aggregated by `f` into an owned row of `A`, so
+/// that `df.group_by(["key"])?.apply(group_apply::<T, A, _>(f))` returns a dataframe with `A`'s
+/// columns and a row for each group. Polars doesn't add the key columns to the result, so `A`
+/// should have fields for them if they're needed. Errors from viewing a group are converted into
+/// polars errors.
+pub fn group_apply<T, A, F>(
+    mut f: F,
+) -> impl FnMut(DataFrame) -> PolarsResult<DataFrame> + Send + Sync
+where
+    T: ColbackView,
+    A: ColbackBuilder,
+    F: for<'a> FnMut(T::View<'a>) -> A::Owned + Send + Sync,
+{
+    move |df| {
+        let mut builder = A::builder(1);
+        A::push_row(&mut builder, &f(T::view(&df)?))?;
+        Ok(A::finish_builder(builder)?)
+    }
+}
+
+/// The rows of a dataframe grouped by the fields of a key struct.
+///
+/// This is created by the `group_by` method of a generated view, and owns the dataframes for each
+/// group so that typed views can be created over them.
+pub struct Groups<K, T> {
+    /// One row for each group containing its key columns, ordered by first occurrence.
+    keys: DataFrame,
+    frames: Vec<DataFrame>,
+    _types: PhantomData<(K, T)>,
+}
+
+impl<K: ColbackKey, T: ColbackView> Groups<K, T> {
+    /// Group a dataframe by the columns of the key struct.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the dataframe doesn't have the key struct's columns.
+    pub fn new(df: &DataFrame) -> Result<Self> {
+        let names = K::column_names();
+        K::view(df)?;
+
+        let group_by = df.group_by_stable(names.iter().copied())?;
+        let groups = group_by.get_groups();
+        let firsts: Vec<_> = groups.iter().map(|g| g.first()).collect();
+        let frames = groups
+            .iter()
+            .map(|g| group_frame(df, g))
+            .collect::<Result<_>>()?;
+        let keys = df
+            .select(names.iter().copied())?
+            .take(&IdxCa::from_vec("".into(), firsts))?;
+        Ok(Self {
+            keys,
+            frames,
+            _types: PhantomData,
+        })
+    }
+
+    /// The number of groups.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether there are no groups.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// A dataframe with one row for each group, containing its key columns.
+    pub fn keys(&self) -> &DataFrame {
+        &self.keys
+    }
+
+    /// The dataframes for each group, in the same order as [Groups::keys].
+    pub fn frames(&self) -> &[DataFrame] {
+        &self.frames
+    }
+
+    /// Aggregate the view of each group into an owned row of `A`.
+    ///
+    /// The result has the key columns followed by `A`'s columns, with a row for each group in
+    /// the same order as [Groups::keys].
+    ///
+    /// # Errors
+    ///
+    /// This errors if a group can't be viewed as `T`, if `A`'s columns can't be built, or if `A`
+    /// has a column with the same name as a key column.
+    pub fn aggregate<A, F>(&self, mut f: F) -> Result<DataFrame>
+    where
+        A: ColbackBuilder,
+        F: for<'a> FnMut(T::View<'a>) -> A::Owned,
+    {
+        let mut builder = A::builder(self.len());
+        for df in &self.frames {
+            A::push_row(&mut builder, &f(T::view(df)?))?;
+        }
+        let aggregates = A::finish_builder(builder)?;
+        Ok(self.keys.hstack(aggregates.get_columns())?)
+    }
+
+    /// Iterate over the typed key and view of each group.
+    ///
+    /// Groups are ordered by the first occurrence of their key in the original dataframe.
+    pub fn iter(&self) -> Result<impl Iterator<Item = Result<(K::RowRef<'_>, T::View<'_>)>>> {
+        let keys = K::view(&self.keys)?;
+        Ok(self
+            .frames
+            .iter()
+            .enumerate()
+            .map(move |(i, df)| Ok((keys.get(i)?, T::view(df)?))))
+    }
+}
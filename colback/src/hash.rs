@@ -0,0 +1,145 @@
+//! Content hashes of rows that are stable across runs, platforms, and Rust versions.
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use std::time::Duration;
+
+/// A 64 bit FNV-1a hasher for the content hashes of rows.
+///
+/// `std`'s hashers aren't guaranteed to produce the same hashes across Rust versions, so content
+/// hashes that may be persisted, e.g. by change data capture pipelines, use this instead.
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct ContentHasher(u64);
+
+impl ContentHasher {
+    /// Create a hasher with the FNV offset basis.
+    pub fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    /// Add bytes to the hash.
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    /// The hash of the bytes written so far.
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A field value that can be added to the content hash of a row.
+///
+/// Values are written with a fixed encoding, so their hashes don't depend on the platform.
+#[doc(hidden)]
+pub trait ContentHash {
+    /// Write the value to a hasher.
+    fn content_hash(&self, hasher: &mut ContentHasher);
+}
+
+/// Numbers are written as their little endian bytes, and floats as the bytes of their bits.
+macro_rules! content_hash_le {
+    ($($ty:ty),*) => {
+        $(impl ContentHash for $ty {
+            fn content_hash(&self, hasher: &mut ContentHasher) {
+                hasher.write(&self.to_le_bytes());
+            }
+        })*
+    };
+}
+
+content_hash_le!(u8, u16, u32, u64, i32, i64);
+
+impl ContentHash for f32 {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        self.to_bits().content_hash(hasher);
+    }
+}
+
+impl ContentHash for f64 {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        self.to_bits().content_hash(hasher);
+    }
+}
+
+impl ContentHash for bool {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        hasher.write(&[u8::from(*self)]);
+    }
+}
+
+/// Strings are prefixed with their length, so adjacent strings can't run into each other.
+impl ContentHash for str {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        (self.len() as u64).content_hash(hasher);
+        hasher.write(self.as_bytes());
+    }
+}
+
+/// Datetimes are written as their seconds and nanoseconds since the epoch.
+impl ContentHash for NaiveDateTime {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        let utc = self.and_utc();
+        utc.timestamp().content_hash(hasher);
+        utc.timestamp_subsec_nanos().content_hash(hasher);
+    }
+}
+
+impl ContentHash for DateTime<Utc> {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        self.naive_utc().content_hash(hasher);
+    }
+}
+
+/// Durations are written as their whole seconds and remaining nanoseconds.
+impl ContentHash for TimeDelta {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        self.num_seconds().content_hash(hasher);
+        self.subsec_nanos().content_hash(hasher);
+    }
+}
+
+impl ContentHash for Duration {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        self.as_secs().content_hash(hasher);
+        self.subsec_nanos().content_hash(hasher);
+    }
+}
+
+/// Optional values are prefixed with whether they're present, so nulls don't hash like values.
+impl<T: ContentHash> ContentHash for Option<T> {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        match self {
+            Some(value) => {
+                hasher.write(&[1]);
+                value.content_hash(hasher);
+            }
+            None => hasher.write(&[0]),
+        }
+    }
+}
+
+/// Slices are prefixed with their length, like strings.
+impl<T: ContentHash> ContentHash for [T] {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        (self.len() as u64).content_hash(hasher);
+        for value in self {
+            value.content_hash(hasher);
+        }
+    }
+}
+
+impl<T: ContentHash + ?Sized> ContentHash for &T {
+    fn content_hash(&self, hasher: &mut ContentHasher) {
+        (**self).content_hash(hasher);
+    }
+}
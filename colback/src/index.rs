@@ -0,0 +1,96 @@
+//! Hash indexes for point lookups into views.
+
+use crate::{ColbackError, Result, RowViewOps};
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+/// A hash index from a key computed from each row of a view to the rows with that key.
+///
+/// This is created by [RowViewOps::index_by] or [RowViewOps::index_by_multi] and borrows the view
+/// that it indexes, so lookups return row references without scanning the view.
+pub struct ColbackIndex<'v, V, K> {
+    view: &'v V,
+    rows: HashMap<K, Vec<usize>>,
+}
+
+impl<'v, 'a, V, K> ColbackIndex<'v, V, K>
+where
+    V: RowViewOps<'a>,
+    K: Eq + Hash,
+{
+    /// Index the rows of a view, optionally requiring that every key is unique.
+    pub(crate) fn new<F>(view: &'v V, mut f: F, unique: bool) -> Result<Self>
+    where
+        F: FnMut(&V::Row) -> K,
+    {
+        let mut rows: HashMap<K, Vec<usize>> = HashMap::new();
+        for idx in 0..view.len() {
+            let Some(row) = view.get_unskipped(idx)? else {
+                continue;
+            };
+            let indices = rows.entry(f(&row)).or_default();
+            if unique && !indices.is_empty() {
+                return Err(ColbackError::DuplicateKey { idx });
+            }
+            indices.push(idx);
+        }
+        Ok(Self { view, rows })
+    }
+
+    /// The number of distinct keys in the index.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether the index has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Whether any row has the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.rows.contains_key(key)
+    }
+
+    /// The indices of the rows with the given key, in row order.
+    pub fn indices<Q>(&self, key: &Q) -> &[usize]
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.rows.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get the first row with the given key.
+    ///
+    /// Returns `None` if no row has the key.
+    ///
+    /// # Errors
+    ///
+    /// This errors under the same conditions as [RowViewOps::get].
+    pub fn lookup<Q>(&self, key: &Q) -> Result<Option<V::Row>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.indices(key)
+            .first()
+            .map(|&idx| self.view.get(idx))
+            .transpose()
+    }
+
+    /// Get every row with the given key, in row order.
+    pub fn lookup_all<Q>(
+        &self,
+        key: &Q,
+    ) -> impl Iterator<Item = Result<V::Row>> + use<'_, 'v, 'a, V, K, Q>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.indices(key).iter().map(|&idx| self.view.get(idx))
+    }
+}
@@ -0,0 +1,115 @@
+//! Typed readers and writers for polars' file formats.
+
+use crate::{ColbackBuilder, ColbackError, Result};
+use polars::prelude::{
+    CsvReadOptions, CsvWriter, DataFrame, IpcReader, IpcScanOptions, JsonFormat, JsonWriter,
+    LazyFrame, ParquetWriteOptions, PlPathRef, PolarsError, PolarsResult, ScanArgsParquet, Schema,
+    SerReader, SerWriter, UnifiedScanArgs, col,
+};
+use std::{fs::File, path::Path, sync::Arc};
+
+/// A file format that the struct's columns of a dataframe can be written to.
+pub(crate) enum Format<'a> {
+    Parquet(&'a ParquetWriteOptions),
+    /// CSV with a header row.
+    Csv,
+    /// Newline delimited JSON, with one object per row.
+    Ndjson,
+}
+
+impl Format<'_> {
+    fn write(&self, file: File, df: &mut DataFrame) -> PolarsResult<()> {
+        match self {
+            Self::Parquet(options) => options.to_writer(file).finish(df).map(|_| ()),
+            Self::Csv => CsvWriter::new(file).include_header(true).finish(df),
+            Self::Ndjson => JsonWriter::new(file)
+                .with_json_format(JsonFormat::JsonLines)
+                .finish(df),
+        }
+    }
+}
+
+/// Write the struct's columns of a dataframe to a file.
+///
+/// Only the given columns are written, in the given order, so the file matches the struct that
+/// declares them.
+pub(crate) fn write(df: &DataFrame, columns: &[&str], path: &Path, format: Format) -> Result<()> {
+    let mut df = df.select(columns.iter().copied())?;
+    let file = File::create(path).map_err(PolarsError::from)?;
+    format.write(file, &mut df)?;
+    Ok(())
+}
+
+/// Write owned rows to a file with the struct's columns.
+pub(crate) fn write_rows<T: ColbackBuilder>(
+    rows: impl IntoIterator<Item = T::Owned>,
+    path: &Path,
+    format: Format,
+) -> Result<()> {
+    let rows = rows.into_iter();
+    let mut builder = T::builder(rows.size_hint().0);
+    for row in rows {
+        T::push_row(&mut builder, &row)?;
+    }
+    let df = T::finish_builder(builder)?;
+    write(&df, T::column_names(), path, format)
+}
+
+/// Read a CSV file, parsing the columns in `schema` with its dtypes.
+///
+/// Columns that aren't in `schema` have their dtypes inferred as usual.
+pub(crate) fn read_csv(path: &Path, options: CsvReadOptions, schema: Schema) -> Result<DataFrame> {
+    Ok(options
+        .with_schema_overwrite(Some(Arc::new(schema)))
+        .try_into_reader_with_file_path(Some(path.to_path_buf()))?
+        .finish()?)
+}
+
+/// Read an IPC file by memory mapping it.
+///
+/// The columns reference the mapped file rather than being copied into memory. Files that can't be
+/// memory mapped, such as compressed ones, are read normally instead.
+pub(crate) fn read_ipc_mmap(path: &Path) -> Result<DataFrame> {
+    let file = File::open(path).map_err(PolarsError::from)?;
+    Ok(IpcReader::new(file)
+        .memory_mapped(Some(path.to_path_buf()))
+        .finish()?)
+}
+
+/// A file format that can be scanned lazily.
+pub(crate) enum ScanFormat {
+    Parquet,
+    Ipc,
+}
+
+/// Lazily scan a file, projected down to the columns in `schema`.
+///
+/// The file's schema is checked against `schema` before anything is returned, which only needs
+/// the file's metadata.
+pub(crate) fn scan(path: &Path, format: ScanFormat, schema: &Schema) -> Result<LazyFrame> {
+    let path = PlPathRef::from_local_path(path).into_owned();
+    let mut lf = match format {
+        ScanFormat::Parquet => LazyFrame::scan_parquet(path, ScanArgsParquet::default())?,
+        ScanFormat::Ipc => LazyFrame::scan_ipc(path, IpcScanOptions, UnifiedScanArgs::default())?,
+    };
+    let actual = lf.collect_schema()?;
+    check_schema(&actual, schema)?;
+    Ok(lf.select(schema.iter_names().cloned().map(col).collect::<Vec<_>>()))
+}
+
+/// Check that every column in `expected` is in `actual` with the same dtype.
+fn check_schema(actual: &Schema, expected: &Schema) -> Result<()> {
+    for (name, dtype) in expected.iter() {
+        let actual = actual
+            .get(name)
+            .ok_or_else(|| ColbackError::MissingColumn(name.to_string()))?;
+        if actual != dtype {
+            return Err(ColbackError::WrongDtype {
+                col: name.to_string(),
+                expected: dtype.clone(),
+                actual: actual.clone(),
+            });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+//! Typed joins between dataframes backing different structs.
+
+use crate::{ColbackKey, ColbackView, Result};
+use polars::prelude::{DataFrame, DataFrameJoinOps, JoinArgs, JoinType};
+
+/// Marker trait for a struct whose rows can be joined or grouped by the key struct `K`.
+///
+/// This is implemented by the `ColbackKey` derive for every struct listed in its
+/// `#[polars(key_of(...))]` attribute, which also checks at compile time that each of those
+/// structs has the key's fields with the same types.
+pub trait KeyOf<K: ColbackKey>: ColbackView {}
+
+/// Join the dataframes backing two structs on the fields of a shared key struct.
+///
+/// Both dataframes are validated against their structs and against the key struct before they
+/// are joined.
+///
+/// # Errors
+///
+/// This errors if either dataframe fails validation (see [ColbackView::view]) or if the join
+/// itself fails.
+pub fn join<L, R, K>(left: &DataFrame, right: &DataFrame, how: JoinType) -> Result<DataFrame>
+where
+    K: ColbackKey,
+    L: KeyOf<K>,
+    R: KeyOf<K>,
+{
+    L::view(left)?;
+    R::view(right)?;
+    K::view(left)?;
+    K::view(right)?;
+    let on = K::column_names();
+    Ok(left.join(
+        right,
+        on.iter().copied(),
+        on.iter().copied(),
+        JoinArgs::new(how),
+        None,
+    )?)
+}
@@ -34,14 +34,184 @@
 //! assert_eq!(row_ref.col_a, 0);
 //! assert_eq!(row_ref.col_b, true);
 //! ```
+//!
+//! # Concurrency
+//!
+//! Views only borrow the dataframe and never mutate it, so the generated views, row references,
+//! and mutable views are `Send + Sync`. A view can be shared by reference across scoped threads
+//! (for instance with [std::thread::scope]) and each thread can read rows independently. The
+//! derive macro checks this at compile time, and [ColbackView::View] and [ColbackView::RowRef]
+//! require it, so generic code can rely on it as well.
+//!
+//! # String fields
+//!
+//! `String` fields are read as `&str` values that borrow from the column's string view buffers.
+//! Polars validates UTF-8 when a string column is built, so getting a row neither copies the
+//! string nor validates it again. Strings are only allocated when rows are converted into owned
+//! structs.
+//!
+//! # List fields
+//!
+//! `Vec` fields of integers or floats are backed by list columns, and are read as slices that
+//! borrow the list's elements in the same way. Lists with null elements can't be borrowed, so
+//! reading their rows errors with [ColbackError::InvalidNull]. List fields can be exploded into a
+//! row per element with the view's `explode` method, see [explode].
 
 // Trick to allow for codegen within the same crate. This was also required to get the doctest
 // working.
 extern crate self as colback;
 
-pub use colback_derive::ColbackView;
-use polars::{frame::DataFrame, prelude::DataType};
+#[cfg(feature = "proptest")]
+mod arbitrary;
+#[cfg(feature = "serde_arrow")]
+mod arrow_serde;
+mod asof;
+#[cfg(feature = "datafusion")]
+mod batch_stream;
+mod build;
+mod category;
+mod chunks;
+mod constraints;
+mod context;
+#[cfg(feature = "serde")]
+mod de;
+mod debug;
+mod describe;
+mod diff;
+#[cfg(feature = "duckdb")]
+mod duckdb_query;
+mod explode;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod fill;
+mod filter;
+mod foreign_key;
+mod groups;
+mod hash;
+mod index;
+#[cfg(feature = "io")]
+mod io;
+mod join;
+mod list;
+mod melt;
+mod options;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "plugin")]
+mod plugin;
+#[cfg(feature = "pyo3")]
+mod py;
+#[cfg(feature = "arrow")]
+mod record_batch;
+mod rolling;
+mod sample;
+mod scan;
+mod schema;
+mod sort;
+mod split;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "metrics")]
+mod telemetry;
+mod temporal;
+mod testing;
+mod transform;
+mod typed_frame;
+mod unique;
+mod upsert;
+mod zip;
+
+#[cfg(feature = "proptest")]
+#[doc(hidden)]
+pub use arbitrary::{arb_category, arb_epoch, arb_naive_datetime};
+pub use asof::AsofJoin;
+pub use build::{ColbackFrame, ColumnBuffers, IntoDataFrame};
+pub use category::{CategoryIndex, ColbackCategory};
+#[doc(hidden)]
+pub use chrono as __chrono;
+pub use chunks::ChunkIndex;
+#[cfg(feature = "gen")]
+pub use colback_codegen::{StructOptions, generate_struct, struct_definition};
+#[cfg(feature = "schema")]
+pub use colback_derive::struct_from_schema;
+pub use colback_derive::{
+    ColbackBuilder, ColbackCategory, ColbackContext, ColbackKey, ColbackOwned, ColbackSchema,
+    ColbackView, define_row,
+};
+pub use constraints::ConstraintViolation;
+#[doc(hidden)]
+pub use constraints::check_constraints;
+pub use context::{ColbackContext, view_frame};
+#[cfg(feature = "serde")]
+pub use de::{from_json_lines, from_serde_iter};
+#[doc(hidden)]
+pub use debug::fmt_view;
+pub use describe::{ColumnStats, ColumnSummary, DescribeReport};
+pub use diff::{DiffReport, RowChange, diff};
+pub use explode::explode;
+#[doc(hidden)]
+pub use fill::fill_indices;
+pub use filter::{FilterColumn, FilterValue};
+pub use foreign_key::{FkViolations, validate_fk};
+pub use groups::{Groups, group_apply};
+#[doc(hidden)]
+pub use hash::{ContentHash, ContentHasher};
+pub use index::ColbackIndex;
+pub use join::{KeyOf, join};
+#[doc(hidden)]
+pub use list::{ListBuilder, list_any_value};
+#[cfg(feature = "nalgebra")]
+#[doc(hidden)]
+pub use nalgebra as __nalgebra;
+#[cfg(feature = "ndarray")]
+#[doc(hidden)]
+pub use ndarray as __ndarray;
+pub use options::ViewOptions;
+#[cfg(feature = "rayon")]
+pub use parallel::par_map_chunks;
+#[cfg(feature = "plugin")]
+pub use plugin::map_struct;
+use polars::{
+    frame::DataFrame,
+    prelude::{
+        AnyValue, ArrowSchema, BooleanChunked, Column, DataType, Expr, IdxCa, IdxSize, LazyFrame,
+        PolarsError, Schema, UniqueKeepStrategy, col,
+    },
+};
+#[cfg(feature = "ffi")]
+pub use polars_arrow::ffi::ArrowArrayStream;
+#[cfg(feature = "proptest")]
+#[doc(hidden)]
+pub use proptest as __proptest;
+pub use rolling::{RollingWindow, rolling};
+#[doc(hidden)]
+pub use sample::{SampleRng, SampleValue};
+pub use scan::scan_batches;
+pub use schema::{DtypeMismatch, NullConflict, SchemaDiff};
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde as __serde;
+pub use sort::SortField;
+pub use split::{kfold, sample_stratified, sample_weighted, split};
+use std::{borrow::Cow, collections::HashMap, fmt::Display, hash::Hash, iter::Sum};
+#[cfg(feature = "metrics")]
+#[doc(hidden)]
+pub use telemetry::record_view;
+pub use temporal::{EpochValue, datetime_in_unit, naive_datetime};
+#[doc(hidden)]
+pub use temporal::{
+    TimeZoneConversion, check_time_zone, datetime_any_value, from_epoch, time_zone, to_epoch,
+};
+#[doc(hidden)]
+pub use testing::assert_rows;
+pub use testing::{FieldDiff, FieldDiffs, assert_df_matches};
 use thiserror::Error;
+#[doc(hidden)]
+pub use transform::map_rows;
+pub use transform::{replace_column, transform};
+pub use typed_frame::TypedFrame;
+pub use unique::DuplicatesReport;
+pub use zip::zip;
 
 /// Errors that can arise when trying to extract a dataframe to a row view.
 #[derive(Debug, Error)]
@@ -54,6 +224,13 @@ pub enum ColbackError {
     #[error("missing required column(s): {0:?}")]
     MissingColumn(String),
 
+    /// When the dataframe is missing several required columns that were specified in the struct.
+    ///
+    /// Views report every missing column at once, with [ColbackError::MissingColumn] used when
+    /// only one is missing.
+    #[error("missing required column(s): {0:?}")]
+    MissingColumns(Vec<String>),
+
     #[error("column {col} has wrong dtype: expected {expected:?}, got {actual:?}")]
     WrongDtype {
         col: String,
@@ -61,6 +238,20 @@ pub enum ColbackError {
         actual: DataType,
     },
 
+    /// Thrown when a datetime column has a different time zone than its field expects.
+    ///
+    /// Time zones are `None` for naive datetimes.
+    #[error(
+        "column {col} has time zone {}, expected {}",
+        format_time_zone(.actual),
+        format_time_zone(.expected)
+    )]
+    TimezoneMismatch {
+        col: String,
+        expected: Option<String>,
+        actual: Option<String>,
+    },
+
     /// Thrown if the dataframe has a null value and the null handling policy is to error out.
     #[error("null values encountered in non-nullable column {col} at row {idx}")]
     InvalidNull {
@@ -71,29 +262,621 @@ pub enum ColbackError {
         /// Index where the null was encountered
         idx: usize,
     },
+
+    /// Thrown if a float column has a NaN value and the NaN handling policy is to error out.
+    #[error("NaN value encountered in column {col} at row {idx}")]
+    InvalidNan { col: String, idx: usize },
+
+    /// Thrown when a value of a wider integer column doesn't fit in the narrower type of its field.
+    #[error("value {value} in column {col} at row {idx} is out of range for its field")]
+    ValueOutOfRange {
+        col: String,
+        idx: usize,
+        value: String,
+    },
+
+    /// Thrown when an input (such as a filter mask) does not have the same length as the view.
+    #[error("length mismatch: expected {expected}, got {actual}")]
+    LengthMismatch { expected: usize, actual: usize },
+
+    /// Thrown when trying to access a row that is past the end of the view.
+    #[error("row index {idx} is out of bounds for a view of length {len}")]
+    IndexOutOfBounds { idx: usize, len: usize },
+
+    /// Thrown when some of several input dataframes fail validation.
+    ///
+    /// Each entry holds the index of the failing dataframe along with its error.
+    #[error("{} dataframe(s) failed validation: {}", .0.len(), format_frame_errors(.0))]
+    InvalidFrames(Vec<(usize, ColbackError)>),
+
+    /// Thrown when a dataframe that a context requires isn't provided.
+    #[error("missing required dataframe: {0}")]
+    MissingFrame(String),
+
+    /// Thrown when some of the named dataframes of a context fail validation.
+    ///
+    /// Each entry holds the name of the failing dataframe along with its error.
+    #[error("{} dataframe(s) failed validation: {}", .0.len(), format_frame_errors(.0))]
+    InvalidContext(Vec<(String, ColbackError)>),
+
+    /// Thrown when a key that is required to be unique occurs more than once in a dataframe.
+    #[error("duplicate key at row {idx}")]
+    DuplicateKey { idx: usize },
+
+    /// Thrown when keys of a child dataframe don't occur in its parent dataframe.
+    #[error("{} key(s) missing from the parent dataframe", .0.indices.len())]
+    ForeignKey(FkViolations),
+
+    /// Signals that a row is skipped because a field with the "skip" null handling policy is null,
+    /// or a category field with the "skip" unknown policy has an unknown category.
+    ///
+    /// Iteration and aggregation over a view leave these rows out, so this is only returned when
+    /// getting a skipped row directly or by position.
+    #[error("row {idx} is skipped because of its value in column {col}")]
+    RowSkipped { col: String, idx: usize },
+
+    /// Thrown when a category field's column has a value that isn't one of the field's categories
+    /// and the unknown category policy is to error out.
+    #[error("unknown category {value:?} in column {col} at row {idx}")]
+    UnknownCategory {
+        col: String,
+        idx: usize,
+        value: String,
+    },
+
+    /// Thrown when rows of a dataframe violate constraints of the struct, such as `min` bounds.
+    ///
+    /// Each violated constraint is reported with the number of rows that violate it and the
+    /// indices of the first few of them.
+    #[error("{} constraint(s) violated: {}", .0.len(), format_violations(.0))]
+    ConstraintViolations(Vec<ConstraintViolation>),
+
+    /// Thrown when a row can't be deserialized into an owned row.
+    #[error("failed to deserialize row {idx}: {msg}")]
+    Deserialize { idx: usize, msg: String },
+
+    /// An error from an underlying polars operation.
+    #[error(transparent)]
+    Polars(#[from] PolarsError),
+}
+
+impl From<ColbackError> for PolarsError {
+    /// Convert an error so that it can be returned from polars callbacks, such as expression
+    /// plugins and anonymous scans.
+    fn from(err: ColbackError) -> Self {
+        match err {
+            ColbackError::Polars(e) => e,
+            ColbackError::MissingColumn(_) | ColbackError::MissingColumns(_) => {
+                PolarsError::ColumnNotFound(err.to_string().into())
+            }
+            ColbackError::WrongDtype { .. } | ColbackError::TimezoneMismatch { .. } => {
+                PolarsError::SchemaMismatch(err.to_string().into())
+            }
+            ColbackError::IndexOutOfBounds { .. } => {
+                PolarsError::OutOfBounds(err.to_string().into())
+            }
+            _ => PolarsError::ComputeError(err.to_string().into()),
+        }
+    }
+}
+
+/// Format the time zone of [ColbackError::TimezoneMismatch].
+fn format_time_zone(tz: &Option<String>) -> &str {
+    tz.as_deref().unwrap_or("none")
+}
+
+/// Format the violations of [ColbackError::ConstraintViolations] as a single line.
+fn format_violations(violations: &[ConstraintViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Format the errors of [ColbackError::InvalidFrames] or [ColbackError::InvalidContext] as a
+/// single line.
+fn format_frame_errors<T: Display>(errors: &[(T, ColbackError)]) -> String {
+    errors
+        .iter()
+        .map(|(idx, e)| format!("[{idx}] {e}"))
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 /// Convenience alias for results from this crate.
 pub type Result<T> = std::result::Result<T, ColbackError>;
 
+/// Check that a dataframe has all of the given columns, reporting every missing column at once.
+#[doc(hidden)]
+pub fn check_columns(df: &DataFrame, names: &[&str]) -> Result<()> {
+    let mut missing: Vec<String> = names
+        .iter()
+        .filter(|name| df.column(name).is_err())
+        .map(|name| name.to_string())
+        .collect();
+    match missing.len() {
+        0 => Ok(()),
+        1 => Err(ColbackError::MissingColumn(missing.remove(0))),
+        _ => Err(ColbackError::MissingColumns(missing)),
+    }
+}
+
+/// Dynamic access to the fields of a row, for code that handles rows of any struct.
+///
+/// This is implemented by every row reference generated by the derive macro and is object safe,
+/// so middleware such as validation, logging, or metrics can be written once against
+/// `&dyn RowAccess`. Fields are identified by the names of the columns that back them.
+pub trait RowAccess {
+    /// The column names of the row's fields, in field order.
+    fn field_names(&self) -> &'static [&'static str];
+
+    /// The value of the field backed by the column `name`, or `None` if the row has no such
+    /// field. Null values are `AnyValue::Null`.
+    fn get_field(&self, name: &str) -> Option<AnyValue<'_>>;
+
+    /// The number of fields in the row.
+    fn len(&self) -> usize {
+        self.field_names().len()
+    }
+
+    /// Whether the row has no fields.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A field of a struct deriving [ColbackView], identifying the column that backs it.
+///
+/// This is implemented by the field enum generated by the derive macro, which has one variant per
+/// struct field.
+pub trait ColumnField: Copy + 'static {
+    /// The name of the column backing this field.
+    fn column_name(&self) -> &'static str;
+
+    /// All of the struct's fields, in field order.
+    fn all() -> &'static [Self];
+
+    /// A column expression for the column backing this field.
+    fn expr(&self) -> Expr {
+        col(self.column_name())
+    }
+}
+
+/// Row access for the view structs generated by the derive macro.
+///
+/// The generated views have inherent methods with the same names, this trait lets generic code
+/// access the rows of any view. Together with [ColbackView], whose views implement this trait and
+/// whose rows implement [RowAccess], utilities can be written once for every derived struct:
+///
+/// ```rust
+/// use colback::{ColbackView, Result, RowAccess, RowViewOps};
+/// use polars::prelude::{AnyValue, DataFrame};
+///
+/// /// Count the null values of each field of a struct's rows.
+/// fn count_nulls<T: ColbackView>(df: &DataFrame) -> Result<Vec<(&'static str, usize)>> {
+///     let view = T::view(df)?;
+///     let mut counts: Vec<_> = T::column_names().iter().map(|&name| (name, 0)).collect();
+///     for row in view.iter() {
+///         let row = row?;
+///         for (name, count) in &mut counts {
+///             if row.get_field(name) == Some(AnyValue::Null) {
+///                 *count += 1;
+///             }
+///         }
+///     }
+///     Ok(counts)
+/// }
+///
+/// #[derive(ColbackView)]
+/// struct MyRow {
+///     col_a: u32,
+///     #[polars(null = "option")]
+///     col_b: Option<u32>,
+/// }
+///
+/// let df = polars::df!("col_a" => [1u32, 2], "col_b" => [None, Some(2u32)]).unwrap();
+/// assert_eq!(count_nulls::<MyRow>(&df).unwrap(), vec![("col_a", 0), ("col_b", 1)]);
+/// ```
+pub trait RowViewOps<'a> {
+    /// The row reference type produced by the view.
+    type Row;
+
+    /// The dataframe backing the view.
+    ///
+    /// Pages of a view are backed by the whole dataframe, see [RowViewOps::frame] for only the
+    /// view's rows.
+    fn df(&self) -> &'a DataFrame;
+
+    /// The rows of the view as a dataframe.
+    ///
+    /// This borrows [RowViewOps::df] unless the view is a page of it, in which case it's a
+    /// zero-copy slice of the page's rows.
+    fn frame(&self) -> Cow<'a, DataFrame> {
+        Cow::Borrowed(self.df())
+    }
+
+    /// The number of rows in the view.
+    ///
+    /// This includes skipped rows, since rows are indexed by their position in the view. Use
+    /// [RowViewOps::skipped_column] to tell which rows are skipped.
+    fn len(&self) -> usize;
+
+    /// Whether the view has no rows.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the row at a given index.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the index is out of bounds or if the row has a null value that violates a
+    /// field's null handling policy.
+    fn get(&self, idx: usize) -> Result<Self::Row>;
+
+    /// Get the row at a given index, or `None` if it's skipped because a field with the "skip"
+    /// null handling policy is null.
+    ///
+    /// # Errors
+    ///
+    /// This errors under the same conditions as [RowViewOps::get], except for
+    /// [ColbackError::RowSkipped].
+    fn get_unskipped(&self, idx: usize) -> Result<Option<Self::Row>> {
+        #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+        if let Some(col) = self.skipped_column(idx) {
+            #[cfg(feature = "metrics")]
+            telemetry::record_skipped_row(col);
+            return Ok(None);
+        }
+        self.get(idx).map(Some)
+    }
+
+    /// The column of the first field with the "skip" null handling policy that's null at a given
+    /// index, or `None` if the row isn't skipped.
+    ///
+    /// This only checks the skipped fields, so it's cheaper than getting the row. It's also
+    /// `None` for indices that are out of bounds.
+    fn skipped_column(&self, idx: usize) -> Option<&'static str> {
+        let _ = idx;
+        None
+    }
+
+    /// Iterate over the rows of the view, leaving out skipped rows.
+    ///
+    /// Each item is the result of [RowViewOps::get_unskipped] for a row that isn't skipped.
+    fn iter(&self) -> impl Iterator<Item = Result<Self::Row>> {
+        (0..self.len()).filter_map(|idx| self.get_unskipped(idx).transpose())
+    }
+
+    /// The names of the columns backing the view's struct, in field order.
+    ///
+    /// See [ColbackSchema::column_names].
+    fn column_names(&self) -> &'static [&'static str];
+
+    /// Write the struct's columns of the view's dataframe to a parquet file.
+    ///
+    /// Columns of the dataframe that the struct doesn't declare aren't written.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the file can't be created or written.
+    #[cfg(feature = "io")]
+    fn write_parquet(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        options: &polars::prelude::ParquetWriteOptions,
+    ) -> Result<()> {
+        let format = io::Format::Parquet(options);
+        io::write(&self.frame(), self.column_names(), path.as_ref(), format)
+    }
+
+    /// Write the struct's columns of the view's dataframe to a CSV file with a header row.
+    ///
+    /// The header uses the struct's column names, and columns of the dataframe that the struct
+    /// doesn't declare aren't written.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the file can't be created or written.
+    #[cfg(feature = "io")]
+    fn write_csv(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        io::write(
+            &self.frame(),
+            self.column_names(),
+            path.as_ref(),
+            io::Format::Csv,
+        )
+    }
+
+    /// Write the struct's columns of the view's dataframe to a newline delimited JSON file.
+    ///
+    /// Each row is written as an object keyed by the struct's column names, and columns of the
+    /// dataframe that the struct doesn't declare aren't written.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the file can't be created or written.
+    #[cfg(feature = "io")]
+    fn write_ndjson(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        io::write(
+            &self.frame(),
+            self.column_names(),
+            path.as_ref(),
+            io::Format::Ndjson,
+        )
+    }
+
+    /// Export the struct's columns of the view's dataframe over the Arrow C stream interface.
+    ///
+    /// The stream yields one struct array per chunk of the dataframe, with one field per column
+    /// of the struct, in field order. The arrays share their buffers with the dataframe, so
+    /// consumers in the same process, such as pyarrow or DuckDB, can read the view's data without
+    /// a copy. Columns of the dataframe that the struct doesn't declare aren't exported.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the struct's columns can't be combined into struct arrays.
+    #[cfg(feature = "ffi")]
+    fn export_ffi(&self) -> Result<ArrowArrayStream> {
+        ffi::export_stream(&self.frame(), self.column_names())
+    }
+
+    /// Fold every row into an accumulator, leaving out skipped rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first row error that is encountered, see [RowViewOps::get_unskipped].
+    fn fold<B, F>(&self, init: B, mut f: F) -> Result<B>
+    where
+        F: FnMut(B, Self::Row) -> B,
+    {
+        (0..self.len()).try_fold(init, |acc, idx| {
+            Ok(match self.get_unskipped(idx)? {
+                Some(row) => f(acc, row),
+                None => acc,
+            })
+        })
+    }
+
+    /// Fold every row into an accumulator with a fallible closure, stopping at the first error.
+    /// Skipped rows are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from the closure or from getting a row, see
+    /// [RowViewOps::get_unskipped].
+    fn try_fold<B, E, F>(&self, init: B, mut f: F) -> std::result::Result<B, E>
+    where
+        E: From<ColbackError>,
+        F: FnMut(B, Self::Row) -> std::result::Result<B, E>,
+    {
+        (0..self.len()).try_fold(init, |acc, idx| match self.get_unskipped(idx)? {
+            Some(row) => f(acc, row),
+            None => Ok(acc),
+        })
+    }
+
+    /// Sum a value selected from every row, leaving out skipped rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first row error that is encountered, see [RowViewOps::get_unskipped].
+    fn sum_by<T, F>(&self, mut f: F) -> Result<T>
+    where
+        T: Sum,
+        F: FnMut(&Self::Row) -> T,
+    {
+        (0..self.len())
+            .filter_map(|idx| self.get_unskipped(idx).transpose())
+            .map(|row| row.map(|row| f(&row)))
+            .sum()
+    }
+
+    /// Get the row with the minimum key, or the first of them if several are equally minimal.
+    ///
+    /// Returns `None` if the view is empty or every row is skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first row error that is encountered, see [RowViewOps::get_unskipped].
+    fn min_by_key<K, F>(&self, mut f: F) -> Result<Option<Self::Row>>
+    where
+        K: Ord,
+        F: FnMut(&Self::Row) -> K,
+    {
+        let best = self.fold(None, |best: Option<(K, Self::Row)>, row| {
+            let key = f(&row);
+            match best {
+                Some((ref best_key, _)) if *best_key <= key => best,
+                _ => Some((key, row)),
+            }
+        })?;
+        Ok(best.map(|(_, row)| row))
+    }
+
+    /// Get the row with the maximum key, or the last of them if several are equally maximal.
+    ///
+    /// Returns `None` if the view is empty or every row is skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first row error that is encountered, see [RowViewOps::get_unskipped].
+    fn max_by_key<K, F>(&self, mut f: F) -> Result<Option<Self::Row>>
+    where
+        K: Ord,
+        F: FnMut(&Self::Row) -> K,
+    {
+        let best = self.fold(None, |best: Option<(K, Self::Row)>, row| {
+            let key = f(&row);
+            match best {
+                Some((ref best_key, _)) if *best_key > key => best,
+                _ => Some((key, row)),
+            }
+        })?;
+        Ok(best.map(|(_, row)| row))
+    }
+
+    /// Split the view's dataframe into partitions keyed by a value computed from each row.
+    ///
+    /// Each partition keeps the rows of its key in their original order, and has the same columns
+    /// as the view's dataframe so it can be viewed as the same struct. Skipped rows aren't in any
+    /// partition.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first row error that is encountered, see [RowViewOps::get_unskipped].
+    fn partition_by<K, F>(&self, mut f: F) -> Result<HashMap<K, DataFrame>>
+    where
+        K: Eq + Hash,
+        F: FnMut(&Self::Row) -> K,
+    {
+        let mut indices: HashMap<K, Vec<IdxSize>> = HashMap::new();
+        for idx in 0..self.len() {
+            if let Some(row) = self.get_unskipped(idx)? {
+                indices.entry(f(&row)).or_default().push(idx as IdxSize);
+            }
+        }
+        indices
+            .into_iter()
+            .map(|(key, idx)| Ok((key, self.frame().take(&IdxCa::from_vec("".into(), idx))?)))
+            .collect()
+    }
+
+    /// Build a hash index from a key computed from each row, requiring every key to be unique.
+    /// Skipped rows aren't indexed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first row error that is encountered, see [RowViewOps::get_unskipped], or
+    /// [ColbackError::DuplicateKey] for the first row whose key was already seen.
+    fn index_by<K, F>(&self, f: F) -> Result<ColbackIndex<'_, Self, K>>
+    where
+        Self: Sized,
+        K: Eq + Hash,
+        F: FnMut(&Self::Row) -> K,
+    {
+        ColbackIndex::new(self, f, true)
+    }
+
+    /// Build a hash index from a key computed from each row, allowing several rows per key.
+    /// Skipped rows aren't indexed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first row error that is encountered, see [RowViewOps::get_unskipped].
+    fn index_by_multi<K, F>(&self, f: F) -> Result<ColbackIndex<'_, Self, K>>
+    where
+        Self: Sized,
+        K: Eq + Hash,
+        F: FnMut(&Self::Row) -> K,
+    {
+        ColbackIndex::new(self, f, false)
+    }
+}
+
+/// The schema of a struct's columns, without typed views.
+///
+/// This is implemented by the `ColbackSchema` derive macro, which reads the same field
+/// attributes as [ColbackView] but only generates the schema, so it compiles faster for crates
+/// that just configure readers or validate dataframes. The `ColbackView` derive macro implements
+/// this as well, since it's a supertrait of [ColbackView], so structs don't derive both.
+///
+/// ```rust
+/// use colback::ColbackSchema;
+/// use polars::prelude::DataType;
+///
+/// #[derive(ColbackSchema)]
+/// struct MyRow {
+///     col_a: u32,
+///     #[polars(name = "b", null = "option")]
+///     col_b: Option<String>,
+/// }
+///
+/// assert_eq!(MyRow::column_names(), &["col_a", "b"]);
+/// assert_eq!(MyRow::dtypes(), vec![DataType::UInt32, DataType::String]);
+/// assert_eq!(MyRow::nullable_columns(), &["b"]);
+/// ```
+pub trait ColbackSchema {
+    /// The names of the columns backing the struct, in field order.
+    fn column_names() -> &'static [&'static str];
+
+    /// The names and dtypes of the columns backing the struct, in field order.
+    fn schema() -> Schema;
+
+    /// The names of the columns that may hold nulls, i.e. those whose fields don't use the
+    /// "error" null handling policy, in field order.
+    fn nullable_columns() -> &'static [&'static str];
+
+    /// The dtypes of the columns backing the struct, in field order.
+    fn dtypes() -> Vec<DataType> {
+        Self::schema().iter_values().cloned().collect()
+    }
+
+    /// Column expressions selecting the struct's columns, in field order.
+    ///
+    /// This can be used to project lazy queries down to the columns that the struct needs.
+    fn projection() -> Vec<Expr> {
+        Self::column_names().iter().map(|name| col(*name)).collect()
+    }
+
+    /// The struct's columns as an arrow schema, in field order.
+    ///
+    /// The fields have the arrow types that polars uses for [ColbackSchema::schema], and only the
+    /// columns in [ColbackSchema::nullable_columns] are marked as nullable.
+    fn arrow_schema() -> ArrowSchema {
+        schema::arrow_schema(&Self::schema(), Self::nullable_columns())
+    }
+
+    /// The struct's columns as JSON, for consumers outside of Rust such as validators, schema
+    /// registries, or API documentation.
+    ///
+    /// The schema is an object with a `columns` array holding the `name`, polars `dtype`, and
+    /// `nullable` flag of every column, in field order.
+    fn schema_json() -> String {
+        schema::to_json(&Self::schema(), Self::nullable_columns())
+    }
+
+    /// Compare the struct's columns with those of a dataframe.
+    ///
+    /// This lists the missing and extra columns, the columns with the wrong dtype, and the
+    /// columns with nulls that the struct doesn't allow, rather than stopping at the first error
+    /// like [ColbackView::view].
+    fn schema_diff(df: &DataFrame) -> SchemaDiff {
+        schema::diff(&Self::schema(), Self::nullable_columns(), df)
+    }
+}
+
 /// Trait for a struct that contains a reference to a row of a dataframe.
 ///
 /// This is typically implemented by the crate's derive macros. This is implemented on a fully
-/// realized struct and defines the associated generated row reference struct types.
-pub trait ColbackView: Sized {
+/// realized struct and defines the associated generated row reference struct types. The
+/// struct's schema comes from [ColbackSchema], which the derive macro implements as well.
+pub trait ColbackView: ColbackSchema + Sized {
     /// Stores the underlying data required for row reference structs.
     ///
     /// This has a reference to the dataframe and the extracted column chunks and is used to
     /// generate the row reference proxies.
-    type View<'a>
+    ///
+    /// Views are `Send + Sync`, so they can be shared across threads.
+    type View<'a>: RowViewOps<'a, Row = Self::RowRef<'a>> + Send + Sync
     where
         Self: 'a;
 
     /// The proxy class that represents a row.
-    type RowRef<'a>
+    ///
+    /// String fields borrow from the dataframe, see [String fields](crate#string-fields). Rows
+    /// implement [RowAccess], so generic code can read their fields by column name.
+    type RowRef<'a>: RowAccess + Send + Sync
     where
         Self: 'a;
 
+    /// An enum with a variant for each of the struct's fields.
+    type Field: ColumnField;
+
+    /// Buffers for each of the struct's columns, used to collect owned rows into a dataframe.
+    type Columns: ColumnBuffers<Self>;
+
     /// Create a view struct for a given dataframe.
     ///
     /// The view struct can be used to generate row reference proxy structs.
@@ -103,45 +886,740 @@ pub trait ColbackView: Sized {
     /// This may throw an error if the dataframe is missing data, has nulls (depending on the null
     /// handling policy), or if there are dtype mismatches. See [ColbackError] for more details.
     fn view(df: &DataFrame) -> Result<Self::View<'_>>;
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use colback_derive::ColbackView;
-    use polars::df;
+    /// The names of the columns whose nulls are filled from other rows, i.e. those whose fields
+    /// use the "forward", "backward", or "mean" fill, in field order.
+    ///
+    /// These fills depend on the whole dataframe, so viewing part of a dataframe on its own can
+    /// fill its nulls differently than viewing the whole dataframe.
+    fn filled_columns() -> &'static [&'static str];
 
-    #[test]
-    fn ui_pass() {
-        let t = trybuild::TestCases::new();
-        t.pass("tests/ui/pass_*.rs");
+    /// Create a view struct for a dataframe passed from Python.
+    ///
+    /// Errors convert into Python exceptions with `?` in functions that return a `PyResult`.
+    ///
+    /// # Errors
+    ///
+    /// This errors under the same conditions as [ColbackView::view].
+    #[cfg(feature = "pyo3")]
+    fn view_py(df: &pyo3_polars::PyDataFrame) -> Result<Self::View<'_>> {
+        Self::view(&df.0)
     }
 
-    #[test]
-    fn ui_fail() {
-        let t = trybuild::TestCases::new();
-        t.compile_fail("tests/ui/fail_*.rs");
+    /// Map every row of a struct column whose fields match the struct to a value.
+    ///
+    /// This is the body of a row-wise expression plugin: the kernel gets one row reference at a
+    /// time, and the values it returns are collected into the plugin's output series. See
+    /// [map_struct].
+    ///
+    /// # Errors
+    ///
+    /// This errors under the same conditions as [map_struct].
+    #[cfg(feature = "plugin")]
+    fn map_struct<T, F>(series: &polars::prelude::Series, f: F) -> Result<polars::prelude::Series>
+    where
+        Self: 'static,
+        F: for<'a> FnMut(Self::RowRef<'a>) -> T,
+        polars::prelude::Series: polars::prelude::NamedFrom<Vec<T>, [T]>,
+    {
+        plugin::map_struct::<Self, T, F>(series, f)
     }
 
-    #[test]
-    fn test_extract_struct() {
-        #[derive(ColbackView, Eq, PartialEq)]
-        struct SomeStruct {
-            row_a: u32,
-            row_b: bool,
-        }
+    /// Read a CSV file, parsing the struct's columns with the struct's dtypes.
+    ///
+    /// The struct's dtypes override the dtypes that polars would otherwise infer for those
+    /// columns, so files whose values happen to look like a different type (e.g. numeric
+    /// identifiers in a string column) are still read consistently. The result is projected down
+    /// to the struct's columns, in field order.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the file can't be read or parsed with the struct's dtypes, or under the
+    /// same conditions as [ColbackView::view].
+    #[cfg(feature = "io")]
+    fn read_csv(
+        path: impl AsRef<std::path::Path>,
+        options: polars::prelude::CsvReadOptions,
+    ) -> Result<DataFrame> {
+        let df = io::read_csv(path.as_ref(), options, Self::schema())?;
+        Self::select(&df)
+    }
 
-        let df = df! [
-            "row_a" => [0u32, 1u32],
-            "row_b" => [true, false],
-        ]
-        .unwrap();
+    /// Memory map an IPC (Arrow) file, projected down to the struct's columns and validated.
+    ///
+    /// The returned dataframe references the mapped file instead of copying it, so large files can
+    /// be viewed with [ColbackView::view] without reading them into memory up front. Compressed
+    /// files can't be memory mapped and are read normally instead.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the file can't be opened or read, or under the same conditions as
+    /// [ColbackView::view].
+    #[cfg(feature = "io")]
+    fn view_ipc_mmap(path: impl AsRef<std::path::Path>) -> Result<DataFrame> {
+        let df = io::read_ipc_mmap(path.as_ref())?;
+        Self::select(&df)
+    }
 
-        let x = SomeStruct::view(&df).unwrap();
+    /// Convert an arrow-rs record batch into a dataframe projected down to the struct's columns,
+    /// and validate it.
+    ///
+    /// The batch's buffers are shared with the dataframe rather than copied, so sources such as
+    /// Arrow Flight or DataFusion can be viewed with [ColbackView::view] without a copy.
+    ///
+    /// # Errors
+    ///
+    /// This errors if one of the batch's arrays has a type that polars can't represent, or under
+    /// the same conditions as [ColbackView::view].
+    #[cfg(feature = "arrow")]
+    fn view_record_batch(batch: &arrow::record_batch::RecordBatch) -> Result<DataFrame> {
+        let df = record_batch::to_dataframe(batch)?;
+        Self::select(&df)
+    }
 
-        {
-            let row_ref = x.get(0).unwrap();
-            assert_eq!(row_ref.row_a, 0);
+    /// Convert a DataFusion record batch stream into a stream of dataframes projected down to the
+    /// struct's columns.
+    ///
+    /// The stream's schema is validated against the struct once, up front, so every batch it
+    /// yields can be viewed with [ColbackView::view].
+    ///
+    /// # Errors
+    ///
+    /// This errors under the same conditions as [ColbackView::view_record_batch] if the stream's
+    /// schema doesn't match the struct. The returned stream yields an error for every batch that
+    /// DataFusion fails to produce.
+    #[cfg(feature = "datafusion")]
+    fn view_record_batch_stream(
+        stream: datafusion::execution::SendableRecordBatchStream,
+    ) -> Result<impl futures::Stream<Item = Result<DataFrame>> + Send + 'static> {
+        batch_stream::view_stream::<Self>(stream)
+    }
+
+    /// Import a stream of struct arrays over the Arrow C stream interface, projected down to the
+    /// struct's columns and validated.
+    ///
+    /// This lets data handed over by another runtime in the same process, such as pyarrow or
+    /// DuckDB, be viewed with [ColbackView::view] without a round trip through a file. The stream
+    /// is moved out of `stream`, which is left released, and the returned dataframe owns the
+    /// imported buffers, so it can outlive the producer's handle to the stream.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the stream was already released, if it doesn't hold struct arrays, if the
+    /// producer reports an error, or under the same conditions as [ColbackView::view].
+    ///
+    /// # Safety
+    ///
+    /// `stream` must be a valid Arrow C stream whose arrays are valid according to the Arrow C
+    /// data interface.
+    #[cfg(feature = "ffi")]
+    unsafe fn view_ffi(stream: &mut ArrowArrayStream) -> Result<DataFrame> {
+        // SAFETY: upheld by the caller
+        let df = unsafe { ffi::import_stream(stream)? };
+        Self::select(&df)
+    }
+
+    /// Run a DuckDB query, and convert its results into a dataframe projected down to the
+    /// struct's columns and validated.
+    ///
+    /// The results are received from DuckDB as Arrow record batches, and converted as in
+    /// [ColbackView::view_record_batch].
+    ///
+    /// # Errors
+    ///
+    /// This errors if the query can't be prepared or run, or under the same conditions as
+    /// [ColbackView::view_record_batch].
+    #[cfg(feature = "duckdb")]
+    fn query_duckdb(
+        conn: &duckdb::Connection,
+        sql: &str,
+        params: impl duckdb::Params,
+    ) -> Result<DataFrame> {
+        duckdb_query::query::<Self>(conn, sql, params)
+    }
+
+    /// Lazily scan a parquet file, projected down to the struct's columns.
+    ///
+    /// The file's schema is checked against [ColbackSchema::schema] up front, using only the file's
+    /// metadata, and the projection is pushed down into the scan so that columns the struct
+    /// doesn't declare are never read.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the file can't be scanned, or if it's missing one of the struct's columns or
+    /// has one with the wrong dtype.
+    #[cfg(feature = "io")]
+    fn scan_parquet(path: impl AsRef<std::path::Path>) -> Result<polars::prelude::LazyFrame> {
+        io::scan(path.as_ref(), io::ScanFormat::Parquet, &Self::schema())
+    }
+
+    /// Lazily scan an IPC (Arrow) file, projected down to the struct's columns.
+    ///
+    /// See [ColbackView::scan_parquet].
+    ///
+    /// # Errors
+    ///
+    /// This errors if the file can't be scanned, or if it's missing one of the struct's columns or
+    /// has one with the wrong dtype.
+    #[cfg(feature = "io")]
+    fn scan_ipc(path: impl AsRef<std::path::Path>) -> Result<polars::prelude::LazyFrame> {
+        io::scan(path.as_ref(), io::ScanFormat::Ipc, &Self::schema())
+    }
+
+    /// Build a dataframe from owned rows.
+    ///
+    /// Each field is collected into its own column builder, so the rows are only traversed once.
+    /// The columns have the dtypes that [ColbackView::view] expects, so the result can be viewed as
+    /// the struct.
+    fn to_dataframe(rows: impl IntoIterator<Item = Self>) -> Result<DataFrame> {
+        rows.into_iter().collect::<ColbackFrame<Self>>().finish()
+    }
+
+    /// Project a dataframe down to exactly the struct's columns, in field order.
+    ///
+    /// # Errors
+    ///
+    /// The dataframe is validated against the struct before it is projected, so this errors under
+    /// the same conditions as [ColbackView::view].
+    fn select(df: &DataFrame) -> Result<DataFrame> {
+        Self::view(df)?;
+        Ok(df.select(Self::column_names().iter().copied())?)
+    }
+
+    /// Wrap a producer of batches from a custom source as a lazy scan with the struct's schema.
+    ///
+    /// See [scan_batches].
+    ///
+    /// # Errors
+    ///
+    /// This errors under the same conditions as [scan_batches].
+    fn scan_batches<F>(producer: F) -> Result<LazyFrame>
+    where
+        Self: 'static,
+        F: FnMut() -> Result<Option<DataFrame>> + Send + 'static,
+    {
+        scan::scan_batches::<Self, F>(producer)
+    }
+
+    /// Collect a lazy query projected down to the struct's columns, and validate the result.
+    ///
+    /// The projection is added before the query is collected, so only the struct's columns are
+    /// materialized. The returned dataframe can then be viewed with [ColbackView::view].
+    ///
+    /// # Errors
+    ///
+    /// This errors if the query fails, including when it's missing one of the struct's columns,
+    /// or under the same conditions as [ColbackView::view].
+    fn collect_view(lf: LazyFrame) -> Result<DataFrame> {
+        let df = lf.select(Self::projection()).collect()?;
+        Self::view(&df)?;
+        Ok(df)
+    }
+
+    /// Run a polars SQL query, and collect its result projected down to the struct's columns.
+    ///
+    /// The query runs against the frames registered with `ctx`, and its result is collected and
+    /// validated as in [ColbackView::collect_view].
+    ///
+    /// # Errors
+    ///
+    /// This errors if the query can't be parsed or planned, or under the same conditions as
+    /// [ColbackView::collect_view].
+    #[cfg(feature = "sql")]
+    fn sql(query: &str, ctx: &mut polars::sql::SQLContext) -> Result<DataFrame> {
+        Self::collect_view(ctx.execute(query)?)
+    }
+
+    /// Run a lazy query for the struct without blocking the async runtime, and stream the result
+    /// in batches of `batch_size` rows, except for the last batch which may be shorter.
+    ///
+    /// The query is projected down to the struct's columns as in [ColbackView::collect_view], and
+    /// runs on polars' streaming engine from tokio's blocking thread pool, so this must be polled
+    /// within a tokio runtime. Batches are sent as the engine produces them, so the whole result
+    /// is never held in memory. The engine waits while a batch hasn't been polled yet, and stops
+    /// when the stream is dropped. Every batch can be viewed with [ColbackView::view].
+    ///
+    /// # Errors
+    ///
+    /// The stream yields a single error if `batch_size` is zero. Otherwise it ends with an error
+    /// if the blocking task fails, if the query fails, or if a batch can't be viewed as the
+    /// struct (see [ColbackView::view]).
+    #[cfg(feature = "async")]
+    fn stream_async(
+        lf: LazyFrame,
+        batch_size: usize,
+    ) -> impl futures::Stream<Item = Result<DataFrame>> + Send + 'static
+    where
+        Self: 'static,
+    {
+        stream::stream_batches::<Self>(lf, batch_size)
+    }
+
+    /// Vertically concatenate dataframes after validating each of them against the struct.
+    ///
+    /// Every dataframe is validated before anything is concatenated, so a dtype or column
+    /// mismatch in any input can't silently change the result. The output is projected down to
+    /// the struct's columns, in field order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [ColbackError::InvalidFrames] with the index and error of every dataframe that
+    /// failed validation.
+    fn concat(dfs: &[&DataFrame]) -> Result<DataFrame> {
+        let errors: Vec<_> = dfs
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, df)| Self::view(df).err().map(|e| (idx, e)))
+            .collect();
+        if !errors.is_empty() {
+            return Err(ColbackError::InvalidFrames(errors));
+        }
+
+        let mut frames = dfs
+            .iter()
+            .map(|df| df.select(Self::column_names().iter().copied()));
+        let Some(first) = frames.next() else {
+            return Ok(DataFrame::empty());
+        };
+        let mut out = first?;
+        for df in frames {
+            out.vstack_mut_owned(df?)?;
+        }
+        Ok(out)
+    }
+
+    /// Sort a dataframe by some of the struct's fields.
+    ///
+    /// Fields are sorted in the order that they're given, and the sort is stable.
+    ///
+    /// # Errors
+    ///
+    /// The dataframe is validated against the struct before it is sorted, so this errors under
+    /// the same conditions as [ColbackView::view].
+    fn sort(df: &DataFrame, by: &[SortField<Self::Field>]) -> Result<DataFrame> {
+        Self::view(df)?;
+        let (names, options) = sort::sort_args(by);
+        Ok(df.sort(names, options)?)
+    }
+
+    /// Deduplicate a dataframe by some of the struct's fields.
+    ///
+    /// The order of the rows is preserved.
+    ///
+    /// # Errors
+    ///
+    /// The dataframe is validated against the struct before it is deduplicated, so this errors
+    /// under the same conditions as [ColbackView::view].
+    fn unique_by(
+        df: &DataFrame,
+        by: &[Self::Field],
+        keep: UniqueKeepStrategy,
+    ) -> Result<DataFrame> {
+        Self::view(df)?;
+        let names: Vec<String> = by.iter().map(|f| f.column_name().to_string()).collect();
+        Ok(df.unique_stable(Some(&names), keep, None)?)
+    }
+
+    /// Report which values of some of the struct's fields are duplicated, and where.
+    ///
+    /// # Errors
+    ///
+    /// The dataframe is validated against the struct first, so this errors under the same
+    /// conditions as [ColbackView::view].
+    fn duplicates_report(df: &DataFrame, by: &[Self::Field]) -> Result<DuplicatesReport> {
+        Self::view(df)?;
+        unique::duplicates_report(df, by)
+    }
+
+    /// Summary statistics of the struct's columns in a dataframe.
+    ///
+    /// Numeric columns get their min, max, mean and standard deviation, string columns get
+    /// their number of distinct values, and every column gets its null and non-null counts.
+    ///
+    /// # Errors
+    ///
+    /// The dataframe is validated against the struct first, so this errors under the same
+    /// conditions as [ColbackView::view].
+    fn describe(df: &DataFrame) -> Result<DescribeReport> {
+        Self::view(df)?;
+        describe::describe(df, Self::column_names())
+    }
+
+    /// Unpivot a dataframe from wide to long format, with a row for every value field of each
+    /// row.
+    ///
+    /// The output has the columns of the id fields, a `variable` column with the column name of
+    /// each value field, and a `value` column. Fields are variants of the struct's field enum, so
+    /// the reshape can't refer to columns that the struct doesn't declare.
+    ///
+    /// # Errors
+    ///
+    /// The dataframe is validated against the struct first, so this errors under the same
+    /// conditions as [ColbackView::view]. It also errors if there are no value fields, or with
+    /// [ColbackError::WrongDtype] if the value fields don't all have the same dtype.
+    fn melt(
+        df: &DataFrame,
+        id_fields: &[Self::Field],
+        value_fields: &[Self::Field],
+    ) -> Result<DataFrame> {
+        Self::view(df)?;
+        melt::melt(df, id_fields, value_fields)
+    }
+
+    /// Create a view struct after consolidating the dataframe's chunks.
+    ///
+    /// Random row access has to find the chunk that a row lives in, which gets slower as a frame
+    /// becomes fragmented (e.g. after many `vstack` calls). This rechunks any column of the
+    /// dataframe that has more than one chunk before creating the view.
+    ///
+    /// # Errors
+    ///
+    /// See [ColbackView::view].
+    fn view_rechunked(df: &mut DataFrame) -> Result<Self::View<'_>> {
+        let options = ViewOptions {
+            rechunk: true,
+            ..ViewOptions::new()
+        };
+        Self::view_with(df, options)
+    }
+
+    /// Create a view struct with the given options.
+    ///
+    /// The dataframe is only modified if the options call for it, such as rechunking a
+    /// fragmented frame.
+    ///
+    /// # Errors
+    ///
+    /// See [ColbackView::view].
+    fn view_with(df: &mut DataFrame, options: ViewOptions) -> Result<Self::View<'_>> {
+        if options.should_rechunk(df) {
+            df.rechunk_mut();
+        }
+        Self::view(df)
+    }
+}
+
+/// Iterate over the indices of the rows selected by a boolean mask.
+///
+/// This walks the set bits of each chunk in the mask rather than checking every index, so sparse
+/// masks are cheap to traverse. Null values in the mask are treated as `false`.
+pub fn mask_indices(mask: &BooleanChunked) -> impl Iterator<Item = usize> + '_ {
+    let mut offset = 0;
+    mask.downcast_iter().flat_map(move |arr| {
+        let start = offset;
+        offset += arr.len();
+        arr.values()
+            .true_idx_iter()
+            .filter(move |&i| arr.validity().is_none_or(|v| v.get_bit(i)))
+            .map(move |i| i + start)
+    })
+}
+
+/// Trait for a struct whose fields can be used to group the rows of other views.
+///
+/// This is implemented with the `ColbackKey` derive macro, which also derives [ColbackView]. The
+/// key's fields must use the same column names and types as the fields of the views that are
+/// grouped by it. Structs listed in the key's `#[polars(key_of(...))]` attribute implement
+/// [KeyOf] so that they can be joined with [join].
+pub trait ColbackKey: ColbackView {}
+
+/// Trait for a struct with an owned counterpart to its row references.
+///
+/// This is implemented with the `ColbackOwned` derive macro, which generates a
+/// `<StructName>Owned` struct holding owned copies of the row values (e.g. `String` instead of
+/// `&str`). Owned rows don't borrow the dataframe, so they can be cached, sent across threads or
+/// returned from functions.
+pub trait ColbackOwned: ColbackView {
+    /// The owned version of a row.
+    type Owned: FieldDiffs;
+
+    /// Copy the values of a row reference into an owned row.
+    fn to_owned_row(row: &Self::RowRef<'_>) -> Self::Owned;
+
+    /// Generate the synthetic row at index `idx`, see [ColbackBuilder::sample_df].
+    #[doc(hidden)]
+    fn sample_row(idx: usize, rng: &mut SampleRng) -> Self::Owned;
+
+    /// The struct's columns as arrow-rs fields, for use as a serde_arrow schema.
+    ///
+    /// The fields have the arrow types that polars uses for [ColbackSchema::schema], so arrays that
+    /// serde_arrow builds for them can be viewed as the struct.
+    ///
+    /// # Errors
+    ///
+    /// This errors if one of the struct's dtypes can't be represented by arrow-rs.
+    #[cfg(feature = "serde_arrow")]
+    fn serde_arrow_fields() -> Result<Vec<arrow::datatypes::FieldRef>> {
+        arrow_serde::fields(&Self::schema())
+    }
+
+    /// Convert owned rows into a record batch with serde_arrow, using
+    /// [ColbackOwned::serde_arrow_fields] as the schema.
+    ///
+    /// This is an alternative to the struct's builder for teams that already convert records with
+    /// serde_arrow. The batch can be viewed with [ColbackView::view_record_batch].
+    ///
+    /// # Errors
+    ///
+    /// This errors if the fields can't be built or if serde_arrow fails to convert a row.
+    #[cfg(feature = "serde_arrow")]
+    fn to_record_batch(rows: &[Self::Owned]) -> Result<arrow::record_batch::RecordBatch>
+    where
+        Self::Owned: serde::Serialize,
+    {
+        arrow_serde::to_record_batch(&Self::serde_arrow_fields()?, rows)
+    }
+}
+
+/// Trait for a struct with a generated builder for dataframes with its columns.
+///
+/// This is implemented with the `ColbackBuilder` derive macro, which generates a
+/// `<StructName>Builder` struct backed by a polars chunked array builder for each column. Rows
+/// can be appended as owned rows, as individual field values, or as nulls.
+pub trait ColbackBuilder: ColbackOwned {
+    /// The builder for dataframes with the struct's columns.
+    type Builder;
+
+    /// Create a builder with room for `capacity` rows in every column.
+    fn builder(capacity: usize) -> Self::Builder;
+
+    /// Append an owned row to a builder.
+    ///
+    /// # Errors
+    ///
+    /// This errors with [ColbackError::ValueOutOfRange] if a datetime or `epoch` value doesn't
+    /// fit in its column, in which case the builder is left unchanged.
+    fn push_row(builder: &mut Self::Builder, row: &Self::Owned) -> Result<()>;
+
+    /// Build a dataframe from the rows appended to a builder.
+    ///
+    /// # Errors
+    ///
+    /// This errors if a column can't be cast to the dtype that the struct expects.
+    fn finish_builder(builder: Self::Builder) -> Result<DataFrame>;
+
+    /// Append an owned row to the end of a dataframe.
+    ///
+    /// See [ColbackBuilder::append_rows].
+    ///
+    /// # Errors
+    ///
+    /// See [ColbackBuilder::append_rows].
+    fn append_row(df: &mut DataFrame, row: &Self::Owned) -> Result<()> {
+        Self::append_rows(df, std::iter::once(row))
+    }
+
+    /// Append owned rows to the end of a dataframe.
+    ///
+    /// The rows are collected into a new frame with the struct's builder, which is then stacked
+    /// onto the dataframe, so the existing columns aren't rebuilt. Columns of the dataframe that
+    /// the struct doesn't declare are filled with nulls for the new rows. Each append adds chunks
+    /// to the dataframe, see [ColbackView::view_rechunked] for consolidating them.
+    ///
+    /// # Errors
+    ///
+    /// The dataframe is validated against the struct before anything is appended, so this errors
+    /// under the same conditions as [ColbackView::view], and if a row can't be pushed (see
+    /// [ColbackBuilder::push_row]).
+    fn append_rows<'r, I>(df: &mut DataFrame, rows: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'r Self::Owned>,
+        Self::Owned: 'r,
+    {
+        Self::view(df)?;
+        let rows = rows.into_iter();
+        let mut builder = Self::builder(rows.size_hint().0);
+        for row in rows {
+            Self::push_row(&mut builder, row)?;
+        }
+        let extension = Self::finish_builder(builder)?;
+        let height = extension.height();
+        let columns = df
+            .get_columns()
+            .iter()
+            .map(|c| match extension.column(c.name()) {
+                Ok(column) => column.clone(),
+                Err(_) => Column::full_null(c.name().clone(), height, c.dtype()),
+            })
+            .collect();
+        df.vstack_mut_owned(DataFrame::new(columns)?)?;
+        Ok(())
+    }
+
+    /// Merge owned rows into a dataframe by some of the struct's fields.
+    ///
+    /// Rows of the dataframe whose key matches one of `rows` have the struct's columns replaced
+    /// with the row's values, keeping their position. Rows with a key that doesn't occur in the
+    /// dataframe are appended in order, and columns of the dataframe that the struct doesn't
+    /// declare are filled with nulls for them. If several of `rows` share a key, the last one
+    /// wins. Null keys never match.
+    ///
+    /// # Errors
+    ///
+    /// The dataframe is validated against the struct first, so this errors under the same
+    /// conditions as [ColbackView::view], and if a row can't be pushed (see
+    /// [ColbackBuilder::push_row]).
+    fn upsert(df: &DataFrame, rows: &[Self::Owned], by: &[Self::Field]) -> Result<DataFrame> {
+        Self::view(df)?;
+        let mut builder = Self::builder(rows.len());
+        for row in rows {
+            Self::push_row(&mut builder, row)?;
+        }
+        upsert::upsert(df, Self::finish_builder(builder)?, by)
+    }
+
+    /// Write owned rows to a parquet file with the struct's columns.
+    ///
+    /// The rows are collected with the struct's builder, so the file has the names and dtypes
+    /// that the struct's view expects.
+    ///
+    /// # Errors
+    ///
+    /// This errors if a row can't be pushed (see [ColbackBuilder::push_row]), if a column can't be
+    /// built (see [ColbackBuilder::finish_builder]) or if the file can't be created or written.
+    #[cfg(feature = "io")]
+    fn write_parquet(
+        rows: impl IntoIterator<Item = Self::Owned>,
+        path: impl AsRef<std::path::Path>,
+        options: &polars::prelude::ParquetWriteOptions,
+    ) -> Result<()> {
+        io::write_rows::<Self>(rows, path.as_ref(), io::Format::Parquet(options))
+    }
+
+    /// Write owned rows to a CSV file with the struct's columns and a header row.
+    ///
+    /// # Errors
+    ///
+    /// This errors if a row can't be pushed (see [ColbackBuilder::push_row]), if a column can't be
+    /// built (see [ColbackBuilder::finish_builder]) or if the file can't be created or written.
+    #[cfg(feature = "io")]
+    fn write_csv(
+        rows: impl IntoIterator<Item = Self::Owned>,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<()> {
+        io::write_rows::<Self>(rows, path.as_ref(), io::Format::Csv)
+    }
+
+    /// Write owned rows to a newline delimited JSON file with the struct's columns.
+    ///
+    /// # Errors
+    ///
+    /// This errors if a row can't be pushed (see [ColbackBuilder::push_row]), if a column can't be
+    /// built (see [ColbackBuilder::finish_builder]) or if the file can't be created or written.
+    #[cfg(feature = "io")]
+    fn write_ndjson(
+        rows: impl IntoIterator<Item = Self::Owned>,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<()> {
+        io::write_rows::<Self>(rows, path.as_ref(), io::Format::Ndjson)
+    }
+
+    /// Build owned rows and export them over the Arrow C stream interface.
+    ///
+    /// The rows are collected with the struct's builder, so the stream's struct arrays have the
+    /// names and dtypes that the struct's view expects. See [RowViewOps::export_ffi].
+    ///
+    /// # Errors
+    ///
+    /// This errors if a row can't be pushed (see [ColbackBuilder::push_row]) or if a column can't
+    /// be built (see [ColbackBuilder::finish_builder]).
+    #[cfg(feature = "ffi")]
+    fn export_ffi(rows: impl IntoIterator<Item = Self::Owned>) -> Result<ArrowArrayStream> {
+        let rows = rows.into_iter();
+        let mut builder = Self::builder(rows.size_hint().0);
+        for row in rows {
+            Self::push_row(&mut builder, &row)?;
+        }
+        let df = Self::finish_builder(builder)?;
+        ffi::export_stream(&df, Self::column_names())
+    }
+
+    /// Generate a dataframe with `n` rows of deterministic synthetic data.
+    ///
+    /// The same `n` and `seed` always produce the same dataframe, which makes this useful for
+    /// benchmarks, demos, and golden file tests. Values are generated per field type:
+    ///
+    /// - unsigned integers are sequential ids, starting at 0
+    /// - strings cycle through four categories named after the column, e.g. `city_0`
+    /// - datetimes are hourly, starting at 2024-01-01 00:00
+    /// - signed integers, floats, and booleans are drawn from a generator seeded with `seed`
+    /// - optional fields are null for about one in five rows
+    /// - fields with a `min` or `max` are clamped into their bounds
+    ///
+    /// The struct's `check` constraints aren't taken into account, so the dataframe may not
+    /// satisfy them.
+    fn sample_df(n: usize, seed: u64) -> DataFrame {
+        let mut rng = SampleRng::new(seed);
+        let mut builder = Self::builder(n);
+        for idx in 0..n {
+            Self::push_row(&mut builder, &Self::sample_row(idx, &mut rng))
+                .expect("sample rows fit in the struct's columns");
+        }
+        Self::finish_builder(builder).expect("sample rows have the struct's dtypes")
+    }
+
+    /// A proptest strategy for dataframes with the struct's columns and a number of rows in
+    /// `rows`.
+    ///
+    /// The dataframes are built from arbitrary owned rows with the struct's builder, so they can
+    /// always be viewed as the struct: only fields with the "option" null handling policy hold
+    /// nulls, float fields that error on NaNs or read them as nulls never hold NaNs, datetimes
+    /// are within the range of their column's time unit, and fields with a `min` or `max` are
+    /// within their bounds. The struct's `check` constraints aren't taken into
+    /// account, so when the struct has any, the strategy should be filtered on whether its
+    /// dataframes can be viewed, as in `prop_filter("check", |df| Self::view(df).is_ok())`.
+    #[cfg(feature = "proptest")]
+    fn arb_df(rows: std::ops::Range<usize>) -> impl proptest::strategy::Strategy<Value = DataFrame>
+    where
+        Self::Owned: proptest::arbitrary::Arbitrary,
+    {
+        arbitrary::arb_df::<Self>(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use colback_derive::ColbackView;
+    use polars::{
+        df,
+        prelude::{
+            IntoLazy, JoinType, NamedFrom, NewChunkedArray, Series, SortOptions, TimeUnit,
+            TimeZone, lit,
+        },
+    };
+
+    #[test]
+    fn ui_pass() {
+        let t = trybuild::TestCases::new();
+        t.pass("tests/ui/pass_*.rs");
+    }
+
+    #[test]
+    fn ui_fail() {
+        let t = trybuild::TestCases::new();
+        t.compile_fail("tests/ui/fail_*.rs");
+    }
+
+    #[test]
+    fn test_extract_struct() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: bool,
+        }
+
+        let df = df! [
+            "row_a" => [0u32, 1u32],
+            "row_b" => [true, false],
+        ]
+        .unwrap();
+
+        let x = SomeStruct::view(&df).unwrap();
+
+        {
+            let row_ref = x.get(0).unwrap();
+            assert_eq!(row_ref.row_a, 0);
             assert!(row_ref.row_b);
         }
         {
@@ -150,4 +1628,4532 @@ mod tests {
             assert!(!row_ref.row_b);
         }
     }
+
+    #[test]
+    fn test_get_out_of_bounds() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u64,
+        }
+
+        let df = df! [
+            "row_a" => [0u64, 1u64],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        assert!(matches!(
+            view.get(2),
+            Err(ColbackError::IndexOutOfBounds { idx: 2, len: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_view_rechunked() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+        }
+
+        let mut df = df! [
+            "row_a" => [0u32, 1u32],
+        ]
+        .unwrap();
+        df.vstack_mut(&df.clone()).unwrap();
+        assert_eq!(df.max_n_chunks(), 2);
+
+        let view = SomeStruct::view_rechunked(&mut df).unwrap();
+        let values: Vec<u32> = view.iter().map(|r| r.unwrap().row_a).collect();
+        assert_eq!(values, vec![0, 1, 0, 1]);
+        assert_eq!(view.df().max_n_chunks(), 1);
+    }
+
+    #[test]
+    fn test_iter_sorted() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_a" => [2u32, 0u32, 1u32],
+            "row_b" => ["b", "c", "a"],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let by_key: Vec<&str> = view
+            .iter_sorted_by(|r| r.row_a)
+            .unwrap()
+            .map(|r| r.row_b)
+            .collect();
+        assert_eq!(by_key, vec!["c", "a", "b"]);
+
+        let by_col: Vec<u32> = view
+            .iter_sorted_by_column("row_b", SortOptions::default().with_order_descending(true))
+            .unwrap()
+            .map(|r| r.unwrap().row_a)
+            .collect();
+        assert_eq!(by_col, vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn test_filter() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_a" => [2u32, 0u32, 1u32],
+            "row_b" => ["b", "c", "a"],
+            "row_c" => [1.0f64, 2.0, 3.0],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let filtered = view.filter(|r| r.row_a > 0 && r.row_b != "a").unwrap();
+        let expected = df! [
+            "row_a" => [2u32],
+            "row_b" => ["b"],
+            "row_c" => [1.0f64],
+        ]
+        .unwrap();
+        assert!(filtered.equals(&expected));
+
+        // Skipped rows are filtered out without calling the predicate
+        #[derive(ColbackView)]
+        struct SkipStruct {
+            row_a: u32,
+            #[polars(null = "skip")]
+            row_b: u32,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 3],
+            "row_b" => [Some(1u32), None, Some(3)],
+        ]
+        .unwrap();
+        let view = SkipStruct::view(&df).unwrap();
+        let mut calls = 0;
+        let filtered = view
+            .filter(|_| {
+                calls += 1;
+                true
+            })
+            .unwrap();
+        assert_eq!(calls, 2);
+        let expected = df! [
+            "row_a" => [1u32, 3],
+            "row_b" => [1u32, 3],
+        ]
+        .unwrap();
+        assert!(filtered.equals(&expected));
+    }
+
+    #[test]
+    fn test_map_to_series() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_a" => [2u32, 0u32],
+            "row_b" => ["b", "c"],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let series = view
+            .map_to_series("row_c", |r| format!("{}{}", r.row_b, r.row_a))
+            .unwrap();
+        assert_eq!(series.dtype(), &DataType::String);
+
+        let with_col = view
+            .with_column("row_d", |r| (r.row_a > 0).then_some(r.row_a as f64))
+            .unwrap();
+        let expected = df! [
+            "row_a" => [2u32, 0u32],
+            "row_b" => ["b", "c"],
+            "row_d" => [Some(2.0f64), None],
+        ]
+        .unwrap();
+        assert!(with_col.equals_missing(&expected));
+
+        // Skipped rows are null, so the series still lines up with the dataframe
+        #[derive(ColbackView)]
+        struct SkipStruct {
+            row_a: u32,
+            #[polars(null = "skip")]
+            row_b: u32,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 3],
+            "row_b" => [Some(1u32), None, Some(3)],
+        ]
+        .unwrap();
+        let view = SkipStruct::view(&df).unwrap();
+        let series = view
+            .map_to_series("sum", |r| (r.row_a + r.row_b).to_string())
+            .unwrap();
+        assert_eq!(
+            series.str().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![Some("2"), None, Some("6")]
+        );
+        let with_col = view
+            .with_column("row_c", |r| (r.row_b > 1).then_some(r.row_b))
+            .unwrap();
+        let expected = df! [
+            "row_a" => [1u32, 2, 3],
+            "row_b" => [Some(1u32), None, Some(3)],
+            "row_c" => [None, None, Some(3u32)],
+        ]
+        .unwrap();
+        assert!(with_col.equals_missing(&expected));
+
+        let df = df! [
+            "row_a" => [1u32],
+            "row_b" => [None::<u32>],
+        ]
+        .unwrap();
+        let view = SkipStruct::view(&df).unwrap();
+        let series = view.map_to_series("row_c", |r| r.row_a).unwrap();
+        assert_eq!(series.dtype(), &DataType::UInt32);
+        assert_eq!(series.null_count(), 1);
+    }
+
+    #[test]
+    fn test_select() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_b: String,
+            #[polars(name = "row_a")]
+            a: u32,
+        }
+
+        let df = df! [
+            "row_a" => [2u32, 0u32],
+            "row_b" => ["b", "c"],
+            "row_c" => [1.0f64, 2.0],
+        ]
+        .unwrap();
+
+        assert_eq!(SomeStruct::column_names(), &["row_b", "row_a"]);
+        assert_eq!(SomeStruct::projection(), vec![col("row_b"), col("row_a")]);
+
+        let selected = SomeStruct::select(&df).unwrap();
+        let expected = df! [
+            "row_b" => ["b", "c"],
+            "row_a" => [2u32, 0u32],
+        ]
+        .unwrap();
+        assert!(selected.equals(&expected));
+
+        let missing = df.drop("row_a").unwrap();
+        assert!(matches!(
+            SomeStruct::select(&missing),
+            Err(ColbackError::MissingColumn(_))
+        ));
+    }
+
+    #[test]
+    fn test_sort() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 0u32, 1u32, 0u32],
+            "b" => [Some("x"), Some("y"), None, Some("z")],
+        ]
+        .unwrap();
+
+        assert_eq!(SomeStructField::RowB.column_name(), "b");
+        let sorted = SomeStruct::sort(
+            &df,
+            &[
+                SortField::desc(SomeStructField::RowA),
+                SortField::asc(SomeStructField::RowB).nulls_last(),
+            ],
+        )
+        .unwrap();
+        let expected = df! [
+            "row_a" => [1u32, 1u32, 0u32, 0u32],
+            "b" => [Some("x"), None, Some("y"), Some("z")],
+        ]
+        .unwrap();
+        assert!(sorted.equals_missing(&expected));
+    }
+
+    #[test]
+    fn test_unique() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+            row_c: bool,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 0u32, 1u32, 0u32, 1u32],
+            "row_b" => ["x", "y", "x", "z", "x"],
+            "row_c" => [true, false, false, true, true],
+        ]
+        .unwrap();
+        let by = [SomeStructField::RowA, SomeStructField::RowB];
+
+        let unique = SomeStruct::unique_by(&df, &by, UniqueKeepStrategy::Last).unwrap();
+        let expected = df! [
+            "row_a" => [0u32, 0u32, 1u32],
+            "row_b" => ["y", "z", "x"],
+            "row_c" => [false, true, true],
+        ]
+        .unwrap();
+        assert!(unique.equals(&expected));
+
+        let report = SomeStruct::duplicates_report(&df, &by).unwrap();
+        let expected_keys = df! [
+            "row_a" => [1u32],
+            "row_b" => ["x"],
+        ]
+        .unwrap();
+        assert!(report.keys.equals(&expected_keys));
+        assert_eq!(report.indices, vec![vec![0, 2, 4]]);
+
+        let report = SomeStruct::duplicates_report(&df, &[SomeStructField::RowB]).unwrap();
+        assert_eq!(report.indices.len(), 1);
+        assert!(
+            SomeStruct::duplicates_report(&df.head(Some(2)), &by)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_group_by() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        #[derive(ColbackKey, Eq, PartialEq)]
+        struct SomeKey {
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2u32, 3u32, 4u32],
+            "row_b" => ["x", "y", "x", "x"],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let groups = view.group_by::<SomeKey>().unwrap();
+        assert_eq!(groups.len(), 2);
+        let summary: Vec<(&str, Vec<u32>)> = groups
+            .iter()
+            .unwrap()
+            .map(|group| {
+                let (key, group_view) = group.unwrap();
+                let values = group_view.iter().map(|r| r.unwrap().row_a).collect();
+                (key.row_b, values)
+            })
+            .collect();
+        assert_eq!(summary, vec![("x", vec![1, 3, 4]), ("y", vec![2])]);
+
+        #[derive(ColbackKey, Eq, PartialEq)]
+        struct BadKey {
+            row_c: String,
+        }
+        assert!(matches!(
+            view.group_by::<BadKey>(),
+            Err(ColbackError::MissingColumn(_))
+        ));
+    }
+
+    #[test]
+    fn test_join() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct Trade {
+            symbol: String,
+            qty: u32,
+        }
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct Quote {
+            symbol: String,
+            bid: i64,
+        }
+
+        #[derive(ColbackKey, Eq, PartialEq)]
+        #[polars(key_of(Trade, Quote))]
+        struct Symbol {
+            symbol: String,
+        }
+
+        let trades = df! [
+            "symbol" => ["a", "b", "a"],
+            "qty" => [1u32, 2u32, 3u32],
+        ]
+        .unwrap();
+        let quotes = df! [
+            "symbol" => ["a", "c"],
+            "bid" => [10i64, 20i64],
+        ]
+        .unwrap();
+
+        let joined = join::<Trade, Quote, Symbol>(&trades, &quotes, JoinType::Inner).unwrap();
+        let expected = df! [
+            "symbol" => ["a", "a"],
+            "qty" => [1u32, 3u32],
+            "bid" => [10i64, 10i64],
+        ]
+        .unwrap();
+        assert!(joined.equals(&expected));
+
+        let bad_quotes = df! [
+            "symbol" => [1u32, 2u32],
+            "bid" => [10i64, 20i64],
+        ]
+        .unwrap();
+        assert!(matches!(
+            join::<Trade, Quote, Symbol>(&trades, &bad_quotes, JoinType::Inner),
+            Err(ColbackError::WrongDtype { .. })
+        ));
+    }
+
+    #[test]
+    fn test_asof_join() {
+        use chrono::{DateTime, NaiveDateTime, TimeDelta};
+
+        #[derive(ColbackView, PartialEq)]
+        struct Trade {
+            symbol: String,
+            ts: NaiveDateTime,
+            qty: u32,
+        }
+
+        #[derive(ColbackView, PartialEq)]
+        struct Quote {
+            symbol: String,
+            #[polars(name = "quote_ts")]
+            ts: NaiveDateTime,
+            bid: f64,
+        }
+
+        #[derive(ColbackKey, Eq, PartialEq)]
+        #[polars(key_of(Trade, Quote))]
+        struct Symbol {
+            symbol: String,
+        }
+
+        #[derive(ColbackView, PartialEq)]
+        struct TradeWithQuote {
+            ts: NaiveDateTime,
+            qty: u32,
+            #[polars(null = "option")]
+            bid: Option<f64>,
+        }
+
+        let ts = |secs: i64| DateTime::from_timestamp(secs, 0).unwrap().naive_utc();
+        let trades = df! [
+            "symbol" => ["a", "b", "a"],
+            "ts" => [ts(10), ts(11), ts(20)],
+            "qty" => [1u32, 2u32, 3u32],
+        ]
+        .unwrap();
+        let quotes = df! [
+            "symbol" => ["a", "b", "a"],
+            "quote_ts" => [ts(8), ts(1), ts(12)],
+            "bid" => [1.0f64, 2.0, 3.0],
+        ]
+        .unwrap();
+
+        let joined = AsofJoin::<Trade, Quote>::new(TradeField::Ts, QuoteField::Ts)
+            .by::<Symbol>()
+            .tolerance(TimeDelta::seconds(5))
+            .join(&trades, &quotes)
+            .unwrap();
+        let view = TradeWithQuote::view(&joined).unwrap();
+        let rows: Vec<_> = view
+            .iter()
+            .map(|r| {
+                let r = r.unwrap();
+                (r.ts, r.qty, r.bid)
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![(ts(10), 1, Some(1.0)), (ts(11), 2, None), (ts(20), 3, None)]
+        );
+    }
+
+    #[test]
+    fn test_datetime_time_unit() {
+        use chrono::{DateTime, NaiveDateTime};
+
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            #[polars(time_unit = "us")]
+            ts: NaiveDateTime,
+        }
+
+        let ts = DateTime::from_timestamp(1_700_000_000, 1_000_000)
+            .unwrap()
+            .naive_utc();
+        let df = df! [
+            "ts" => [ts],
+        ]
+        .unwrap();
+        assert!(matches!(
+            SomeStruct::view(&df),
+            Err(ColbackError::WrongDtype { .. })
+        ));
+
+        let df = df
+            .lazy()
+            .with_column(col("ts").cast(DataType::Datetime(TimeUnit::Microseconds, None)))
+            .collect()
+            .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        assert_eq!(view.get(0).unwrap().ts, ts);
+
+        // Timestamps past what chrono can represent are out of range rather than null
+        let df = df!("ts" => [i64::MAX])
+            .unwrap()
+            .lazy()
+            .with_column(col("ts").cast(DataType::Datetime(TimeUnit::Microseconds, None)))
+            .collect()
+            .unwrap();
+        assert!(matches!(
+            SomeStruct::view(&df).unwrap().get(0),
+            Err(ColbackError::ValueOutOfRange { idx: 0, ref col, .. }) if col == "ts"
+        ));
+    }
+
+    #[test]
+    fn test_time_zone() {
+        use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta};
+
+        #[derive(ColbackView)]
+        struct Strict {
+            #[polars(time_zone = "UTC")]
+            ts: NaiveDateTime,
+        }
+
+        #[derive(ColbackView)]
+        struct Converted {
+            #[polars(time_zone = "UTC", convert_time_zone)]
+            ts: NaiveDateTime,
+        }
+
+        #[derive(ColbackView)]
+        struct NewYork {
+            #[polars(time_zone = "America/New_York", convert_time_zone)]
+            ts: NaiveDateTime,
+        }
+
+        #[derive(ColbackView)]
+        struct Local {
+            #[polars(convert_time_zone)]
+            ts: NaiveDateTime,
+        }
+
+        let ts = DateTime::from_timestamp(1_700_000_000, 0)
+            .unwrap()
+            .naive_utc();
+        let later = ts + TimeDelta::hours(1);
+        let naive = df! [
+            "ts" => [ts, later],
+        ]
+        .unwrap();
+        assert!(matches!(
+            Strict::view(&naive),
+            Err(ColbackError::TimezoneMismatch { ref expected, actual: None, .. })
+                if expected.as_deref() == Some("UTC")
+        ));
+        assert_eq!(Converted::view(&naive).unwrap().get(0).unwrap().ts, ts);
+
+        // Time zone aware columns hold UTC timestamps, so converting keeps the value
+        let new_york = TimeZone::opt_try_new(Some("America/New_York")).unwrap();
+        let aware = naive
+            .clone()
+            .lazy()
+            .with_column(
+                col("ts")
+                    .cast(DataType::Int64)
+                    .cast(DataType::Datetime(TimeUnit::Milliseconds, new_york)),
+            )
+            .collect()
+            .unwrap();
+        assert!(matches!(
+            Strict::view(&aware),
+            Err(ColbackError::TimezoneMismatch { ref actual, .. })
+                if actual.as_deref() == Some("America/New_York")
+        ));
+        assert_eq!(Converted::view(&aware).unwrap().get(0).unwrap().ts, ts);
+
+        let utc = naive
+            .clone()
+            .lazy()
+            .with_column(col("ts").cast(DataType::Int64).cast(DataType::Datetime(
+                TimeUnit::Milliseconds,
+                Some(TimeZone::UTC),
+            )))
+            .collect()
+            .unwrap();
+        let row = Strict::view(&utc).unwrap().get(0).unwrap();
+        assert_eq!(row.ts, ts);
+        assert_eq!(
+            row.get_field("ts").unwrap().dtype(),
+            DataType::Datetime(TimeUnit::Milliseconds, Some(TimeZone::UTC))
+        );
+
+        // Naive columns are local times in the field's time zone, which is 5 hours behind UTC
+        // in November
+        let est = TimeDelta::hours(5);
+        assert_eq!(NewYork::view(&naive).unwrap().get(0).unwrap().ts, ts + est);
+        // and fields without a time zone read aware columns as local times
+        let local = Local::view(&aware).unwrap();
+        assert_eq!(local.get(0).unwrap().ts, ts - est);
+
+        // Committed columns have the field's dtype, so unedited values are converted too
+        let mut edits = Local::view_mut(&aware).unwrap();
+        edits.set_ts(1, later).unwrap();
+        let committed = edits.commit().unwrap();
+        assert_eq!(
+            committed.column("ts").unwrap().dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, None)
+        );
+        let view = Local::view(&committed).unwrap();
+        assert_eq!(view.get(0).unwrap().ts, ts - est);
+        assert_eq!(view.get(1).unwrap().ts, later);
+
+        // Local times skipped by daylight saving time can't be converted
+        let skipped = NaiveDate::from_ymd_opt(2024, 3, 10)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        let gap = df! [
+            "ts" => [skipped],
+        ]
+        .unwrap();
+        assert!(matches!(
+            NewYork::view(&gap).unwrap().get(0),
+            Err(ColbackError::ValueOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_concat() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        let day_1 = df! [
+            "row_a" => [1u32],
+            "row_b" => ["x"],
+        ]
+        .unwrap();
+        let day_2 = df! [
+            "row_b" => ["y"],
+            "row_c" => [true],
+            "row_a" => [2u32],
+        ]
+        .unwrap();
+        let widened = df! [
+            "row_a" => [3u64],
+            "row_b" => ["z"],
+        ]
+        .unwrap();
+        let missing = df! [
+            "row_a" => [4u32],
+        ]
+        .unwrap();
+
+        let concat = SomeStruct::concat(&[&day_1, &day_2]).unwrap();
+        let expected = df! [
+            "row_a" => [1u32, 2u32],
+            "row_b" => ["x", "y"],
+        ]
+        .unwrap();
+        assert!(concat.equals(&expected));
+
+        let Err(ColbackError::InvalidFrames(errors)) =
+            SomeStruct::concat(&[&day_1, &widened, &day_2, &missing])
+        else {
+            panic!("expected invalid frames");
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], (1, ColbackError::WrongDtype { .. })));
+        assert!(matches!(errors[1], (3, ColbackError::MissingColumn(_))));
+    }
+
+    #[test]
+    fn test_fold() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+            #[polars(null = "option")]
+            row_c: Option<i64>,
+        }
+
+        let df = df! [
+            "row_a" => [2u32, 0u32, 3u32, 0u32],
+            "row_b" => ["b", "c", "a", "d"],
+            "row_c" => [Some(1i64), None, Some(5i64), Some(-2i64)],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let names = view.fold(String::new(), |acc, r| acc + r.row_b).unwrap();
+        assert_eq!(names, "bcad");
+
+        let total: std::result::Result<u32, Box<dyn std::error::Error>> =
+            view.try_fold(0, |acc, r| {
+                r.row_c
+                    .map(|_| acc + r.row_a)
+                    .ok_or_else(|| format!("null at {}", r.row_b).into())
+            });
+        assert_eq!(total.unwrap_err().to_string(), "null at c");
+
+        assert_eq!(view.sum_by(|r| r.row_a).unwrap(), 5);
+        assert_eq!(view.sum_by(|r| r.row_c.unwrap_or(0)).unwrap(), 4);
+        assert_eq!(view.min_by_key(|r| r.row_a).unwrap().unwrap().row_b, "c");
+        assert_eq!(view.max_by_key(|r| r.row_c).unwrap().unwrap().row_b, "a");
+
+        let empty = df.clear();
+        let view = SomeStruct::view(&empty).unwrap();
+        assert!(view.min_by_key(|r| r.row_a).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_exprs() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "row b")]
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_a" => [2u32, 0u32, 3u32],
+            "row b" => ["b", "c", "a"],
+        ]
+        .unwrap();
+
+        assert_eq!(SomeStructCol::row_b(), col("row b"));
+        assert_eq!(SomeStructField::RowB.expr(), col("row b"));
+        assert_eq!(SomeStruct::cols(), vec![col("row_a"), col("row b")]);
+
+        let filtered = df
+            .lazy()
+            .filter(SomeStructCol::row_a().gt(lit(1u32)))
+            .select(SomeStruct::cols())
+            .collect()
+            .unwrap();
+        let view = SomeStruct::view(&filtered).unwrap();
+        let values: Vec<&str> = view.iter().map(|r| r.unwrap().row_b).collect();
+        assert_eq!(values, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_filter_builder() {
+        use chrono::{DateTime, NaiveDateTime};
+
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "option")]
+            name: Option<String>,
+            #[polars(time_unit = "us")]
+            at: NaiveDateTime,
+        }
+
+        let at = |secs| DateTime::from_timestamp(secs, 0).unwrap().naive_utc();
+        let df = df! [
+            "row_a" => [2u32, 7u32, 9u32],
+            "name" => [Some("x"), Some("y"), None],
+            "at" => [at(0), at(1), at(2)],
+        ]
+        .unwrap()
+        .lazy()
+        .with_column(col("at").cast(DataType::Datetime(TimeUnit::Microseconds, None)))
+        .collect()
+        .unwrap();
+
+        let rows = |filter: Expr| {
+            let filtered = df.clone().lazy().filter(filter).collect().unwrap();
+            SomeStruct::view(&filtered)
+                .unwrap()
+                .iter()
+                .map(|r| r.unwrap().row_a)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            rows(
+                SomeStructFilter::row_a()
+                    .gt(5)
+                    .or(SomeStructFilter::name().eq("x"))
+            ),
+            vec![2, 7, 9]
+        );
+        assert_eq!(
+            rows(
+                SomeStructFilter::row_a()
+                    .gt_eq(7)
+                    .and(SomeStructFilter::name().is_not_null())
+            ),
+            vec![7]
+        );
+        assert_eq!(rows(SomeStructFilter::at().lt_eq(at(1))), vec![2, 7]);
+    }
+
+    #[test]
+    fn test_transform() {
+        use chrono::{DateTime, NaiveDateTime};
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct Input {
+            name: String,
+            #[polars(null = "option")]
+            secs: Option<i64>,
+        }
+
+        #[derive(ColbackView, Debug, PartialEq)]
+        struct Output {
+            #[polars(name = "upper name")]
+            upper: String,
+            #[polars(null = "option", time_unit = "us")]
+            at: Option<NaiveDateTime>,
+        }
+
+        let df = df! [
+            "name" => ["a", "b"],
+            "secs" => [Some(1i64), None],
+        ]
+        .unwrap();
+        let out = transform::<Input, Output, _>(&df, |row| Output {
+            upper: row.name.to_uppercase(),
+            at: row
+                .secs
+                .map(|s| DateTime::from_timestamp(s, 0).unwrap().naive_utc()),
+        })
+        .unwrap();
+
+        assert_eq!(out.get_column_names(), vec!["upper name", "at"]);
+        let view = Output::view(&out).unwrap();
+        let rows: Vec<_> = view
+            .iter()
+            .map(|r| r.unwrap())
+            .map(|r| (r.upper.to_string(), r.at))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "A".to_string(),
+                    Some(DateTime::from_timestamp(1, 0).unwrap().naive_utc())
+                ),
+                ("B".to_string(), None),
+            ]
+        );
+
+        let empty = Output::to_dataframe(Vec::new()).unwrap();
+        assert_eq!(empty.height(), 0);
+        assert!(Output::view(&empty).is_ok());
+
+        let df = df! [
+            "name" => [Some("a"), None],
+            "secs" => [Some(1i64), None],
+        ]
+        .unwrap();
+        let mut calls = 0;
+        let result = transform::<Input, Output, _>(&df, |row| {
+            calls += 1;
+            Output {
+                upper: row.name.to_string(),
+                at: None,
+            }
+        });
+        assert!(matches!(
+            result,
+            Err(ColbackError::InvalidNull { idx: 1, .. })
+        ));
+        assert_eq!(calls, 1);
+
+        // Skipped rows don't have an output row
+        #[derive(ColbackView)]
+        struct SkipInput {
+            #[polars(null = "skip")]
+            name: String,
+        }
+
+        let df = df!("name" => [Some("a"), None, Some("c")]).unwrap();
+        let out = transform::<SkipInput, Output, _>(&df, |row| Output {
+            upper: row.name.to_uppercase(),
+            at: None,
+        })
+        .unwrap();
+        let expected = df! [
+            "upper name" => ["A", "C"],
+            "at" => [None::<i64>, None],
+        ]
+        .unwrap();
+        assert!(
+            out.column("upper name")
+                .unwrap()
+                .equals(expected.column("upper name").unwrap())
+        );
+        assert_eq!(out.height(), 2);
+    }
+
+    #[test]
+    fn test_partition_by() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            symbol: String,
+            qty: u32,
+        }
+
+        let df = df! [
+            "symbol" => ["a", "b", "a", "c", "a"],
+            "qty" => [1u32, 2, 3, 4, 5],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let partitions = view.partition_by(|row| row.symbol.to_string()).unwrap();
+
+        assert_eq!(partitions.len(), 3);
+        let qty = |symbol: &str| {
+            SomeStruct::view(&partitions[symbol])
+                .unwrap()
+                .iter()
+                .map(|r| r.unwrap().qty)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(qty("a"), vec![1, 3, 5]);
+        assert_eq!(qty("b"), vec![2]);
+        assert_eq!(qty("c"), vec![4]);
+    }
+
+    #[test]
+    fn test_diff() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            id: u32,
+            name: String,
+            #[polars(null = "option")]
+            score: Option<f64>,
+        }
+
+        let old = df! [
+            "id" => [1u32, 2, 3, 4],
+            "name" => ["a", "b", "c", "d"],
+            "score" => [Some(1.0), None, Some(3.0), None],
+        ]
+        .unwrap();
+        let new = df! [
+            "id" => [4u32, 5, 3, 1],
+            "name" => ["d", "e", "x", "a"],
+            "score" => [None, Some(5.0), Some(3.5), Some(1.0)],
+        ]
+        .unwrap();
+
+        let report = diff::<SomeStruct>(&old, &new, &[SomeStructField::Id]).unwrap();
+        assert_eq!(report.added, vec![1]);
+        assert_eq!(report.removed, vec![1]);
+        assert_eq!(
+            report.changed,
+            vec![RowChange {
+                old_idx: 2,
+                new_idx: 2,
+                fields: vec![SomeStructField::Name, SomeStructField::Score],
+            }]
+        );
+        assert!(
+            diff::<SomeStruct>(&old, &old, &[SomeStructField::Id])
+                .unwrap()
+                .is_empty()
+        );
+
+        // NaN scores are unchanged when they're NaN in both frames
+        let nan = df! [
+            "id" => [1u32],
+            "name" => ["a"],
+            "score" => [Some(f64::NAN)],
+        ]
+        .unwrap();
+        assert!(
+            diff::<SomeStruct>(&nan, &nan, &[SomeStructField::Id])
+                .unwrap()
+                .is_empty()
+        );
+
+        let dup = df! [
+            "id" => [1u32, 1],
+            "name" => ["a", "b"],
+            "score" => [Some(1.0), None],
+        ]
+        .unwrap();
+        assert!(matches!(
+            diff::<SomeStruct>(&old, &dup, &[SomeStructField::Id]),
+            Err(ColbackError::DuplicateKey { idx: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_validate_fk() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct Account {
+            id: u32,
+        }
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct Trade {
+            #[polars(null = "option")]
+            account: Option<u32>,
+        }
+
+        let accounts = df! [
+            "id" => [1u32, 2],
+        ]
+        .unwrap();
+        let trades = df! [
+            "account" => [Some(1u32), Some(3), None, Some(4), Some(3)],
+        ]
+        .unwrap();
+
+        assert!(
+            validate_fk::<Trade, Account>(
+                &trades.head(Some(1)),
+                &accounts,
+                TradeField::Account,
+                AccountField::Id
+            )
+            .is_ok()
+        );
+        let Err(ColbackError::ForeignKey(violations)) = validate_fk::<Trade, Account>(
+            &trades,
+            &accounts,
+            TradeField::Account,
+            AccountField::Id,
+        ) else {
+            panic!("expected foreign key violations");
+        };
+        assert_eq!(violations.indices, vec![vec![1, 4], vec![3]]);
+        let keys = Trade::view(&violations.keys).unwrap();
+        let keys: Vec<_> = keys.iter().map(|r| r.unwrap().account).collect();
+        assert_eq!(keys, vec![Some(3), Some(4)]);
+    }
+
+    #[test]
+    fn test_index_by() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            id: String,
+            qty: u32,
+        }
+
+        let df = df! [
+            "id" => ["a", "b", "a"],
+            "qty" => [1u32, 2, 3],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        assert!(matches!(
+            view.index_by(|row| row.id.to_string()),
+            Err(ColbackError::DuplicateKey { idx: 2 })
+        ));
+        let index = view.index_by(|row| row.qty).unwrap();
+        assert_eq!(index.lookup(&2).unwrap().unwrap().id, "b");
+        assert!(index.lookup(&4).unwrap().is_none());
+
+        let index = view.index_by_multi(|row| row.id.to_string()).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.indices("a"), &[0, 2]);
+        let qty: Vec<_> = index.lookup_all("a").map(|r| r.unwrap().qty).collect();
+        assert_eq!(qty, vec![1, 3]);
+        assert_eq!(index.lookup_all("c").count(), 0);
+    }
+
+    #[test]
+    fn test_zip() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct Names {
+            name: String,
+        }
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct Quantities {
+            qty: u32,
+        }
+
+        let df = df! [
+            "name" => ["a", "b"],
+            "qty" => [1u32, 2],
+        ]
+        .unwrap();
+        let names = Names::view(&df).unwrap();
+        let quantities = Quantities::view(&df).unwrap();
+        let pairs: Vec<_> = zip(&names, &quantities)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .map(|(n, q)| (n.name, q.qty))
+            .collect();
+        assert_eq!(pairs, vec![("a", 1), ("b", 2)]);
+
+        // Indices where either row is skipped are left out
+        #[derive(ColbackView)]
+        struct SkipNames {
+            #[polars(null = "skip")]
+            name: String,
+        }
+
+        #[derive(ColbackView)]
+        struct SkipQuantities {
+            #[polars(null = "skip")]
+            qty: u32,
+        }
+
+        let skip_df = df! [
+            "name" => [Some("a"), None, Some("c"), Some("d")],
+            "qty" => [Some(1u32), Some(2), None, Some(4)],
+        ]
+        .unwrap();
+        let skip_names = SkipNames::view(&skip_df).unwrap();
+        let skip_quantities = SkipQuantities::view(&skip_df).unwrap();
+        let pairs: Vec<_> = zip(&skip_names, &skip_quantities)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .map(|(n, q)| (n.name, q.qty))
+            .collect();
+        assert_eq!(pairs, vec![("a", 1), ("d", 4)]);
+
+        let short = df.head(Some(1));
+        let quantities = Quantities::view(&short).unwrap();
+        assert!(matches!(
+            zip(&names, &quantities),
+            Err(ColbackError::LengthMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_context() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct Account {
+            id: u32,
+        }
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct Trade {
+            account: u32,
+            qty: u32,
+        }
+
+        #[derive(ColbackContext, Eq, PartialEq)]
+        struct Frames {
+            accounts: Account,
+            #[polars(name = "fills")]
+            trades: Trade,
+        }
+
+        assert_eq!(Frames::frame_names(), &["accounts", "fills"]);
+
+        let accounts = df! [
+            "id" => [1u32, 2],
+        ]
+        .unwrap();
+        let trades = df! [
+            "account" => [2u32],
+            "qty" => [10u32],
+        ]
+        .unwrap();
+        let frames = HashMap::from([("accounts", accounts.clone()), ("fills", trades)]);
+        let ctx = Frames::view(&frames).unwrap();
+        assert_eq!(ctx.accounts.len(), 2);
+        assert_eq!(ctx.trades.get(0).unwrap().qty, 10);
+
+        let frames = HashMap::from([("fills".to_string(), accounts)]);
+        let Err(ColbackError::InvalidContext(errors)) = Frames::view(&frames) else {
+            panic!("expected context errors");
+        };
+        assert!(matches!(
+            errors.as_slice(),
+            [
+                (accounts, ColbackError::MissingFrame(_)),
+                (fills, ColbackError::MissingColumns(_)),
+            ] if accounts == "accounts" && fills == "fills"
+        ));
+    }
+
+    #[test]
+    fn test_rolling() {
+        use chrono::{DateTime, NaiveDateTime, TimeDelta};
+
+        #[derive(ColbackView, PartialEq)]
+        struct Reading {
+            ts: NaiveDateTime,
+            value: f64,
+        }
+
+        let ts = |secs: i64| DateTime::from_timestamp(secs, 0).unwrap().naive_utc();
+        let df = df! [
+            "ts" => [ts(0), ts(1), ts(5), ts(6)],
+            "value" => [1.0f64, 2.0, 3.0, 4.0],
+        ]
+        .unwrap();
+        let view = Reading::view(&df).unwrap();
+        let sum = |rows: &[ReadingRowRef<'_>]| rows.iter().map(|r| r.value).sum::<f64>();
+
+        let by_rows = view.rolling("sum", RollingWindow::rows(2), sum).unwrap();
+        assert_eq!(
+            by_rows
+                .f64()
+                .unwrap()
+                .into_no_null_iter()
+                .collect::<Vec<_>>(),
+            vec![1.0, 3.0, 5.0, 7.0]
+        );
+
+        let by_period = view
+            .rolling(
+                "sum",
+                RollingWindow::period(ReadingField::Ts, TimeDelta::seconds(5)),
+                sum,
+            )
+            .unwrap();
+        assert_eq!(
+            by_period
+                .f64()
+                .unwrap()
+                .into_no_null_iter()
+                .collect::<Vec<_>>(),
+            vec![1.0, 3.0, 5.0, 7.0]
+        );
+
+        let counts = view
+            .rolling(
+                "count",
+                RollingWindow::period(ReadingField::Ts, TimeDelta::seconds(6)),
+                |rows| rows.len() as u32,
+            )
+            .unwrap();
+        assert_eq!(
+            counts
+                .u32()
+                .unwrap()
+                .into_no_null_iter()
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 3]
+        );
+
+        // Skipped rows are left out of windows and are null in the series
+        #[derive(ColbackView, PartialEq)]
+        struct SkipReading {
+            #[polars(null = "skip")]
+            ts: NaiveDateTime,
+            #[polars(null = "skip")]
+            value: f64,
+        }
+
+        let skip_df = df! [
+            "ts" => [Some(ts(0)), None, Some(ts(2)), Some(ts(3))],
+            "value" => [Some(1.0f64), Some(2.0), None, Some(4.0)],
+        ]
+        .unwrap();
+        let view = SkipReading::view(&skip_df).unwrap();
+        let skip_sum = |rows: &[SkipReadingRowRef<'_>]| rows.iter().map(|r| r.value).sum::<f64>();
+        let by_rows = view
+            .rolling("sum", RollingWindow::rows(2), skip_sum)
+            .unwrap();
+        assert_eq!(
+            by_rows.f64().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![Some(1.0), None, None, Some(5.0)]
+        );
+        let by_period = view
+            .rolling(
+                "sum",
+                RollingWindow::period(SkipReadingField::Ts, TimeDelta::seconds(3)),
+                skip_sum,
+            )
+            .unwrap();
+        assert_eq!(
+            by_period.f64().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![Some(1.0), None, None, Some(4.0)]
+        );
+
+        let unsorted = df.reverse();
+        let view = Reading::view(&unsorted).unwrap();
+        assert!(matches!(
+            view.rolling(
+                "sum",
+                RollingWindow::period(ReadingField::Ts, TimeDelta::seconds(5)),
+                sum
+            ),
+            Err(ColbackError::Polars(_))
+        ));
+        assert!(matches!(
+            view.rolling("sum", RollingWindow::rows(0), sum),
+            Err(ColbackError::Polars(_))
+        ));
+    }
+
+    #[test]
+    fn test_to_owned() {
+        #[derive(ColbackView, ColbackOwned, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+            #[polars(null = "option")]
+            row_c: Option<String>,
+            #[polars(null = "default", default = 0.5)]
+            row_d: f64,
+        }
+
+        fn first_row(df: DataFrame) -> SomeStructOwned {
+            SomeStruct::view(&df).unwrap().get(0).unwrap().to_owned()
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2],
+            "row_b" => ["x", "y"],
+            "row_c" => [None, Some("z")],
+            "row_d" => [None, Some(1.0f64)],
+        ]
+        .unwrap();
+        let owned = first_row(df.clone());
+        assert_eq!(
+            owned,
+            SomeStructOwned {
+                row_a: 1,
+                row_b: "x".to_string(),
+                row_c: None,
+                row_d: 0.5,
+            }
+        );
+
+        let view = SomeStruct::view(&df).unwrap();
+        let row = view.get(1).unwrap();
+        let owned = std::thread::spawn({
+            let owned = SomeStruct::to_owned_row(&row);
+            move || owned
+        })
+        .join()
+        .unwrap();
+        assert_eq!(owned.row_b, "y");
+        assert_eq!(owned.row_c.as_deref(), Some("z"));
+        assert_eq!(owned.row_d, 1.0);
+    }
+
+    #[test]
+    fn test_to_vec() {
+        #[derive(ColbackView, ColbackOwned, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_a" => [Some(1u32), None, Some(3)],
+            "row_b" => ["x", "y", "z"],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        assert!(matches!(
+            view.to_vec(),
+            Err(ColbackError::InvalidNull { idx: 1, .. })
+        ));
+
+        let rows = view.collect_valid().unwrap();
+        let summary: Vec<(u32, &str)> = rows.iter().map(|r| (r.row_a, r.row_b.as_str())).collect();
+        assert_eq!(summary, vec![(1, "x"), (3, "z")]);
+
+        let valid = df.drop_nulls::<String>(None).unwrap();
+        let rows = SomeStruct::view(&valid).unwrap().to_vec().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].row_b, "z");
+    }
+
+    #[test]
+    fn test_into_dataframe() {
+        use chrono::{DateTime, NaiveDateTime};
+
+        #[derive(ColbackView, Debug, PartialEq)]
+        struct SomeStruct {
+            row_a: u16,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+            #[polars(time_unit = "ns")]
+            row_c: NaiveDateTime,
+        }
+
+        let ts = |secs: i64| DateTime::from_timestamp(secs, 0).unwrap().naive_utc();
+        let df = (0..3)
+            .map(|i| SomeStruct {
+                row_a: i,
+                row_b: (i > 0).then(|| i.to_string()),
+                row_c: ts(i.into()),
+            })
+            .into_dataframe()
+            .unwrap();
+        let expected = df! [
+            "row_a" => [0u16, 1, 2],
+            "row_b" => [None, Some("1"), Some("2")],
+            "row_c" => [ts(0), ts(1), ts(2)],
+        ]
+        .unwrap()
+        .lazy()
+        .with_column(col("row_c").cast(DataType::Datetime(TimeUnit::Nanoseconds, None)))
+        .collect()
+        .unwrap();
+        assert!(df.equals_missing(&expected));
+
+        let view = SomeStruct::view(&df).unwrap();
+        assert_eq!(view.get(2).unwrap().row_c, ts(2));
+        assert_eq!(
+            Vec::<SomeStruct>::new().into_dataframe().unwrap().height(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_colback_frame() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b")]
+            row_b: String,
+        }
+
+        let mut frame: ColbackFrame<SomeStruct> = (0..2)
+            .map(|i| SomeStruct {
+                row_a: i,
+                row_b: i.to_string(),
+            })
+            .collect();
+        assert_eq!(frame.len(), 2);
+        frame.extend(std::iter::once(SomeStruct {
+            row_a: 5,
+            row_b: "five".to_string(),
+        }));
+        frame.push(SomeStruct {
+            row_a: 6,
+            row_b: "six".to_string(),
+        });
+        assert_eq!(frame.len(), 4);
+
+        let df = frame.finish().unwrap();
+        let expected = df! [
+            "row_a" => [0u32, 1, 5, 6],
+            "b" => ["0", "1", "five", "six"],
+        ]
+        .unwrap();
+        assert!(df.equals(&expected));
+
+        let empty = ColbackFrame::<SomeStruct>::with_capacity(8);
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.finish().unwrap().get_column_names(),
+            vec!["row_a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        use chrono::{DateTime, NaiveDateTime};
+
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+            #[polars(null = "default", default = false)]
+            row_c: bool,
+            #[polars(time_unit = "us")]
+            row_d: NaiveDateTime,
+        }
+
+        let ts = |secs: i64| DateTime::from_timestamp(secs, 0).unwrap().naive_utc();
+        let mut builder = SomeStruct::builder(3);
+        builder
+            .push(&SomeStructOwned {
+                row_a: 1,
+                row_b: Some("x".to_string()),
+                row_c: true,
+                row_d: ts(1),
+            })
+            .unwrap();
+        builder.push_fields(2, None, false, ts(2)).unwrap();
+        let df = builder.finish().unwrap();
+
+        let expected = df! [
+            "row_a" => [1u8, 2],
+            "row_b" => [Some("x"), None],
+            "row_c" => [true, false],
+            "row_d" => [ts(1), ts(2)],
+        ]
+        .unwrap()
+        .lazy()
+        .with_column(col("row_d").cast(DataType::Datetime(TimeUnit::Microseconds, None)))
+        .collect()
+        .unwrap();
+        assert!(df.equals_missing(&expected));
+
+        let mut builder = SomeStructBuilder::with_capacity(1);
+        builder
+            .push_fields(3, Some("y".to_string()), true, ts(3))
+            .unwrap();
+        builder.append_nulls(1);
+        let df = builder.finish().unwrap();
+        assert_eq!(df.height(), 2);
+        assert_eq!(df.column("row_b").unwrap().null_count(), 1);
+        let view = SomeStruct::view(&df).unwrap();
+        assert!(matches!(
+            view.get(1),
+            Err(ColbackError::InvalidNull { idx: 1, .. })
+        ));
+
+        // Datetimes that overflow their time unit error instead of being appended as nulls, and
+        // leave the builder unchanged
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, PartialEq)]
+        struct NanoStruct {
+            row_a: u8,
+            #[polars(time_unit = "ns")]
+            row_b: NaiveDateTime,
+        }
+
+        let mut builder = NanoStruct::builder(2);
+        builder.push_fields(1, ts(1)).unwrap();
+        assert!(matches!(
+            builder.push_fields(2, NaiveDateTime::MAX),
+            Err(ColbackError::ValueOutOfRange { idx: 1, ref col, .. }) if col == "row_b"
+        ));
+        let df = builder.finish().unwrap();
+        assert_eq!(df.height(), 1);
+        assert_eq!(df.column("row_a").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_append_rows() {
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        let mut df = df! [
+            "row_b" => ["x"],
+            "row_a" => [1u32],
+            "row_c" => [1.0f64],
+        ]
+        .unwrap();
+        SomeStruct::append_row(
+            &mut df,
+            &SomeStructOwned {
+                row_a: 2,
+                row_b: "y".to_string(),
+            },
+        )
+        .unwrap();
+        let rows = [3, 4].map(|row_a| SomeStructOwned {
+            row_a,
+            row_b: row_a.to_string(),
+        });
+        SomeStruct::append_rows(&mut df, &rows).unwrap();
+
+        let expected = df! [
+            "row_b" => ["x", "y", "3", "4"],
+            "row_a" => [1u32, 2, 3, 4],
+            "row_c" => [Some(1.0f64), None, None, None],
+        ]
+        .unwrap();
+        assert!(df.equals_missing(&expected));
+
+        let mut bad = df! [
+            "row_a" => [1i64],
+            "row_b" => ["x"],
+        ]
+        .unwrap();
+        assert!(matches!(
+            SomeStruct::append_rows(&mut bad, &rows),
+            Err(ColbackError::WrongDtype { .. })
+        ));
+        assert_eq!(bad.height(), 1);
+    }
+
+    #[test]
+    fn test_view_mut() {
+        use chrono::{DateTime, NaiveDateTime};
+
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+            #[polars(null = "option")]
+            row_c: Option<NaiveDateTime>,
+            row_d: bool,
+        }
+
+        let ts = |secs: i64| DateTime::from_timestamp(secs, 0).unwrap().naive_utc();
+        let df = df! [
+            "row_a" => [1u32, 2, 3],
+            "b" => [Some("x"), None, Some("z")],
+            "row_c" => [Some(ts(1)), None, Some(ts(3))],
+            "row_d" => [true, false, true],
+        ]
+        .unwrap();
+
+        let mut view = SomeStruct::view_mut(&df).unwrap();
+        view.set_row_a(1, 20).unwrap();
+        view.set_row_b(0, None).unwrap();
+        view.set_row_b(1, Some("y".to_string())).unwrap();
+        view.set_row_c(1, Some(ts(2))).unwrap();
+        assert!(matches!(
+            view.set_row_a(3, 0),
+            Err(ColbackError::IndexOutOfBounds { idx: 3, len: 3 })
+        ));
+        assert_eq!(view.view().get(1).unwrap().row_a, 2);
+
+        let updated = view.commit().unwrap();
+        let expected = df! [
+            "row_a" => [1u32, 20, 3],
+            "b" => [None, Some("y"), Some("z")],
+            "row_c" => [Some(ts(1)), Some(ts(2)), Some(ts(3))],
+            "row_d" => [true, false, true],
+        ]
+        .unwrap();
+        assert!(updated.equals_missing(&expected));
+        assert_eq!(df.column("row_a").unwrap().u32().unwrap().get(1), Some(2));
+    }
+
+    #[test]
+    fn test_upsert() {
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, Eq, PartialEq)]
+        struct SomeStruct {
+            id: u32,
+            region: String,
+            qty: i64,
+        }
+
+        let df = df! [
+            "id" => [1u32, 2, 1],
+            "region" => ["a", "a", "b"],
+            "qty" => [10i64, 20, 30],
+            "note" => ["x", "y", "z"],
+        ]
+        .unwrap();
+        let row = |id, region: &str, qty| SomeStructOwned {
+            id,
+            region: region.to_string(),
+            qty,
+        };
+        let rows = [
+            row(3, "a", 1),
+            row(1, "b", 2),
+            row(1, "b", 3),
+            row(4, "c", 4),
+        ];
+
+        let by = [SomeStructField::Id, SomeStructField::Region];
+        let upserted = SomeStruct::upsert(&df, &rows, &by).unwrap();
+        let expected = df! [
+            "id" => [1u32, 2, 1, 3, 4],
+            "region" => ["a", "a", "b", "a", "c"],
+            "qty" => [10i64, 20, 3, 1, 4],
+            "note" => [Some("x"), Some("y"), Some("z"), None, None],
+        ]
+        .unwrap();
+        assert!(upserted.equals_missing(&expected));
+
+        let unchanged = SomeStruct::upsert(&df, &[], &by).unwrap();
+        assert!(unchanged.equals(&df));
+    }
+
+    #[test]
+    fn test_df_of() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+        }
+
+        let df = colback::df_of![
+            SomeStruct {
+                row_a: 1,
+                row_b: None,
+            },
+            SomeStruct {
+                row_a: 2,
+                row_b: Some("x".to_string()),
+            },
+        ]
+        .unwrap();
+        let expected = df! [
+            "row_a" => [1u8, 2],
+            "b" => [None, Some("x")],
+        ]
+        .unwrap();
+        assert!(df.equals_missing(&expected));
+    }
+
+    #[test]
+    fn test_replace_column() {
+        use chrono::{NaiveDateTime, TimeDelta};
+
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+            #[polars(time_unit = "us")]
+            row_c: NaiveDateTime,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2],
+            "b" => [Some("x"), None],
+            "row_c" => [1_000_000i64, 2_000_000],
+            "row_d" => [true, false],
+        ]
+        .unwrap()
+        .lazy()
+        .with_column(col("row_c").cast(DataType::Datetime(TimeUnit::Microseconds, None)))
+        .collect()
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let replaced = view
+            .replace_column(SomeStructField::RowB, |r| {
+                r.row_b
+                    .map_or_else(|| r.row_a.to_string(), str::to_uppercase)
+            })
+            .unwrap();
+        assert_eq!(
+            replaced.get_column_names(),
+            vec!["row_a", "b", "row_c", "row_d"]
+        );
+        let values: Vec<_> = replaced
+            .column("b")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(values, vec!["X", "2"]);
+
+        let shifted = view
+            .replace_column(SomeStructField::RowC, |r| r.row_c + TimeDelta::seconds(1))
+            .unwrap();
+        let view = SomeStruct::view(&shifted).unwrap();
+        assert_eq!(view.get(0).unwrap().row_c.and_utc().timestamp(), 2);
+
+        assert!(matches!(
+            SomeStruct::view(&df)
+                .unwrap()
+                .replace_column(SomeStructField::RowA, |r| r.row_a as i64),
+            Err(ColbackError::WrongDtype { .. })
+        ));
+
+        // Skipped rows keep their values
+        #[derive(ColbackView)]
+        struct SkipStruct {
+            row_a: u32,
+            #[polars(null = "skip")]
+            row_b: u32,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 3],
+            "row_b" => [Some(1u32), None, Some(3)],
+        ]
+        .unwrap();
+        let view = SkipStruct::view(&df).unwrap();
+        let replaced = view
+            .replace_column(SkipStructField::RowA, |r| r.row_a * 10)
+            .unwrap();
+        let expected = df! [
+            "row_a" => [10u32, 2, 30],
+            "row_b" => [Some(1u32), None, Some(3)],
+        ]
+        .unwrap();
+        assert!(replaced.equals_missing(&expected));
+        let replaced = view
+            .replace_column(SkipStructField::RowB, |r| r.row_b + 1)
+            .unwrap();
+        assert_eq!(
+            replaced
+                .column("row_b")
+                .unwrap()
+                .u32()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(2), None, Some(4)]
+        );
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn test_write_parquet() {
+        use polars::prelude::{ParquetReader, ParquetWriteOptions, SerReader};
+
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b")]
+            row_b: String,
+        }
+
+        let read = |path: &std::path::Path| {
+            ParquetReader::new(std::fs::File::open(path).unwrap())
+                .finish()
+                .unwrap()
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let options = ParquetWriteOptions::default();
+
+        let df = df! [
+            "extra" => [true, false],
+            "b" => ["x", "y"],
+            "row_a" => [1u32, 2],
+        ]
+        .unwrap();
+        let path = dir.path().join("view.parquet");
+        SomeStruct::view(&df)
+            .unwrap()
+            .write_parquet(&path, &options)
+            .unwrap();
+        assert!(read(&path).equals(&SomeStruct::select(&df).unwrap()));
+
+        let rows = [1, 2].map(|row_a| SomeStructOwned {
+            row_a,
+            row_b: row_a.to_string(),
+        });
+        let path = dir.path().join("rows.parquet");
+        SomeStruct::write_parquet(rows, &path, &options).unwrap();
+        let expected = df! [
+            "row_a" => [1u32, 2],
+            "b" => ["1", "2"],
+        ]
+        .unwrap();
+        assert!(read(&path).equals(&expected));
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn test_write_csv_ndjson() {
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let df = df! [
+            "b" => [Some("x"), None],
+            "extra" => [true, false],
+            "row_a" => [1u32, 2],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let path = dir.path().join("view.csv");
+        view.write_csv(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "row_a,b\n1,x\n2,\n"
+        );
+
+        let path = dir.path().join("view.ndjson");
+        view.write_ndjson(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"row_a\":1,\"b\":\"x\"}\n{\"row_a\":2,\"b\":null}\n"
+        );
+
+        let rows = [SomeStructOwned {
+            row_a: 3,
+            row_b: Some("z".to_string()),
+        }];
+        let path = dir.path().join("rows.csv");
+        SomeStruct::write_csv(rows.clone(), &path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "row_a,b\n3,z\n");
+
+        let path = dir.path().join("rows.ndjson");
+        SomeStruct::write_ndjson(rows, &path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"row_a\":3,\"b\":\"z\"}\n"
+        );
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn test_read_csv() {
+        use polars::prelude::CsvReadOptions;
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+            #[polars(name = "zip")]
+            row_b: String,
+        }
+
+        assert_eq!(
+            SomeStruct::schema(),
+            Schema::from_iter([
+                ("row_a".into(), DataType::UInt8),
+                ("zip".into(), DataType::String),
+            ])
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        std::fs::write(&path, "extra,zip,row_a\ntrue,01234,1\nfalse,98765,2\n").unwrap();
+        let df = SomeStruct::read_csv(&path, CsvReadOptions::default()).unwrap();
+        let expected = df! [
+            "row_a" => [1u8, 2],
+            "zip" => ["01234", "98765"],
+        ]
+        .unwrap();
+        assert!(df.equals(&expected));
+
+        std::fs::write(&path, "row_a,zip\nx,1\n").unwrap();
+        assert!(SomeStruct::read_csv(&path, CsvReadOptions::default()).is_err());
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn test_scan_parquet_ipc() {
+        use polars::prelude::{IpcWriter, ParquetWriter, SerWriter};
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+            row_b: String,
+        }
+
+        let mut df = df! [
+            "extra" => [true, false],
+            "row_b" => ["a", "b"],
+            "row_a" => [1u8, 2],
+        ]
+        .unwrap();
+        let expected = df! [
+            "row_a" => [1u8, 2],
+            "row_b" => ["a", "b"],
+        ]
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let parquet = dir.path().join("rows.parquet");
+        let file = std::fs::File::create(&parquet).unwrap();
+        ParquetWriter::new(file).finish(&mut df).unwrap();
+        let scanned = SomeStruct::scan_parquet(&parquet)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert!(scanned.equals(&expected));
+
+        let ipc = dir.path().join("rows.arrow");
+        let file = std::fs::File::create(&ipc).unwrap();
+        IpcWriter::new(file).finish(&mut df).unwrap();
+        let scanned = SomeStruct::scan_ipc(&ipc).unwrap().collect().unwrap();
+        assert!(scanned.equals(&expected));
+
+        #[derive(ColbackView)]
+        struct WrongStruct {
+            row_a: u16,
+        }
+        assert!(matches!(
+            WrongStruct::scan_parquet(&parquet),
+            Err(ColbackError::WrongDtype { .. })
+        ));
+    }
+
+    #[test]
+    fn test_collect_view() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_b: String,
+            row_a: u32,
+        }
+
+        let df = df! [
+            "row_a" => [2u32, 0u32, 5u32],
+            "row_b" => ["b", "c", "d"],
+            "row_c" => [1.0f64, 2.0, 3.0],
+        ]
+        .unwrap();
+
+        let lf = df.clone().lazy().filter(col("row_c").gt(lit(1.5)));
+        let collected = SomeStruct::collect_view(lf).unwrap();
+        let expected = df! [
+            "row_b" => ["c", "d"],
+            "row_a" => [0u32, 5u32],
+        ]
+        .unwrap();
+        assert!(collected.equals(&expected));
+        assert_eq!(SomeStruct::view(&collected).unwrap().len(), 2);
+
+        let lf = df.lazy().with_column(col("row_a").cast(DataType::Int64));
+        assert!(matches!(
+            SomeStruct::collect_view(lf),
+            Err(ColbackError::WrongDtype { .. })
+        ));
+        let lf = df! ["row_b" => ["b"]].unwrap().lazy();
+        assert!(SomeStruct::collect_view(lf).is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_stream_async() {
+        use futures::StreamExt;
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 3, 4, 5],
+            "row_b" => ["a", "b", "c", "d", "e"],
+        ]
+        .unwrap();
+
+        let batches = SomeStruct::stream_async(df.clone().lazy(), 2)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let heights: Vec<_> = batches.iter().map(DataFrame::height).collect();
+        assert_eq!(heights, vec![2, 2, 1]);
+        assert_eq!(batches[2].width(), 1);
+        assert_eq!(
+            SomeStruct::view(&batches[1]).unwrap().get(0).unwrap().row_a,
+            3
+        );
+
+        let errors = SomeStruct::stream_async(df.clone().lazy(), 0)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].is_err());
+
+        // Batches are produced while the query runs, and dropping the stream stops it
+        let mut batches = Box::pin(SomeStruct::stream_async(df.clone().lazy(), 1));
+        assert_eq!(batches.next().await.unwrap().unwrap().height(), 1);
+        drop(batches);
+
+        let lf = df! ["row_b" => ["a", "b"]].unwrap().lazy();
+        let errors = SomeStruct::stream_async(lf, 2).collect::<Vec<_>>().await;
+        assert!(matches!(errors[..], [Err(_)]));
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn test_view_ipc_mmap() {
+        use polars::prelude::{IpcWriter, SerWriter};
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+            row_b: String,
+        }
+
+        let mut df = df! [
+            "row_b" => ["a", "b"],
+            "extra" => [true, false],
+            "row_a" => [1u8, 2],
+        ]
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.arrow");
+        let file = std::fs::File::create(&path).unwrap();
+        IpcWriter::new(file).finish(&mut df).unwrap();
+
+        let mapped = SomeStruct::view_ipc_mmap(&path).unwrap();
+        assert_eq!(SomeStruct::column_names(), mapped.get_column_names_str());
+        let view = SomeStruct::view(&mapped).unwrap();
+        assert_eq!(view.get(1).unwrap().row_b, "b");
+
+        #[derive(ColbackView)]
+        struct WrongStruct {
+            row_a: u16,
+        }
+        assert!(matches!(
+            WrongStruct::view_ipc_mmap(&path),
+            Err(ColbackError::WrongDtype { .. })
+        ));
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn test_view_record_batch() {
+        use arrow::{
+            array::{ArrayRef, BooleanArray, StringArray, UInt8Array},
+            record_batch::RecordBatch,
+        };
+        use std::sync::Arc;
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+        }
+
+        let batch = RecordBatch::try_from_iter([
+            (
+                "row_b",
+                Arc::new(StringArray::from(vec![Some("a"), None])) as ArrayRef,
+            ),
+            (
+                "extra",
+                Arc::new(BooleanArray::from(vec![true, false])) as ArrayRef,
+            ),
+            (
+                "row_a",
+                Arc::new(UInt8Array::from(vec![1u8, 2])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let df = SomeStruct::view_record_batch(&batch).unwrap();
+        let expected = df! [
+            "row_a" => [1u8, 2],
+            "row_b" => [Some("a"), None],
+        ]
+        .unwrap();
+        assert!(df.equals_missing(&expected));
+
+        #[derive(ColbackView)]
+        struct WrongStruct {
+            row_a: u16,
+        }
+        assert!(matches!(
+            WrongStruct::view_record_batch(&batch),
+            Err(ColbackError::WrongDtype { .. })
+        ));
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn test_view_record_batch_stream() {
+        use arrow::{
+            array::{ArrayRef, StringArray, UInt8Array},
+            record_batch::RecordBatch,
+        };
+        use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+        }
+
+        let batch = |a: Vec<u8>| {
+            let b = a.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+            RecordBatch::try_from_iter([
+                ("row_b", Arc::new(StringArray::from(b)) as ArrayRef),
+                ("row_a", Arc::new(UInt8Array::from(a)) as ArrayRef),
+            ])
+            .unwrap()
+        };
+        let batches = vec![batch(vec![1, 2]), batch(vec![3])];
+        let schema = batches[0].schema();
+        let stream = || {
+            Box::pin(RecordBatchStreamAdapter::new(
+                schema.clone(),
+                futures::stream::iter(batches.clone().into_iter().map(Ok)),
+            )) as datafusion::execution::SendableRecordBatchStream
+        };
+
+        let dfs = SomeStruct::view_record_batch_stream(stream())
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(dfs.len(), 2);
+        assert!(dfs[1].equals(&df! ["row_a" => [3u8]].unwrap()));
+
+        #[derive(ColbackView)]
+        struct WrongStruct {
+            row_c: u8,
+        }
+        assert!(matches!(
+            WrongStruct::view_record_batch_stream(stream()),
+            Err(ColbackError::MissingColumn(_))
+        ));
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_view_ffi() {
+        use polars::prelude::{CompatLevel, IntoSeries};
+        use polars_arrow::ffi::export_iterator;
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_b" => ["a", "b"],
+            "extra" => [true, false],
+            "row_a" => [1u8, 2],
+        ]
+        .unwrap();
+        let series = df.into_struct("rows".into()).into_series();
+        let field = series
+            .dtype()
+            .to_arrow_field("rows".into(), CompatLevel::newest());
+        let array = series.to_arrow(0, CompatLevel::newest());
+        let mut stream = export_iterator(Box::new(std::iter::once(Ok(array))), field);
+
+        let imported = unsafe { SomeStruct::view_ffi(&mut stream) }.unwrap();
+        let expected = df! [
+            "row_a" => [1u8, 2],
+            "row_b" => ["a", "b"],
+        ]
+        .unwrap();
+        assert!(imported.equals(&expected));
+        assert!(unsafe { SomeStruct::view_ffi(&mut stream) }.is_err());
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_export_ffi() {
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_b" => ["a", "b"],
+            "extra" => [true, false],
+            "row_a" => [1u8, 2],
+        ]
+        .unwrap();
+        let expected = df! [
+            "row_a" => [1u8, 2],
+            "row_b" => ["a", "b"],
+        ]
+        .unwrap();
+
+        let mut stream = SomeStruct::view(&df).unwrap().export_ffi().unwrap();
+        let imported = unsafe { SomeStruct::view_ffi(&mut stream) }.unwrap();
+        assert!(imported.equals(&expected));
+
+        let rows = SomeStruct::view(&df).unwrap().to_vec().unwrap();
+        let mut stream = SomeStruct::export_ffi(rows).unwrap();
+        let imported = unsafe { SomeStruct::view_ffi(&mut stream) }.unwrap();
+        assert!(imported.equals(&expected));
+    }
+
+    #[cfg(feature = "pyo3")]
+    #[test]
+    fn test_view_py() {
+        use pyo3::{PyErr, PyResult};
+        use pyo3_polars::PyDataFrame;
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+        }
+
+        fn first(df: &PyDataFrame) -> PyResult<u8> {
+            Ok(SomeStruct::view_py(df)?.get(0)?.row_a)
+        }
+
+        let df = PyDataFrame(df! ["row_a" => [3u8, 4]].unwrap());
+        assert_eq!(first(&df).unwrap(), 3);
+        assert_eq!(SomeStruct::view_py(&df).unwrap().len(), 2);
+
+        let missing = PyDataFrame(df! ["row_b" => [3u8]].unwrap());
+        assert!(first(&missing).is_err());
+        let _: PyErr = ColbackError::MissingColumn("row_a".to_string()).into();
+    }
+
+    #[cfg(feature = "plugin")]
+    #[test]
+    fn test_map_struct() {
+        use polars::prelude::{IntoSeries, PolarsResult, Series};
+
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+        }
+
+        // Shaped like the function behind a `#[polars_expr]` plugin
+        fn kernel(inputs: &[Series]) -> PolarsResult<Series> {
+            Ok(SomeStruct::map_struct(&inputs[0], |row| {
+                row.row_a + row.row_b.map_or(0, |b| b.len() as u32)
+            })?)
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 3],
+            "row_b" => [Some("ab"), None, Some("c")],
+        ]
+        .unwrap();
+        let series = df.into_struct("rows".into()).into_series();
+        let out = kernel(&[series]).unwrap();
+        assert_eq!(out.name(), "rows");
+        assert!(out.equals(&Series::new("rows".into(), [3u32, 2, 4])));
+
+        // Skipped rows are null in the output
+        #[derive(ColbackView)]
+        struct SkipStruct {
+            #[polars(null = "skip")]
+            row_b: String,
+        }
+
+        let df = df!("row_b" => [Some("ab"), None]).unwrap();
+        let series = df.into_struct("rows".into()).into_series();
+        let out = SkipStruct::map_struct(&series, |row| row.row_b.len() as u32).unwrap();
+        assert!(out.equals_missing(&Series::new("rows".into(), [Some(2u32), None])));
+
+        let wrong = df! ["row_a" => [1i64], "row_b" => ["x"]].unwrap();
+        let wrong = wrong.into_struct("rows".into()).into_series();
+        assert!(matches!(
+            kernel(&[wrong]),
+            Err(PolarsError::SchemaMismatch(_))
+        ));
+        assert!(kernel(&[Series::new("rows".into(), [1u32])]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        use chrono::NaiveDateTime;
+        use serde_json::json;
+
+        #[derive(ColbackView, ColbackOwned, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+            #[polars(time_unit = "us")]
+            row_c: NaiveDateTime,
+        }
+
+        let dt = NaiveDateTime::parse_from_str("2024-01-02 03:04:05", "%Y-%m-%d %H:%M:%S").unwrap();
+        let df = df! [
+            "row_a" => [1u32, 2],
+            "b" => [Some("x"), None],
+            "row_c" => [dt, dt],
+        ]
+        .unwrap()
+        .lazy()
+        .with_column(col("row_c").cast(DataType::Datetime(TimeUnit::Microseconds, None)))
+        .collect()
+        .unwrap();
+
+        let view = SomeStruct::view(&df).unwrap();
+        let expected = json!({"row_a": 1, "b": "x", "row_c": "2024-01-02T03:04:05"});
+        assert_eq!(
+            serde_json::to_value(view.get(0).unwrap()).unwrap(),
+            expected
+        );
+        let expected = json!({"row_a": 2, "b": null, "row_c": "2024-01-02T03:04:05"});
+        assert_eq!(
+            serde_json::to_value(view.get(1).unwrap().to_owned()).unwrap(),
+            expected
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_from_json_lines() {
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+        }
+
+        let json = "{\"row_a\": 1, \"b\": \"x\"}\n{\"row_a\": 2}\n";
+        let df = from_json_lines::<SomeStruct>(json.as_bytes()).unwrap();
+        let expected = df! [
+            "row_a" => [1u32, 2],
+            "b" => [Some("x"), None],
+        ]
+        .unwrap();
+        assert!(df.equals_missing(&expected));
+
+        let rows = [
+            Ok(SomeStructOwned {
+                row_a: 3,
+                row_b: None,
+            }),
+            Err("bad row"),
+        ];
+        assert!(matches!(
+            from_serde_iter::<SomeStruct, _, _>(rows),
+            Err(ColbackError::Deserialize { idx: 1, .. })
+        ));
+
+        let json = "{\"row_a\": -1}\n";
+        assert!(matches!(
+            from_json_lines::<SomeStruct>(json.as_bytes()),
+            Err(ColbackError::Deserialize { idx: 0, .. })
+        ));
+    }
+
+    #[cfg(feature = "serde_arrow")]
+    #[test]
+    fn test_serde_arrow() {
+        #[derive(ColbackView, ColbackOwned, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+        }
+
+        let fields = SomeStruct::serde_arrow_fields().unwrap();
+        let names: Vec<_> = fields.iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, vec!["row_a", "b"]);
+
+        let rows = [
+            SomeStructOwned {
+                row_a: 1,
+                row_b: Some("x".to_string()),
+            },
+            SomeStructOwned {
+                row_a: 2,
+                row_b: None,
+            },
+        ];
+        let batch = SomeStruct::to_record_batch(&rows).unwrap();
+        let df = SomeStruct::view_record_batch(&batch).unwrap();
+        let expected = df! [
+            "row_a" => [1u32, 2],
+            "b" => [Some("x"), None],
+        ]
+        .unwrap();
+        assert!(df.equals_missing(&expected));
+    }
+
+    #[cfg(feature = "duckdb")]
+    #[test]
+    fn test_query_duckdb() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+        }
+
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE t (row_a UINTEGER, row_b VARCHAR, extra DOUBLE);
+             INSERT INTO t VALUES (1, 'x', 0.5), (2, NULL, 1.5), (3, 'z', 2.5);",
+        )
+        .unwrap();
+
+        let df = SomeStruct::query_duckdb(&conn, "SELECT * FROM t WHERE extra > ?", [1.0]).unwrap();
+        let expected = df! [
+            "row_a" => [2u32, 3],
+            "row_b" => [None, Some("z")],
+        ]
+        .unwrap();
+        assert!(df.equals_missing(&expected));
+
+        let empty =
+            SomeStruct::query_duckdb(&conn, "SELECT * FROM t WHERE extra > 10", []).unwrap();
+        assert_eq!(empty.height(), 0);
+
+        assert!(matches!(
+            SomeStruct::query_duckdb(&conn, "SELECT row_b FROM t", []),
+            Err(ColbackError::MissingColumn(_))
+        ));
+    }
+
+    #[cfg(feature = "sql")]
+    #[test]
+    fn test_sql() {
+        use polars::sql::SQLContext;
+
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            total: f64,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 1, 2],
+            "row_b" => [0.5f64, 1.0, 2.0],
+        ]
+        .unwrap();
+        let mut ctx = SQLContext::new();
+        ctx.register("t", df.lazy());
+
+        let totals = SomeStruct::sql(
+            "SELECT row_a, SUM(row_b) AS total, COUNT(*) AS n FROM t GROUP BY row_a ORDER BY row_a",
+            &mut ctx,
+        )
+        .unwrap();
+        let expected = df! [
+            "row_a" => [1u32, 2],
+            "total" => [1.5f64, 2.0],
+        ]
+        .unwrap();
+        assert!(totals.equals(&expected));
+
+        assert!(SomeStruct::sql("SELECT row_a FROM t", &mut ctx).is_err());
+        assert!(SomeStruct::sql("SELECT nonsense FROM", &mut ctx).is_err());
+    }
+
+    #[test]
+    fn test_scan_batches() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        let mut batches = vec![
+            df! ["row_b" => ["c"], "row_a" => [3u32], "extra" => [true]].unwrap(),
+            df! ["row_a" => [1u32, 2], "row_b" => ["a", "b"]].unwrap(),
+        ];
+        let lf = SomeStruct::scan_batches(move || Ok(batches.pop())).unwrap();
+        assert_eq!(
+            lf.clone().collect_schema().unwrap().as_ref(),
+            &SomeStruct::schema()
+        );
+
+        let df = lf.filter(col("row_a").gt(lit(1u32))).collect().unwrap();
+        let expected = df! [
+            "row_a" => [2u32, 3],
+            "row_b" => ["b", "c"],
+        ]
+        .unwrap();
+        assert!(df.equals(&expected));
+
+        let mut batches = vec![df! ["row_a" => [1i64], "row_b" => ["a"]].unwrap()];
+        let lf = SomeStruct::scan_batches(move || Ok(batches.pop())).unwrap();
+        assert!(lf.collect().is_err());
+    }
+
+    #[cfg(feature = "gen")]
+    #[test]
+    fn test_struct_definition() {
+        let datetime = DataType::Datetime(TimeUnit::Microseconds, None);
+        let scores = DataType::List(Box::new(DataType::Float32));
+        let columns = [
+            ("id", &DataType::UInt32, false),
+            ("Order Total", &DataType::Float64, true),
+            ("type", &DataType::String, false),
+            ("2nd", &DataType::Boolean, false),
+            ("orderID", &DataType::Int64, false),
+            ("created_at", &datetime, false),
+            ("scores", &scores, false),
+            ("tags", &DataType::Null, true),
+        ];
+        let expected = "\
+use chrono::NaiveDateTime;
+use colback::ColbackView;
+
+#[derive(ColbackView)]
+pub struct Orders {
+    pub id: u32,
+    #[polars(name = \"Order Total\", null = \"option\")]
+    pub order_total: Option<f64>,
+    #[polars(name = \"type\")]
+    pub type_: String,
+    #[polars(name = \"2nd\")]
+    pub col_2nd: bool,
+    #[polars(name = \"orderID\")]
+    pub order_id: i64,
+    #[polars(time_unit = \"us\")]
+    pub created_at: NaiveDateTime,
+    pub scores: Vec<f32>,
+    // unsupported column \"tags\" with dtype null
+}
+";
+        assert_eq!(struct_definition("Orders", columns), expected);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_struct_from_schema() {
+        colback::struct_from_schema!("tests/data/orders.csv" as Orders);
+
+        let df = df! [
+            "order id" => [1i64, 2],
+            "customerName" => ["a", "b"],
+            "total" => [Some(1.5f64), None],
+        ]
+        .unwrap();
+        assert_eq!(
+            Orders::column_names(),
+            &["order id", "customerName", "total"]
+        );
+        let view = Orders::view(&df).unwrap();
+        let row = view.get(1).unwrap();
+        assert_eq!(row.order_id, 2);
+        assert_eq!(row.customer_name, "b");
+        assert_eq!(row.total, None);
+    }
+
+    #[test]
+    fn test_define_row() {
+        colback::define_row!(Orders {
+            "order_id" => UInt64,
+            "ts" => Datetime(us, None),
+            "Customer Name" => Option<String>,
+        });
+
+        assert_eq!(
+            Orders::schema(),
+            Schema::from_iter([
+                ("order_id".into(), DataType::UInt64),
+                (
+                    "ts".into(),
+                    DataType::Datetime(TimeUnit::Microseconds, None)
+                ),
+                ("Customer Name".into(), DataType::String),
+            ])
+        );
+
+        let ts = chrono::DateTime::from_timestamp(1_700_000_000, 0)
+            .unwrap()
+            .naive_utc();
+        let df = df! [
+            "order_id" => [7u64],
+            "ts" => [ts],
+            "Customer Name" => [None::<&str>],
+        ]
+        .unwrap()
+        .lazy()
+        .with_column(col("ts").cast(DataType::Datetime(TimeUnit::Microseconds, None)))
+        .collect()
+        .unwrap();
+        let view = Orders::view(&df).unwrap();
+        let row = view.get(0).unwrap();
+        assert_eq!(row.order_id, 7);
+        assert_eq!(row.ts, ts);
+        assert_eq!(row.customer_name, None);
+    }
+
+    #[test]
+    fn test_schema_export() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b \"quoted\"", null = "option")]
+            row_b: Option<String>,
+            #[polars(null = "default", default = 0.0)]
+            row_c: f64,
+        }
+
+        assert_eq!(SomeStruct::nullable_columns(), &["b \"quoted\"", "row_c"]);
+        assert_eq!(
+            SomeStruct::schema_json(),
+            "{\"columns\":[\
+             {\"name\":\"row_a\",\"dtype\":\"u32\",\"nullable\":false},\
+             {\"name\":\"b \\\"quoted\\\"\",\"dtype\":\"str\",\"nullable\":true},\
+             {\"name\":\"row_c\",\"dtype\":\"f64\",\"nullable\":true}]}"
+        );
+
+        let arrow = SomeStruct::arrow_schema();
+        let nullable: Vec<_> = arrow.iter_values().map(|f| f.is_nullable).collect();
+        assert_eq!(nullable, vec![false, true, true]);
+        assert_eq!(
+            arrow.get("row_a").unwrap().dtype(),
+            &polars::prelude::ArrowDataType::UInt32
+        );
+    }
+
+    #[cfg(feature = "proptest")]
+    #[test]
+    fn test_arb_df() {
+        use chrono::NaiveDateTime;
+        use proptest::{arbitrary::any, collection::vec, test_runner::TestRunner};
+
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, PartialEq)]
+        struct SomeStruct {
+            row_a: u8,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+            #[polars(null = "default", default = false)]
+            row_c: bool,
+            #[polars(time_unit = "us")]
+            row_d: NaiveDateTime,
+            #[polars(null = "option", time_unit = "ns")]
+            row_e: Option<NaiveDateTime>,
+        }
+
+        let mut runner = TestRunner::deterministic();
+        runner
+            .run(&SomeStruct::arb_df(2..10), |df| {
+                let view = SomeStruct::view(&df).unwrap();
+                assert!((2..10).contains(&view.len()));
+                Ok(())
+            })
+            .unwrap();
+
+        // Dataframes satisfy the bounds of their fields
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, PartialEq)]
+        struct BoundedStruct {
+            #[polars(min = -1, max = 10)]
+            row_a: i64,
+            #[polars(max = 10.0)]
+            row_b: f64,
+            #[polars(null = "option", min = 5)]
+            row_c: Option<u32>,
+        }
+
+        runner
+            .run(&BoundedStruct::arb_df(0..10), |df| {
+                BoundedStruct::view(&df).unwrap();
+                Ok(())
+            })
+            .unwrap();
+
+        // Float fields that error on NaNs never hold them
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, PartialEq)]
+        struct FloatStruct {
+            #[polars(nan = "error")]
+            row_a: f64,
+            #[polars(nan = "null", null = "option")]
+            row_b: Option<f32>,
+        }
+
+        runner
+            .run(&FloatStruct::arb_df(0..10), |df| {
+                let rows = FloatStruct::view(&df).unwrap().to_vec().unwrap();
+                assert!(rows.iter().all(|row| !row.row_a.is_nan()));
+                Ok(())
+            })
+            .unwrap();
+
+        // Owned rows survive a round trip through a dataframe unchanged
+        runner
+            .run(&vec(any::<SomeStructOwned>(), 0..10), |rows| {
+                let mut builder = SomeStruct::builder(rows.len());
+                for row in &rows {
+                    builder.push(row).unwrap();
+                }
+                let df = builder.finish().unwrap();
+                assert_eq!(SomeStruct::view(&df).unwrap().to_vec().unwrap(), rows);
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sample_df() {
+        use chrono::NaiveDateTime;
+
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, PartialEq)]
+        struct SomeStruct {
+            id: u32,
+            city: String,
+            #[polars(null = "option")]
+            score: Option<f64>,
+            delta: i64,
+            #[polars(time_unit = "us")]
+            ts: NaiveDateTime,
+        }
+
+        let df = SomeStruct::sample_df(8, 7);
+        assert!(df.equals_missing(&SomeStruct::sample_df(8, 7)));
+        assert!(!df.equals_missing(&SomeStruct::sample_df(8, 8)));
+
+        let rows = SomeStruct::view(&df).unwrap().to_vec().unwrap();
+        assert_eq!(rows.len(), 8);
+        for (idx, row) in rows.iter().enumerate() {
+            assert_eq!(row.id, idx as u32);
+            assert_eq!(row.city, format!("city_{}", idx % 4));
+            assert!((-1000..1000).contains(&row.delta));
+            assert!(row.score.is_none_or(|score| (0.0..1000.0).contains(&score)));
+        }
+        assert_eq!(rows[1].ts - rows[0].ts, chrono::TimeDelta::hours(1));
+        assert_eq!(SomeStruct::sample_df(0, 7).height(), 0);
+
+        // Values are clamped into the bounds of their fields
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, PartialEq)]
+        struct BoundedStruct {
+            #[polars(min = 0, max = 10)]
+            delta: i64,
+            #[polars(null = "option", min = 500.0)]
+            score: Option<f64>,
+        }
+
+        let rows = BoundedStruct::view(&BoundedStruct::sample_df(20, 7))
+            .unwrap()
+            .to_vec()
+            .unwrap();
+        for row in rows {
+            assert!((0..=10).contains(&row.delta));
+            assert!(row.score.is_none_or(|score| score >= 500.0));
+        }
+    }
+
+    #[test]
+    fn test_assert_rows() {
+        #[derive(ColbackView, ColbackOwned, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2],
+            "b" => [Some("x"), None],
+        ]
+        .unwrap();
+        let row = |row_a, row_b: Option<&str>| SomeStructOwned {
+            row_a,
+            row_b: row_b.map(str::to_string),
+        };
+        let expected = [row(1, Some("x")), row(2, None)];
+        colback::assert_rows_eq!(SomeStruct::view(&df).unwrap(), expected);
+        assert_df_matches::<SomeStruct>(&df, &expected);
+
+        assert_eq!(
+            expected[0].field_diffs(&row(1, Some("y"))),
+            vec![FieldDiff {
+                field: "b",
+                expected: "Some(\"x\")".to_string(),
+                actual: "Some(\"y\")".to_string(),
+            }]
+        );
+
+        let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            assert_df_matches::<SomeStruct>(&df, &[row(1, Some("y"))]);
+        }))
+        .unwrap_err();
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert_eq!(
+            msg,
+            "rows differ:\n  \
+             row 0, field `b`: expected Some(\"y\"), actual Some(\"x\")\n  \
+             expected 1 rows, actual 2 rows\n  \
+             row 1 is unexpected: SomeStructOwned { row_a: 2, row_b: None }\n"
+        );
+
+        // Skipped rows are left out
+        #[derive(ColbackView, ColbackOwned, PartialEq)]
+        struct SkipStruct {
+            row_a: u32,
+            #[polars(null = "skip")]
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2],
+            "row_b" => [None, Some("y")],
+        ]
+        .unwrap();
+        assert_df_matches::<SkipStruct>(
+            &df,
+            &[SkipStructOwned {
+                row_a: 2,
+                row_b: "y".to_string(),
+            }],
+        );
+    }
+
+    #[test]
+    fn test_schema_diff() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+            #[polars(null = "option")]
+            row_c: Option<f64>,
+            row_d: bool,
+        }
+
+        let df = df! [
+            "row_b" => [Some("x"), None],
+            "row_a" => [1i64, 2],
+            "row_c" => [None, Some(1.0)],
+            "row_e" => [1u8, 2],
+        ]
+        .unwrap();
+        let diff = SomeStruct::schema_diff(&df);
+        assert_eq!(
+            diff,
+            SchemaDiff {
+                missing: vec!["row_d".to_string()],
+                extra: vec!["row_e".to_string()],
+                dtype_mismatches: vec![DtypeMismatch {
+                    col: "row_a".to_string(),
+                    expected: DataType::UInt32,
+                    actual: DataType::Int64,
+                }],
+                null_conflicts: vec![NullConflict {
+                    col: "row_b".to_string(),
+                    null_count: 1,
+                }],
+            }
+        );
+        assert!(!diff.is_compatible());
+
+        let df = df! [
+            "row_a" => [1u32],
+            "row_b" => ["x"],
+            "row_c" => [None::<f64>],
+            "row_d" => [true],
+            "row_e" => [1u8],
+        ]
+        .unwrap();
+        let diff = SomeStruct::schema_diff(&df);
+        assert!(diff.is_compatible());
+        assert!(!diff.is_empty());
+        assert!(SomeStruct::schema_diff(&df.drop("row_e").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_debug() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2],
+            "b" => [Some("x"), None],
+            "row_c" => [true, false],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        assert_eq!(
+            format!("{view:?}"),
+            "SomeStructView { shape: (2, 3), dtypes: {\"row_a\": UInt32, \"b\": String} }"
+        );
+        assert_eq!(
+            format!("{:?}", view.get(0).unwrap()),
+            "SomeStructRowRef { row_a: 1, b: Some(\"x\") }"
+        );
+    }
+
+    #[test]
+    fn test_row_copy() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2],
+            "row_b" => [Some("x"), None],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let first = view.get(0).unwrap();
+        let by_value = move || (first.row_a, first.row_b);
+        assert_eq!(by_value(), (1, Some("x")));
+        let kept = [first; 2];
+        assert_eq!(kept[1].row_b, first.row_b);
+    }
+
+    #[test]
+    fn test_row_traits() {
+        use std::collections::HashSet;
+
+        #[derive(ColbackView, ColbackOwned, PartialEq, Eq, Hash)]
+        #[polars(derive(PartialEq, Eq, Hash))]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 1],
+            "row_b" => [Some("x"), None, Some("x")],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        assert_eq!(view.get(0).unwrap(), view.get(2).unwrap());
+        assert_ne!(view.get(0).unwrap(), view.get(1).unwrap());
+
+        let rows: HashSet<_> = view.iter().map(|row| row.unwrap()).collect();
+        assert_eq!(rows.len(), 2);
+        let owned: HashSet<_> = view.to_vec().unwrap().into_iter().collect();
+        assert_eq!(owned.len(), 2);
+    }
+
+    #[test]
+    fn test_row_into_struct() {
+        #[derive(ColbackView, Debug, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+            #[polars(null = "default", default = 0)]
+            row_c: i64,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2],
+            "row_b" => [Some("x"), None],
+            "row_c" => [None, Some(3i64)],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let rows: Vec<SomeStruct> = view.iter().map(|row| row.unwrap().into()).collect();
+        assert_eq!(
+            rows,
+            vec![
+                SomeStruct {
+                    row_a: 1,
+                    row_b: Some("x".to_string()),
+                    row_c: 0,
+                },
+                SomeStruct {
+                    row_a: 2,
+                    row_b: None,
+                    row_c: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_any_map() {
+        use chrono::{DateTime, NaiveDateTime};
+
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+            #[polars(time_unit = "us")]
+            row_c: NaiveDateTime,
+        }
+
+        let ts = DateTime::from_timestamp(1, 0).unwrap().naive_utc();
+        let df = df! [
+            "row_a" => [1u32],
+            "b" => [None::<&str>],
+            "row_c" => [ts],
+        ]
+        .unwrap()
+        .lazy()
+        .with_column(col("row_c").cast(DataType::Datetime(TimeUnit::Microseconds, None)))
+        .collect()
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let map = view.get(0).unwrap().to_any_map();
+        assert_eq!(
+            map.keys().copied().collect::<Vec<_>>(),
+            ["row_a", "b", "row_c"]
+        );
+        assert_eq!(map["row_a"], AnyValue::UInt32(1));
+        assert_eq!(map["b"], AnyValue::Null);
+        assert_eq!(
+            map["row_c"],
+            AnyValue::Datetime(1_000_000, TimeUnit::Microseconds, None)
+        );
+    }
+
+    #[test]
+    fn test_row_access() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+        }
+
+        #[derive(ColbackView, PartialEq)]
+        struct OtherStruct {
+            #[polars(null = "option")]
+            row_b: Option<String>,
+            row_c: bool,
+        }
+
+        fn describe(row: &dyn RowAccess) -> String {
+            row.field_names()
+                .iter()
+                .map(|name| format!("{name}={}", row.get_field(name).unwrap()))
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+
+        let df_a = df!["row_a" => [1u32]].unwrap();
+        let df_b = df!["row_b" => [Some("x")], "row_c" => [true]].unwrap();
+        let view_a = SomeStruct::view(&df_a).unwrap();
+        let view_b = OtherStruct::view(&df_b).unwrap();
+        let row_a = view_a.get(0).unwrap();
+        let row_b = view_b.get(0).unwrap();
+        let rows: [&dyn RowAccess; 2] = [&row_a, &row_b];
+        assert_eq!(
+            rows.map(describe),
+            ["row_a=1".to_string(), "row_b=\"x\",row_c=true".to_string()]
+        );
+        assert_eq!(rows.map(|row| row.len()), [1, 2]);
+        assert!(rows[0].get_field("row_b").is_none());
+    }
+
+    #[test]
+    fn test_get_field() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2],
+            "b" => [Some("x"), None],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        assert_eq!(SomeStructRowRef::FIELD_NAMES, ["row_a", "b"]);
+
+        let fields = ["b", "row_a"];
+        let row = view.get(0).unwrap();
+        let values: Vec<_> = fields.iter().map(|f| row.get_field(f).unwrap()).collect();
+        assert_eq!(values, [AnyValue::String("x"), AnyValue::UInt32(1)]);
+        assert_eq!(view.get(1).unwrap().get_field("b"), Some(AnyValue::Null));
+        assert_eq!(row.get_field("row_b"), None);
+    }
+
+    #[test]
+    fn test_view_shared_across_threads() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+        }
+
+        let df = df!["row_a" => (0..100u32).collect::<Vec<_>>()].unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let sums: Vec<u32> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4)
+                .map(|i| {
+                    let view = &view;
+                    s.spawn(move || {
+                        (i * 25..(i + 1) * 25)
+                            .map(|idx| view.get(idx).unwrap().row_a)
+                            .sum()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(sums.iter().sum::<u32>(), (0..100u32).sum::<u32>());
+    }
+
+    #[test]
+    fn test_missing_columns() {
+        #[derive(ColbackView, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: bool,
+            row_c: i64,
+        }
+
+        let df = df!["row_b" => [true]].unwrap();
+        let err = SomeStruct::view(&df).err().unwrap();
+        assert!(matches!(
+            &err,
+            ColbackError::MissingColumns(cols) if cols == &["row_a", "row_c"]
+        ));
+        assert_eq!(
+            err.to_string(),
+            "missing required column(s): [\"row_a\", \"row_c\"]"
+        );
+
+        let df = df!["row_a" => [1u32], "row_b" => [true]].unwrap();
+        assert!(matches!(
+            SomeStruct::view(&df),
+            Err(ColbackError::MissingColumn(col)) if col == "row_c"
+        ));
+    }
+
+    #[test]
+    fn test_null_skip() {
+        #[derive(ColbackView, ColbackOwned, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "skip")]
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 3],
+            "row_b" => [Some("x"), None, Some("z")],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        assert!(matches!(
+            view.get(1),
+            Err(ColbackError::RowSkipped { idx: 1, ref col }) if col == "row_b"
+        ));
+        assert!(view.get_unskipped(1).unwrap().is_none());
+        assert_eq!(view.skipped_column(1), Some("row_b"));
+        assert_eq!(view.skipped_column(2), None);
+        assert!(matches!(
+            view.get_unskipped(3),
+            Err(ColbackError::IndexOutOfBounds { idx: 3, len: 3 })
+        ));
+        // Pages check the rows of the dataframe that they index
+        assert_eq!(view.page(1, 1).skipped_column(0), Some("row_b"));
+
+        let rows: Vec<_> = view.iter().map(|row| row.unwrap().row_a).collect();
+        assert_eq!(rows, vec![1, 3]);
+        assert_eq!(view.to_vec().unwrap().len(), 2);
+        assert_eq!(view.sum_by(|row| row.row_a).unwrap(), 4);
+        assert_eq!(view.fold(0, |n, _| n + 1).unwrap(), 2);
+        assert_eq!(SomeStruct::nullable_columns(), &["row_b"]);
+
+        // Fields whose values are converted are skipped without converting them
+        #[derive(ColbackView)]
+        struct DateStruct {
+            #[polars(null = "skip")]
+            ts: chrono::NaiveDateTime,
+        }
+
+        let ts = chrono::DateTime::from_timestamp(1_700_000_000, 0)
+            .unwrap()
+            .naive_utc();
+        let df = df!("ts" => [Some(ts), None]).unwrap();
+        let view = DateStruct::view(&df).unwrap();
+        assert_eq!(view.skipped_column(1), Some("ts"));
+        assert_eq!(view.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_null_fill() {
+        #[derive(ColbackView, ColbackOwned, PartialEq)]
+        struct SomeStruct {
+            #[polars(fill = "forward")]
+            row_a: u32,
+            #[polars(fill = "backward")]
+            row_b: String,
+            #[polars(fill = "mean")]
+            row_c: f64,
+            #[polars(fill = "zero")]
+            row_d: i64,
+        }
+
+        let df = df! [
+            "row_a" => [Some(1u32), None, Some(3), None],
+            "row_b" => [None, Some("b"), None, Some("d")],
+            "row_c" => [Some(1.0), None, Some(3.0), Some(5.0)],
+            "row_d" => [None, Some(2i64), None, Some(4)],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let rows: Vec<_> = view
+            .iter()
+            .map(|row| {
+                let row = row.unwrap();
+                (row.row_a, row.row_b, row.row_c, row.row_d)
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (1, "b", 1.0, 0),
+                (1, "b", 3.0, 2),
+                (3, "d", 3.0, 0),
+                (3, "d", 5.0, 4),
+            ]
+        );
+        assert_eq!(
+            SomeStruct::nullable_columns(),
+            &["row_a", "row_b", "row_c", "row_d"]
+        );
+
+        // Leading nulls have nothing to fill forward from
+        let df = df! [
+            "row_a" => [None, Some(2u32)],
+            "row_b" => ["a", "b"],
+            "row_c" => [1.0, 2.0],
+            "row_d" => [1i64, 2],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        assert!(matches!(
+            view.get(0),
+            Err(ColbackError::InvalidNull { idx: 0, ref col }) if col == "row_a"
+        ));
+        assert_eq!(view.get(1).unwrap().row_a, 2);
+
+        #[derive(ColbackView)]
+        struct IntStruct {
+            #[polars(fill = "mean")]
+            row_a: i64,
+        }
+
+        // The mean of 1 and 2 is rounded up rather than truncated
+        let df = df!("row_a" => [Some(1i64), Some(2), None]).unwrap();
+        assert_eq!(IntStruct::view(&df).unwrap().get(2).unwrap().row_a, 2);
+    }
+
+    #[test]
+    fn test_missing_default() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(missing = "default", default = 7)]
+            row_b: i64,
+            #[polars(null = "default", missing = "default", default = true)]
+            row_c: bool,
+            #[polars(null = "option", missing = "default", default = 1.5)]
+            row_d: Option<f64>,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let rows: Vec<_> = view
+            .iter()
+            .map(|row| {
+                let row = row.unwrap();
+                (row.row_a, row.row_b, row.row_c, row.row_d)
+            })
+            .collect();
+        assert_eq!(rows, vec![(1, 7, true, Some(1.5)), (2, 7, true, Some(1.5))]);
+
+        // Columns that are present use their values and null policies
+        let df = df! [
+            "row_a" => [1u32],
+            "row_b" => [3i64],
+            "row_c" => [None::<bool>],
+            "row_d" => [None::<f64>],
+        ]
+        .unwrap();
+        let row = SomeStruct::view(&df).unwrap().get(0).unwrap();
+        assert_eq!((row.row_b, row.row_c, row.row_d), (3, true, None));
+
+        let df = df! [
+            "row_b" => [3i64],
+        ]
+        .unwrap();
+        assert!(matches!(
+            SomeStruct::view(&df),
+            Err(ColbackError::MissingColumn(ref col)) if col == "row_a"
+        ));
+    }
+
+    #[test]
+    fn test_nan_policy() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            #[polars(nan = "error")]
+            row_a: f64,
+            #[polars(nan = "null", null = "option")]
+            row_b: Option<f32>,
+            #[polars(nan = "default", default = -1.0)]
+            row_c: f64,
+            #[polars(nan = "null", null = "default", default = 0.0)]
+            row_d: f64,
+        }
+
+        let df = df! [
+            "row_a" => [1.0, f64::NAN],
+            "row_b" => [Some(f32::NAN), None],
+            "row_c" => [f64::NAN, 2.0],
+            "row_d" => [Some(f64::NAN), None],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let row = view.get(0).unwrap();
+        assert_eq!(
+            (row.row_a, row.row_b, row.row_c, row.row_d),
+            (1.0, None, -1.0, 0.0)
+        );
+        assert!(matches!(
+            view.get(1),
+            Err(ColbackError::InvalidNan { idx: 1, ref col }) if col == "row_a"
+        ));
+    }
+
+    #[test]
+    fn test_cast_from() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            #[polars(cast_from = "i64")]
+            row_a: i32,
+            #[polars(cast_from = "u64", overflow = "saturate")]
+            row_b: u8,
+            #[polars(null = "option", cast_from = "i64", overflow = "saturate")]
+            row_c: Option<i32>,
+        }
+
+        let df = df! [
+            "row_a" => [1i64, i64::MAX],
+            "row_b" => [2u64, 300],
+            "row_c" => [None, Some(i64::MIN)],
+        ]
+        .unwrap();
+        assert_eq!(SomeStruct::schema().get("row_a"), Some(&DataType::Int64));
+        let view = SomeStruct::view(&df).unwrap();
+        let row = view.get(0).unwrap();
+        assert_eq!((row.row_a, row.row_b, row.row_c), (1, 2, None));
+        assert!(matches!(
+            view.get(1),
+            Err(ColbackError::ValueOutOfRange { idx: 1, ref col, ref value })
+                if col == "row_a" && *value == i64::MAX.to_string()
+        ));
+
+        let df = df! [
+            "row_a" => [3i64],
+            "row_b" => [300u64],
+            "row_c" => [Some(i64::MIN)],
+        ]
+        .unwrap();
+        let row = SomeStruct::from(SomeStruct::view(&df).unwrap().get(0).unwrap());
+        assert_eq!((row.row_b, row.row_c), (u8::MAX, Some(i32::MIN)));
+
+        // Rows are written back with the wider dtypes of the columns
+        let written = vec![row].into_dataframe().unwrap();
+        assert_eq!(written.column("row_b").unwrap().dtype(), &DataType::UInt64);
+        assert!(SomeStruct::view(&written).is_ok());
+    }
+
+    #[test]
+    fn test_epoch_fields() {
+        use chrono::{DateTime, TimeDelta, Utc};
+        use std::time::Duration;
+
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            #[polars(epoch = "ms")]
+            row_a: DateTime<Utc>,
+            #[polars(epoch = "us", null = "option")]
+            row_b: Option<TimeDelta>,
+            #[polars(epoch = "ms", cast_from = "u64")]
+            row_c: Duration,
+        }
+
+        let df = df! [
+            "row_a" => [1_704_067_200_000i64, i64::MAX],
+            "row_b" => [Some(-1_500i64), None],
+            "row_c" => [2_000u64, u64::MAX],
+        ]
+        .unwrap();
+        assert_eq!(SomeStruct::schema().get("row_a"), Some(&DataType::Int64));
+        assert_eq!(SomeStruct::schema().get("row_c"), Some(&DataType::UInt64));
+        let view = SomeStruct::view(&df).unwrap();
+        let row = view.get(0).unwrap();
+        assert_eq!(
+            row.row_a,
+            DateTime::from_timestamp(1_704_067_200, 0).unwrap()
+        );
+        assert_eq!(row.row_b, Some(TimeDelta::microseconds(-1_500)));
+        assert_eq!(row.row_c, Duration::from_secs(2));
+        assert!(matches!(
+            view.get(1),
+            Err(ColbackError::ValueOutOfRange { idx: 1, ref col, ref value })
+                if col == "row_a" && *value == i64::MAX.to_string()
+        ));
+
+        // Rows are written back as the integers of their columns
+        let row = SomeStruct::from(row);
+        let written = vec![row].into_dataframe().unwrap();
+        assert!(written.equals(&df.head(Some(1))));
+
+        // Values that don't fit in their column aren't written as nulls
+        let row = SomeStruct {
+            row_a: DateTime::from_timestamp(0, 0).unwrap(),
+            row_b: None,
+            row_c: Duration::MAX,
+        };
+        assert!(matches!(
+            vec![row].into_dataframe(),
+            Err(ColbackError::ValueOutOfRange { idx: 0, ref col, .. }) if col == "row_c"
+        ));
+    }
+
+    #[test]
+    fn test_category_fields() {
+        use polars::prelude::{Categories, FrozenCategories};
+
+        #[derive(ColbackCategory, Clone, Copy, Debug, PartialEq)]
+        enum Color {
+            Red,
+            #[polars(name = "blue")]
+            Blue,
+            Other,
+        }
+
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder)]
+        struct SomeStruct {
+            #[polars(category, null = "option")]
+            row_a: Option<Color>,
+            #[polars(category, unknown = "other(Other)")]
+            row_b: Color,
+            #[polars(category, unknown = "skip")]
+            row_c: Color,
+        }
+
+        let df = df! [
+            "row_a" => [Some("Red"), None, Some("green"), Some("Red")],
+            "row_b" => ["blue", "green", "Red", "Blue"],
+            "row_c" => ["Red", "blue", "Red", "Green"],
+        ]
+        .unwrap();
+        assert_eq!(SomeStruct::schema().get("row_a"), Some(&DataType::String));
+        let view = SomeStruct::view(&df).unwrap();
+        let row = view.get(0).unwrap();
+        assert_eq!(row.row_a, Some(Color::Red));
+        assert_eq!(row.row_b, Color::Blue);
+        assert_eq!(row.get_field("row_b"), Some(AnyValue::String("blue")));
+        let row = view.get(1).unwrap();
+        assert_eq!(row.row_a, None);
+        assert_eq!(row.row_b, Color::Other);
+        assert!(matches!(
+            view.get(2),
+            Err(ColbackError::UnknownCategory { idx: 2, ref col, ref value })
+                if col == "row_a" && value == "green"
+        ));
+        assert!(matches!(
+            view.get(3),
+            Err(ColbackError::RowSkipped { idx: 3, ref col }) if col == "row_c"
+        ));
+        assert_eq!(view.skipped_column(3), Some("row_c"));
+        assert_eq!(view.iter().count(), 3);
+
+        // Categorical and enum columns are read through the names of their categories
+        let names = ["Red", "blue", "green", "Blue", "Green"];
+        for dtype in [
+            DataType::from_categories(Categories::global()),
+            DataType::from_frozen_categories(FrozenCategories::new(names).unwrap()),
+        ] {
+            let cast = df
+                .clone()
+                .lazy()
+                .with_columns(["row_a", "row_b", "row_c"].map(|name| col(name).cast(dtype.clone())))
+                .collect()
+                .unwrap();
+            let view = SomeStruct::view(&cast).unwrap();
+            let row = view.get(0).unwrap();
+            assert_eq!((row.row_a, row.row_b), (Some(Color::Red), Color::Blue));
+            assert_eq!(view.get(1).unwrap().row_b, Color::Other);
+            assert!(matches!(
+                view.get(2),
+                Err(ColbackError::UnknownCategory { idx: 2, ref value, .. }) if value == "green"
+            ));
+            assert_eq!(view.skipped_column(3), Some("row_c"));
+        }
+        let wrong = df
+            .clone()
+            .lazy()
+            .with_column(lit(1u32).alias("row_b"))
+            .collect()
+            .unwrap();
+        assert!(matches!(
+            SomeStruct::view(&wrong),
+            Err(ColbackError::WrongDtype { ref col, .. }) if col == "row_b"
+        ));
+
+        // Rows are written back as category names, which filters compare against
+        let mut builder = SomeStruct::builder(2);
+        builder.push(&view.get(0).unwrap().to_owned()).unwrap();
+        builder
+            .push_fields(None, Color::Other, Color::Blue)
+            .unwrap();
+        let built = builder.finish().unwrap();
+        let expected = df! [
+            "row_a" => [Some("Red"), None],
+            "row_b" => ["blue", "Other"],
+            "row_c" => ["Red", "blue"],
+        ]
+        .unwrap();
+        assert!(built.equals_missing(&expected));
+        let rows = vec![SomeStruct::from(view.get(1).unwrap())];
+        assert!(
+            rows.into_dataframe()
+                .unwrap()
+                .equals_missing(&expected.slice(1, 1))
+        );
+        let filtered = df
+            .lazy()
+            .filter(SomeStructFilter::row_c().eq(Color::Red))
+            .collect()
+            .unwrap();
+        assert_eq!(filtered.height(), 2);
+
+        // Categories are serialized as their names
+        #[cfg(feature = "serde")]
+        {
+            let row = SomeStruct::view(&built).unwrap().get(0).unwrap().to_owned();
+            let json = serde_json::to_value(&row).unwrap();
+            let expected = serde_json::json!({"row_a": "Red", "row_b": "blue", "row_c": "Red"});
+            assert_eq!(json, expected);
+            assert_eq!(
+                serde_json::from_value::<SomeStructOwned>(json).unwrap(),
+                row
+            );
+            assert!(serde_json::from_str::<Color>("\"green\"").is_err());
+        }
+    }
+
+    #[test]
+    fn test_group_aggregate() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        #[derive(ColbackKey, Eq, PartialEq)]
+        struct SomeKey {
+            row_b: String,
+        }
+
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder)]
+        struct SomeAgg {
+            total: u32,
+            n: u32,
+        }
+
+        fn agg(group: SomeStructView<'_>) -> SomeAggOwned {
+            SomeAggOwned {
+                total: group.iter().map(|row| row.unwrap().row_a).sum(),
+                n: group.len() as u32,
+            }
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2u32, 3u32, 4u32],
+            "row_b" => ["x", "y", "x", "x"],
+        ]
+        .unwrap();
+        let aggregates = SomeStruct::view(&df)
+            .unwrap()
+            .group_by::<SomeKey>()
+            .unwrap()
+            .aggregate::<SomeAgg, _>(agg)
+            .unwrap();
+        let expected = df! [
+            "row_b" => ["x", "y"],
+            "total" => [8u32, 2u32],
+            "n" => [3u32, 1u32],
+        ]
+        .unwrap();
+        assert!(aggregates.equals(&expected));
+
+        let applied = df
+            .group_by(["row_b"])
+            .unwrap()
+            .apply(group_apply::<SomeStruct, SomeAgg, _>(agg))
+            .unwrap()
+            .sort(["n"], Default::default())
+            .unwrap();
+        let expected = df! [
+            "total" => [2u32, 8u32],
+            "n" => [1u32, 3u32],
+        ]
+        .unwrap();
+        assert!(applied.equals(&expected));
+    }
+
+    #[test]
+    fn test_typed_frame() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "skip")]
+            row_b: u32,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 3],
+            "row_b" => [Some(1u32), None, Some(3)],
+        ]
+        .unwrap();
+        let mut frame = TypedFrame::<SomeStruct>::new(df).unwrap();
+        assert_eq!(frame.len(), 3);
+        assert_eq!(frame.get(2).unwrap().row_a, 3);
+        let rows: Vec<_> = frame.iter().map(|row| row.unwrap().row_a).collect();
+        assert_eq!(rows, vec![1, 3]);
+
+        frame
+            .update(|df| {
+                df.with_column(Series::new("row_b".into(), [4u32, 5, 6]))?;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(frame.view().get(1).unwrap().row_b, 5);
+        assert_eq!(frame.clone().get(2).unwrap().row_b, 6);
+
+        // Invalid updates leave the frame as it was
+        let err = frame.update(|df| {
+            df.with_column(Series::new("row_a".into(), ["x", "y", "z"]))?;
+            Ok(())
+        });
+        assert!(matches!(err, Err(ColbackError::WrongDtype { .. })));
+        assert_eq!(
+            frame.df().column("row_a").unwrap().dtype(),
+            &DataType::UInt32
+        );
+
+        assert!(TypedFrame::<SomeStruct>::new(df!("row_a" => [1u32]).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_generic_views() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "skip")]
+            row_b: u32,
+        }
+
+        #[derive(ColbackView)]
+        struct OtherStruct {
+            row_c: i64,
+        }
+
+        /// The values of a field of every row of any struct's view.
+        fn field_values<T: ColbackView>(
+            df: &DataFrame,
+            name: &str,
+        ) -> Result<Vec<AnyValue<'static>>> {
+            let view = T::view(df)?;
+            view.iter()
+                .map(|row| Ok(row?.get_field(name).unwrap().into_static()))
+                .collect()
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 3],
+            "row_b" => [Some(1u32), None, Some(3)],
+            "row_c" => [4i64, 5, 6],
+        ]
+        .unwrap();
+        assert_eq!(
+            field_values::<SomeStruct>(&df, "row_a").unwrap(),
+            vec![AnyValue::UInt32(1), AnyValue::UInt32(3)]
+        );
+        assert_eq!(
+            field_values::<OtherStruct>(&df, "row_c").unwrap(),
+            vec![AnyValue::Int64(4), AnyValue::Int64(5), AnyValue::Int64(6)]
+        );
+    }
+
+    #[test]
+    fn test_derive_schema() {
+        #[derive(ColbackSchema)]
+        #[allow(dead_code)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+            #[polars(cast_from = "i64")]
+            row_c: i32,
+        }
+
+        #[derive(ColbackView)]
+        struct SameStruct {
+            row_a: u32,
+            #[polars(name = "b", null = "option")]
+            row_b: Option<String>,
+            #[polars(cast_from = "i64")]
+            row_c: i32,
+        }
+
+        assert_eq!(SomeStruct::schema(), SameStruct::schema());
+        assert_eq!(
+            SomeStruct::nullable_columns(),
+            SameStruct::nullable_columns()
+        );
+        assert_eq!(
+            SomeStruct::dtypes(),
+            vec![DataType::UInt32, DataType::String, DataType::Int64]
+        );
+
+        let df = df! [
+            "row_a" => [1u32],
+            "b" => ["x"],
+            "row_c" => [2i64],
+            "extra" => [0],
+        ]
+        .unwrap();
+        assert!(SomeStruct::schema_diff(&df).is_compatible());
+        let projected = df
+            .lazy()
+            .select(SomeStruct::projection())
+            .collect()
+            .unwrap();
+        assert_eq!(projected.get_column_names(), SomeStruct::column_names());
+    }
+
+    #[test]
+    fn test_view_with() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+        }
+
+        let mut df = df! [
+            "row_a" => [0u32, 1u32],
+        ]
+        .unwrap();
+        df.vstack_mut(&df.clone()).unwrap();
+        df.vstack_mut(&df.clone()).unwrap();
+        assert_eq!(df.max_n_chunks(), 4);
+
+        let view = SomeStruct::view_with(&mut df, ViewOptions::default()).unwrap();
+        assert_eq!(view.df().max_n_chunks(), 4);
+
+        // Frames within the chunk limit are left alone
+        let options = ViewOptions {
+            rechunk: true,
+            max_chunks: 4,
+        };
+        let view = SomeStruct::view_with(&mut df, options).unwrap();
+        assert_eq!(view.df().max_n_chunks(), 4);
+
+        let options = ViewOptions {
+            max_chunks: 2,
+            ..options
+        };
+        let view = SomeStruct::view_with(&mut df, options).unwrap();
+        assert_eq!(view.len(), 8);
+        assert_eq!(view.df().max_n_chunks(), 1);
+    }
+
+    #[test]
+    fn test_raw() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+        }
+
+        let mut df = df! [
+            "row_a" => [0u32, 1],
+            "row_b" => [Some("x"), None],
+        ]
+        .unwrap();
+        df.vstack_mut(&df.clone()).unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let raw = view.raw();
+
+        let sum: u32 = raw
+            .row_a
+            .chunks()
+            .iter()
+            .map(|c| c.values().iter().sum::<u32>())
+            .sum();
+        assert_eq!(sum, 2);
+        let (chunk, local_idx) = raw.row_b.chunk_at(2).unwrap();
+        assert_eq!((chunk.value(local_idx), local_idx), ("x", 0));
+        assert!(
+            raw.row_b
+                .chunk_at(3)
+                .is_some_and(|(c, i)| c.get(i).is_none())
+        );
+    }
+
+    #[test]
+    fn test_string_zero_copy() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: String,
+        }
+
+        // Strings of up to 12 bytes are inlined in their views, longer ones are in data buffers
+        let df = df! [
+            "row_a" => ["short", "a string that is too long to inline"],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let (chunk, _) = view.raw().row_a.chunk_at(0).unwrap();
+        for idx in 0..view.len() {
+            let row = view.get(idx).unwrap();
+            assert_eq!(row.row_a.as_ptr(), chunk.value(idx).as_ptr());
+        }
+        let buffer = chunk.data_buffers()[0].as_slice().as_ptr_range();
+        assert!(buffer.contains(&view.get(1).unwrap().row_a.as_ptr()));
+    }
+
+    #[test]
+    fn test_pages() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(fill = "forward")]
+            row_b: i64,
+        }
+
+        let df = df! [
+            "row_a" => [0u32, 1, 2, 3, 4],
+            "row_b" => [Some(10i64), None, None, Some(13), None],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let pages: Vec<_> = view.pages(2).collect();
+        assert_eq!(pages.iter().map(|p| p.len()).collect::<Vec<_>>(), [2, 2, 1]);
+        let row = pages[1].get(0).unwrap();
+        assert_eq!((row.row_a, row.row_b), (2, 10));
+        assert!(pages[2].get(1).is_err());
+        assert_eq!(pages[2].frame().height(), 1);
+        assert_eq!(pages[1].filter(|row| row.row_a == 3).unwrap().height(), 1);
+
+        let page = view.page(1, 3);
+        assert_eq!(
+            page.iter()
+                .map(|row| row.unwrap().row_b)
+                .collect::<Vec<_>>(),
+            [13, 13]
+        );
+        assert_eq!(page.page(1, 1).get(0).unwrap().row_a, 4);
+        assert!(view.page(5, 2).is_empty());
+    }
+
+    #[test]
+    fn test_split() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        let df = df! [
+            "row_a" => (0..20u32).collect::<Vec<_>>(),
+            "row_b" => (0..20).map(|i| if i < 15 { "x" } else { "y" }).collect::<Vec<_>>(),
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let (train, test) = view.split(0.8, 7).unwrap();
+        assert_eq!((train.height(), test.height()), (16, 4));
+        assert_eq!(view.split(0.8, 7).unwrap(), (train.clone(), test));
+        let ids: Vec<_> = train
+            .column("row_a")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert!(ids.is_sorted());
+        assert!(view.split(1.5, 7).is_err());
+
+        let (train, test) = view
+            .split_stratified(SomeStructField::RowB, 0.6, 7)
+            .unwrap();
+        let count = |df: &DataFrame, value| {
+            let values = df.column("row_b").unwrap().str().unwrap();
+            values.into_no_null_iter().filter(|&v| v == value).count()
+        };
+        assert_eq!((count(&train, "x"), count(&train, "y")), (9, 3));
+        assert_eq!((count(&test, "x"), count(&test, "y")), (6, 2));
+
+        let folds = view.kfold_stratified(SomeStructField::RowB, 5, 7).unwrap();
+        assert_eq!(folds.len(), 5);
+        for (train, test) in &folds {
+            assert_eq!((train.height(), test.height()), (16, 4));
+            assert_eq!(count(test, "y"), 1);
+        }
+        let mut tested: Vec<_> = folds
+            .iter()
+            .flat_map(|(_, test)| {
+                test.column("row_a")
+                    .unwrap()
+                    .u32()
+                    .unwrap()
+                    .into_no_null_iter()
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        tested.sort();
+        assert_eq!(tested, (0..20).collect::<Vec<_>>());
+        assert!(view.kfold(1, 7).is_err());
+    }
+
+    #[test]
+    fn test_sample_stratified() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+            #[polars(null = "option")]
+            row_c: Option<f64>,
+        }
+
+        let df = df! [
+            "row_a" => (0..10u32).collect::<Vec<_>>(),
+            "row_b" => ["x", "x", "x", "x", "x", "x", "y", "y", "y", "z"],
+            "row_c" => [Some(1.0), Some(0.0), None, Some(2.0), Some(1.0), Some(0.0), Some(3.0), Some(0.0), Some(1.0), Some(0.0)],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let ids = |df: &DataFrame| {
+            let ids = df.column("row_a").unwrap().u32().unwrap();
+            ids.into_no_null_iter().collect::<Vec<_>>()
+        };
+
+        let sample = view.sample_stratified(SomeStructField::RowB, 2, 3).unwrap();
+        let groups = sample.column("row_b").unwrap().str().unwrap();
+        let groups: Vec<_> = groups.into_no_null_iter().collect();
+        assert_eq!(groups, ["x", "x", "y", "y", "z"]);
+        assert!(ids(&sample).is_sorted());
+        assert_eq!(
+            view.sample_stratified(SomeStructField::RowB, 2, 3).unwrap(),
+            sample
+        );
+
+        // Only the rows with positive weights can be sampled
+        let sample = view.sample_weighted(SomeStructField::RowC, 10, 3).unwrap();
+        assert_eq!(ids(&sample), [0, 3, 4, 6, 8]);
+        let sample = view.sample_weighted(SomeStructField::RowC, 2, 3).unwrap();
+        assert_eq!(sample.height(), 2);
+        assert!(ids(&sample).iter().all(|id| [0, 3, 4, 6, 8].contains(id)));
+        assert!(view.sample_weighted(SomeStructField::RowB, 2, 3).is_err());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 1, 1],
+            "row_b" => [Some("x"), Some("x"), None],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let hashes: Vec<_> = (0..3)
+            .map(|i| view.get(i).unwrap().content_hash())
+            .collect();
+        // Pinned so that changes to the encoding are caught
+        assert_eq!(hashes[0], 0x11bc_65ab_1001_0092);
+        assert_eq!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[2]);
+
+        let fingerprint = view.fingerprint().unwrap();
+        assert_eq!(
+            SomeStruct::view(&df.clone())
+                .unwrap()
+                .fingerprint()
+                .unwrap(),
+            fingerprint
+        );
+        let changed = df! [
+            "row_a" => [1u32, 2, 1],
+            "row_b" => [Some("x"), Some("x"), None],
+        ]
+        .unwrap();
+        assert_ne!(
+            SomeStruct::view(&changed).unwrap().fingerprint().unwrap(),
+            fingerprint
+        );
+        assert_ne!(view.page(0, 2).fingerprint().unwrap(), fingerprint);
+    }
+
+    #[test]
+    fn test_explode() {
+        use polars::prelude::{NamedFrom, Series};
+
+        #[derive(ColbackView)]
+        struct Order {
+            order_id: u32,
+            items: Vec<i64>,
+        }
+
+        #[derive(ColbackView)]
+        struct Item {
+            order_id: u32,
+            items: i64,
+        }
+
+        let items = Series::new(
+            "items".into(),
+            [
+                Series::new("".into(), [1i64, 2]),
+                Series::new("".into(), [3i64]),
+            ],
+        );
+        let df = DataFrame::new(vec![
+            Series::new("order_id".into(), [7u32, 8]).into(),
+            items.into(),
+        ])
+        .unwrap();
+        let view = Order::view(&df).unwrap();
+
+        let exploded = view.explode::<Item>(OrderField::Items).unwrap();
+        let items = Item::view(&exploded).unwrap();
+        let rows: Vec<_> = items
+            .iter()
+            .map(|row| row.map(|row| (row.order_id, row.items)))
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(rows, [(7, 1), (7, 2), (8, 3)]);
+        assert!(matches!(
+            view.explode::<Item>(OrderField::OrderId),
+            Err(ColbackError::Polars(_))
+        ));
+    }
+
+    #[test]
+    fn test_list_fields() {
+        use polars::prelude::{NamedFrom, Series};
+
+        #[derive(ColbackView, ColbackOwned, ColbackBuilder, PartialEq)]
+        struct SomeStruct {
+            row_a: Vec<u32>,
+            #[polars(null = "option")]
+            row_b: Option<Vec<f64>>,
+        }
+
+        let mut builder = SomeStruct::builder(3);
+        builder.push_fields(vec![1, 2], Some(vec![0.5])).unwrap();
+        builder.push_fields(vec![], None).unwrap();
+        builder
+            .push(&SomeStructOwned {
+                row_a: vec![3],
+                row_b: Some(vec![]),
+            })
+            .unwrap();
+        let df = builder.finish().unwrap();
+        assert_eq!(
+            df.column("row_a").unwrap().dtype(),
+            &DataType::List(Box::new(DataType::UInt32))
+        );
+
+        let view = SomeStruct::view(&df).unwrap();
+        let row = view.get(0).unwrap();
+        assert_eq!(row.row_a, [1, 2]);
+        assert_eq!(row.row_b, Some(&[0.5][..]));
+        assert_eq!(view.get(1).unwrap().row_b, None);
+        assert_eq!(
+            row.get_field("row_a"),
+            Some(AnyValue::List(Series::new("".into(), [1u32, 2])))
+        );
+        let owned = view.to_vec().unwrap();
+        assert_eq!(owned[2].row_a, [3]);
+        let rows = owned.into_iter().map(|row| SomeStruct {
+            row_a: row.row_a,
+            row_b: row.row_b,
+        });
+        assert!(rows.into_dataframe().unwrap().equals_missing(&df));
+
+        let mut view = SomeStruct::view_mut(&df).unwrap();
+        view.set_row_a(1, vec![4, 5]).unwrap();
+        let updated = view.commit().unwrap();
+        let view = SomeStruct::view(&updated).unwrap();
+        assert_eq!(view.get(1).unwrap().row_a, [4, 5]);
+        assert_eq!(view.get(2).unwrap().row_a, [3]);
+
+        // Lists with null elements can't be borrowed as slices
+        let nulls = Series::new("row_a".into(), [Series::new("".into(), [Some(1u32), None])]);
+        let df = DataFrame::new(vec![
+            nulls.into(),
+            Series::new("row_b".into(), [Series::new("".into(), [1.0])]).into(),
+        ])
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        assert!(matches!(
+            view.get(0),
+            Err(ColbackError::InvalidNull { idx: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_melt() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: String,
+            row_b: i64,
+            row_c: i64,
+            row_d: f64,
+        }
+
+        let df = df! [
+            "row_a" => ["x", "y"],
+            "row_b" => [1i64, 2],
+            "row_c" => [3i64, 4],
+            "row_d" => [0.5, 1.5],
+        ]
+        .unwrap();
+
+        let melted = SomeStruct::melt(
+            &df,
+            &[SomeStructField::RowA],
+            &[SomeStructField::RowB, SomeStructField::RowC],
+        )
+        .unwrap();
+        let expected = df! [
+            "row_a" => ["x", "y", "x", "y"],
+            "variable" => ["row_b", "row_b", "row_c", "row_c"],
+            "value" => [1i64, 2, 3, 4],
+        ]
+        .unwrap();
+        assert!(melted.equals(&expected));
+
+        assert!(matches!(
+            SomeStruct::melt(
+                &df,
+                &[SomeStructField::RowA],
+                &[SomeStructField::RowB, SomeStructField::RowD],
+            ),
+            Err(ColbackError::WrongDtype { .. })
+        ));
+        assert!(SomeStruct::melt(&df, &[SomeStructField::RowA], &[]).is_err());
+    }
+
+    #[test]
+    fn test_constraints() {
+        #[derive(ColbackView)]
+        #[polars(check = col("row_a").lt(col("row_b")))]
+        struct SomeStruct {
+            #[polars(min = -1)]
+            row_a: i64,
+            #[polars(null = "option", max = 10.0)]
+            row_b: Option<f64>,
+        }
+
+        let df = df! [
+            "row_a" => [0i64, -1, 5, -2],
+            "row_b" => [Some(1.0), None, Some(4.0), Some(12.0)],
+        ]
+        .unwrap();
+        let Err(ColbackError::ConstraintViolations(violations)) = SomeStruct::view(&df) else {
+            panic!("expected constraint violations");
+        };
+        // Constraints are labeled as they're written
+        assert_eq!(
+            violations[..],
+            [
+                ConstraintViolation {
+                    constraint: r#"col("row_a").lt(col("row_b"))"#.to_string(),
+                    count: 1,
+                    examples: vec![2],
+                },
+                ConstraintViolation {
+                    constraint: "row_a >= -1".to_string(),
+                    count: 1,
+                    examples: vec![3],
+                },
+                ConstraintViolation {
+                    constraint: "row_b <= 10.0".to_string(),
+                    count: 1,
+                    examples: vec![3],
+                },
+            ]
+        );
+
+        let df = df! [
+            "row_a" => [0i64, 3],
+            "row_b" => [None, Some(4.0)],
+        ]
+        .unwrap();
+        assert!(SomeStruct::view(&df).is_ok());
+    }
+
+    #[test]
+    fn test_describe() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: i64,
+            #[polars(null = "option")]
+            row_b: Option<String>,
+            row_c: bool,
+        }
+
+        let df = df! [
+            "row_a" => [1i64, 2, 3, 6],
+            "row_b" => [Some("x"), None, Some("y"), Some("x")],
+            "row_c" => [true, false, true, true],
+            "extra" => [0, 0, 0, 0],
+        ]
+        .unwrap();
+        let report = SomeStruct::describe(&df).unwrap();
+        assert_eq!(report.columns.len(), 3);
+        let row_a = report.get("row_a").unwrap();
+        assert_eq!((row_a.count, row_a.null_count), (4, 0));
+        let ColumnSummary::Numeric {
+            min,
+            max,
+            mean,
+            std,
+        } = row_a.summary
+        else {
+            panic!("expected numeric statistics");
+        };
+        assert_eq!((min, max, mean), (Some(1.0), Some(6.0), Some(3.0)));
+        assert!((std.unwrap() - (14f64 / 3.0).sqrt()).abs() < 1e-9);
+        let row_b = report.get("row_b").unwrap();
+        assert_eq!((row_b.count, row_b.null_count), (3, 1));
+        assert_eq!(row_b.summary, ColumnSummary::Cardinality(2));
+        assert_eq!(report.get("row_c").unwrap().summary, ColumnSummary::Other);
+        assert!(report.get("extra").is_none());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_view_metrics() {
+        #[derive(ColbackView)]
+        #[allow(dead_code)]
+        struct SomeStruct {
+            row_a: i64,
+            #[polars(null = "skip")]
+            row_b: i64,
+        }
+
+        use metrics_util::{
+            CompositeKey, MetricKind,
+            debugging::{DebugValue, DebuggingRecorder},
+        };
+
+        let df = df! [
+            "row_a" => [1i64, 2, 3],
+            "row_b" => [Some(1i64), None, Some(3)],
+        ]
+        .unwrap();
+        let missing = df!("row_a" => [1i64]).unwrap();
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let view = SomeStruct::view(&df).unwrap();
+            assert_eq!(view.iter().count(), 2);
+            assert!(matches!(
+                SomeStruct::view(&missing),
+                Err(ColbackError::MissingColumn(_))
+            ));
+        });
+
+        let counters = snapshotter.snapshot().into_hashmap();
+        let counter = |name: &'static str, labels: &[(&'static str, &'static str)]| {
+            let labels = labels
+                .iter()
+                .map(|&(key, value)| metrics::Label::new(key, value));
+            let key = metrics::Key::from_parts(name, labels.collect::<Vec<_>>());
+            match counters.get(&CompositeKey::new(MetricKind::Counter, key)) {
+                Some((_, _, DebugValue::Counter(value))) => *value,
+                _ => panic!("no counter named {name}"),
+            }
+        };
+        let view_label = ("struct", "SomeStruct");
+        assert_eq!(counter("colback_rows_viewed_total", &[view_label]), 3);
+        assert_eq!(counter("colback_schema_failures_total", &[view_label]), 1);
+        let null_label = |column| [view_label, ("column", column)];
+        assert_eq!(
+            counter("colback_null_values_total", &null_label("row_a")),
+            0
+        );
+        assert_eq!(
+            counter("colback_null_values_total", &null_label("row_b")),
+            1
+        );
+        assert_eq!(
+            counter("colback_rows_skipped_total", &[("column", "row_b")]),
+            1
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_map_chunks() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+        }
+
+        let mut df = df! [
+            "row_a" => [1u32, 2u32],
+            "row_b" => ["x", "y"],
+        ]
+        .unwrap();
+        df.vstack_mut(&df!("row_a" => [3u32], "row_b" => ["z"]).unwrap())
+            .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+        let doubled = view
+            .par_map_chunks(|chunk| {
+                let [values] = chunk.raw().row_a.chunks() else {
+                    panic!("chunks are viewed on their own");
+                };
+                let doubled: Vec<u32> = values.values().iter().map(|v| v * 2).collect();
+                Ok(df!("doubled" => doubled)?)
+            })
+            .unwrap();
+        assert_eq!(doubled.first_col_n_chunks(), 2);
+        assert!(doubled.equals(&df!("doubled" => [2u32, 4, 6]).unwrap()));
+
+        // A frame without any of the struct's columns has no chunks, so it's viewed as a whole
+        #[derive(ColbackView)]
+        struct Defaults {
+            #[polars(missing = "default", default = 7)]
+            row_c: u32,
+        }
+
+        let view = Defaults::view(&df).unwrap();
+        let counts = view
+            .par_map_chunks(|chunk| Ok(df!("count" => [chunk.len() as u32])?))
+            .unwrap();
+        assert!(counts.equals(&df!("count" => [3u32]).unwrap()));
+
+        // Fills from other rows would only see the rows of a chunk
+        #[derive(ColbackView)]
+        struct Filled {
+            row_a: u32,
+            #[polars(fill = "forward")]
+            row_b: i64,
+        }
+
+        let df = df! [
+            "row_a" => [1u32],
+            "row_b" => [Some(1i64)],
+        ]
+        .unwrap();
+        assert_eq!(Filled::filled_columns(), ["row_b"]);
+        assert!(
+            Filled::view(&df)
+                .unwrap()
+                .par_map_chunks(|_| Ok(DataFrame::empty()))
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_to_ndarray() {
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: u32,
+            row_b: String,
+            #[polars(null = "option")]
+            row_c: Option<f64>,
+            #[polars(null = "skip")]
+            row_d: i64,
+        }
+
+        let df = df! [
+            "row_a" => [1u32, 2, 3],
+            "row_b" => ["x", "y", "z"],
+            "row_c" => [Some(0.5), None, Some(1.5)],
+            "row_d" => [Some(-1i64), Some(-2), None],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let matrix = view.to_ndarray().unwrap();
+        assert_eq!(matrix.dim(), (2, 3));
+        assert_eq!(matrix.row(0).to_vec(), vec![1.0, 0.5, -1.0]);
+        assert_eq!(matrix[[1, 0]], 2.0);
+        assert!(matrix[[1, 1]].is_nan());
+
+        let matrix = view
+            .to_ndarray_of(&[SomeStructField::RowD, SomeStructField::RowA])
+            .unwrap();
+        assert_eq!(matrix, ndarray::array![[-1.0, 1.0], [-2.0, 2.0]]);
+        assert!(view.to_ndarray_of(&[SomeStructField::RowB]).is_err());
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_to_matrix() {
+        use nalgebra::{DMatrix, Vector3};
+
+        #[derive(ColbackView)]
+        struct SomeStruct {
+            row_a: f64,
+            #[polars(null = "default", default = 0.0)]
+            row_b: f64,
+            #[polars(null = "skip")]
+            row_c: f64,
+        }
+
+        let df = df! [
+            "row_a" => [1.0, 2.0, 3.0],
+            "row_b" => [Some(0.5), None, Some(1.5)],
+            "row_c" => [Some(-1.0), Some(-2.0), None],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        assert_eq!(
+            view.get(0).unwrap().to_vector(),
+            Vector3::new(1.0, 0.5, -1.0)
+        );
+        assert_eq!(
+            view.to_matrix().unwrap(),
+            DMatrix::from_row_slice(2, 3, &[1.0, 0.5, -1.0, 2.0, 0.0, -2.0])
+        );
+    }
+
+    #[test]
+    fn test_iter_filtered() {
+        #[derive(ColbackView, Eq, PartialEq)]
+        struct SomeStruct {
+            row_a: u32,
+        }
+
+        let df = df! [
+            "row_a" => [0u32, 1u32, 2u32, 3u32],
+        ]
+        .unwrap();
+        let view = SomeStruct::view(&df).unwrap();
+
+        let mask = BooleanChunked::from_slice("".into(), &[true; 5]);
+        assert!(matches!(
+            view.iter_filtered(&mask),
+            Err(ColbackError::LengthMismatch {
+                expected: 4,
+                actual: 5
+            })
+        ));
+
+        let mask: BooleanChunked = [Some(true), Some(false), None, Some(true)]
+            .into_iter()
+            .collect();
+        let values: Vec<u32> = view
+            .iter_filtered(&mask)
+            .unwrap()
+            .map(|r| r.unwrap().row_a)
+            .collect();
+        assert_eq!(values, vec![0, 3]);
+    }
+
+    #[test]
+    fn test_mask_indices_multi_chunk() {
+        let mut mask = BooleanChunked::from_slice("".into(), &[false, true]);
+        mask.append(&BooleanChunked::from_slice("".into(), &[true, false, true]))
+            .unwrap();
+        assert_eq!(mask_indices(&mask).collect::<Vec<_>>(), vec![1, 2, 4]);
+    }
 }
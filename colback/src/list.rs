@@ -0,0 +1,86 @@
+//! List columns of numbers, which back `Vec` fields.
+
+use crate::ChunkIndex;
+use polars::prelude::{
+    AnyValue, ChunkedArray, IntoSeries, ListBuilderTrait, ListChunked, ListPrimitiveChunkedBuilder,
+    ListType, NewChunkedArray, PlSmallStr, PolarsNumericType,
+};
+
+impl<'a> ChunkIndex<'a, ListType> {
+    /// Get the elements of the list at a given row, borrowed from the column.
+    ///
+    /// Returns `None` if the list is null or if the index is out of bounds, and `Some(None)` if
+    /// any of the list's elements are null, since those can't be borrowed as a slice.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `T` isn't the type of the list's elements.
+    #[inline]
+    pub fn get_slice<T: PolarsNumericType>(&self, idx: usize) -> Option<Option<&'a [T::Native]>> {
+        let (chunk, local_idx) = self.chunk_at(idx)?;
+        if chunk.validity().is_some_and(|v| !v.get_bit(local_idx)) {
+            return None;
+        }
+        let (start, end) = chunk.offsets().start_end(local_idx);
+        let values = chunk
+            .values()
+            .as_any()
+            .downcast_ref::<T::Array>()
+            .expect("list elements have the requested type");
+        if values
+            .validity()
+            .is_some_and(|v| v.null_count_range(start, end - start) > 0)
+        {
+            return Some(None);
+        }
+        Some(Some(&values.values()[start..end]))
+    }
+}
+
+/// Builds a list column of numbers from slices, like the chunked array builders of other fields.
+#[doc(hidden)]
+pub struct ListBuilder<T: PolarsNumericType>(ListPrimitiveChunkedBuilder<T>);
+
+impl<T: PolarsNumericType> ListBuilder<T> {
+    /// Create a builder with room for `capacity` lists.
+    pub fn new(name: PlSmallStr, capacity: usize) -> Self {
+        Self(ListPrimitiveChunkedBuilder::new(
+            name,
+            capacity,
+            capacity,
+            T::get_static_dtype(),
+        ))
+    }
+
+    /// Append a list with the given elements.
+    pub fn append_value(&mut self, value: impl AsRef<[T::Native]>) {
+        self.0.append_slice(value.as_ref());
+    }
+
+    /// Append a list with the given elements, or a null list.
+    pub fn append_option(&mut self, value: Option<impl AsRef<[T::Native]>>) {
+        self.0
+            .append_opt_slice(value.as_ref().map(|value| value.as_ref()));
+    }
+
+    /// Append a null list.
+    pub fn append_null(&mut self) {
+        ListBuilderTrait::append_null(&mut self.0);
+    }
+
+    /// Finish building the column.
+    pub fn finish(mut self) -> ListChunked {
+        ListBuilderTrait::finish(&mut self.0)
+    }
+}
+
+/// Convert an optional list of numbers to the dynamic value of a list column.
+#[doc(hidden)]
+pub fn list_any_value<T: PolarsNumericType>(value: Option<&[T::Native]>) -> AnyValue<'static> {
+    match value {
+        Some(values) => {
+            AnyValue::List(ChunkedArray::<T>::from_slice(PlSmallStr::EMPTY, values).into_series())
+        }
+        None => AnyValue::Null,
+    }
+}
@@ -0,0 +1,59 @@
+//! Typed unpivoting of frames from wide to long format.
+
+use crate::{ColbackError, ColumnField, Result};
+use polars::prelude::{Column, DataFrame, PolarsError};
+
+/// Unpivot the value fields of a dataframe into `variable` and `value` columns, keeping the id
+/// fields of each row.
+///
+/// The output has the id columns, then a `variable` column with the name of each value field's
+/// column, then a `value` column. It holds the rows for each value field in turn, in the order
+/// that the fields are given.
+///
+/// # Errors
+///
+/// This errors if there are no value fields, or with [ColbackError::WrongDtype] if the value
+/// fields don't all have the same dtype, since their values share a column.
+pub(crate) fn melt<F: ColumnField>(
+    df: &DataFrame,
+    id_fields: &[F],
+    value_fields: &[F],
+) -> Result<DataFrame> {
+    let Some(first) = value_fields.first() else {
+        return Err(
+            PolarsError::InvalidOperation("melt needs at least one value field".into()).into(),
+        );
+    };
+    let column = |name: &str| {
+        df.column(name)
+            .map_err(|_| ColbackError::MissingColumn(name.to_string()))
+    };
+    let expected = column(first.column_name())?.dtype();
+    for field in value_fields {
+        let name = field.column_name();
+        let actual = column(name)?.dtype();
+        if actual != expected {
+            return Err(ColbackError::WrongDtype {
+                col: name.to_string(),
+                expected: expected.clone(),
+                actual: actual.clone(),
+            });
+        }
+    }
+
+    let ids = df.select(id_fields.iter().map(|field| field.column_name()))?;
+    let mut out: Option<DataFrame> = None;
+    for field in value_fields {
+        let name = field.column_name();
+        let variable = Column::new("variable".into(), vec![name; df.height()]);
+        let value = column(name)?.clone().with_name("value".into());
+        let part = ids.hstack(&[variable, value])?;
+        match &mut out {
+            Some(out) => {
+                out.vstack_mut(&part)?;
+            }
+            None => out = Some(part),
+        }
+    }
+    Ok(out.expect("there is at least one value field"))
+}
@@ -0,0 +1,39 @@
+//! Options for creating views.
+
+use polars::prelude::DataFrame;
+
+/// Options for creating a view with [ColbackView::view_with](crate::ColbackView::view_with).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewOptions {
+    /// Whether to consolidate the chunks of a fragmented dataframe before creating the view.
+    ///
+    /// Random access has to find the chunk that a row lives in, so it slows down as a frame
+    /// collects chunks, e.g. after many `vstack` calls.
+    pub rechunk: bool,
+    /// The number of chunks that a column can have before the dataframe counts as fragmented.
+    ///
+    /// Once any column has more chunks than this, every column with more than one chunk is
+    /// rechunked. This defaults to 1.
+    pub max_chunks: usize,
+}
+
+impl ViewOptions {
+    /// Options that create views over dataframes as they are.
+    pub fn new() -> Self {
+        Self {
+            rechunk: false,
+            max_chunks: 1,
+        }
+    }
+
+    /// Whether a dataframe should be rechunked before it is viewed.
+    pub(crate) fn should_rechunk(&self, df: &DataFrame) -> bool {
+        self.rechunk && df.max_n_chunks() > self.max_chunks
+    }
+}
+
+impl Default for ViewOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,61 @@
+//! Parallel maps over the chunks of views.
+
+use crate::{ColbackView, Result, RowViewOps};
+use polars::prelude::{DataFrame, PolarsError};
+use rayon::prelude::*;
+
+/// Map each chunk of a view's rows to a dataframe in parallel, stacking the outputs in row order.
+///
+/// This is meant for CPU bound work where parallelizing over rows has too much overhead. The
+/// struct's columns are selected from the view and their chunks are aligned, which rechunks them
+/// into a single chunk if their layouts differ. Each chunk is then viewed as `T` and passed to
+/// `f` on rayon's thread pool, so every column of a chunk's view has a single array that can be
+/// read as a slice through its `raw` accessors. The outputs are stacked without rechunking, so
+/// the result has a chunk for each chunk of the input. If none of the struct's columns are in the
+/// dataframe there are no chunks, so the whole dataframe is viewed and passed to `f` instead.
+///
+/// Fields that fill their nulls from other rows (see [ColbackView::filled_columns]) can't be
+/// filled correctly from a single chunk, so they're rejected.
+///
+/// # Errors
+///
+/// This errors if `T` has fields that fill their nulls from other rows, if `f` errors for any
+/// chunk, or if the outputs don't have the same schema.
+pub fn par_map_chunks<'a, T, V, F>(view: &V, f: F) -> Result<DataFrame>
+where
+    T: ColbackView,
+    V: RowViewOps<'a>,
+    F: Fn(T::View<'_>) -> Result<DataFrame> + Sync,
+{
+    if let Some(filled) = T::filled_columns().first() {
+        return Err(PolarsError::InvalidOperation(
+            format!(
+                "column {filled} is filled from other rows, so chunks can't be viewed on their own"
+            )
+            .into(),
+        )
+        .into());
+    }
+    let frame = view.frame();
+    // Columns that aren't in the frame are left out, since a view can be missing optional ones
+    let names = view
+        .column_names()
+        .iter()
+        .copied()
+        .filter(|name| frame.column(name).is_ok());
+    let mut df = frame.select(names)?;
+    let chunks: Vec<_> = df.split_chunks().collect();
+    if chunks.is_empty() {
+        return f(T::view(&frame)?);
+    }
+    let outputs = chunks
+        .par_iter()
+        .map(|chunk| f(T::view(chunk)?))
+        .collect::<Result<Vec<_>>>()?;
+    let mut outputs = outputs.into_iter();
+    let mut stacked = outputs.next().expect("there is an output for every chunk");
+    for output in outputs {
+        stacked.vstack_mut_owned(output)?;
+    }
+    Ok(stacked)
+}
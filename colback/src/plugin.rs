@@ -0,0 +1,33 @@
+//! Row-wise kernels over struct columns, for polars expression plugins.
+
+use crate::{ColbackError, ColbackView, Result, transform::map_rows};
+use polars::prelude::{NamedFrom, Series};
+
+/// Map every row of a struct column to a value, collecting the values into a series.
+///
+/// The struct column's fields are viewed as `In`, so an expression plugin can compute its output
+/// with a typed kernel that takes one row reference at a time. The output series has the same
+/// name and length as the input, and its dtype is inferred from the type returned by `f`. `f`
+/// isn't called for skipped rows, which are null in the output.
+///
+/// # Errors
+///
+/// This errors if `series` isn't a struct column, with [ColbackError::InvalidNull] if one of the
+/// column's rows is null, if the struct's fields can't be viewed as `In` (see
+/// [ColbackView::view]), or if a row can't be materialized (see [RowViewOps::get_unskipped]).
+pub fn map_struct<In, T, F>(series: &Series, mut f: F) -> Result<Series>
+where
+    In: ColbackView + 'static,
+    F: for<'a> FnMut(In::RowRef<'a>) -> T,
+    Series: NamedFrom<Vec<T>, [T]>,
+{
+    if let Some(idx) = series.is_null().iter().position(|null| null == Some(true)) {
+        return Err(ColbackError::InvalidNull {
+            col: series.name().to_string(),
+            idx,
+        });
+    }
+    let df = series.struct_()?.clone().unnest();
+    let view = In::view(&df)?;
+    map_rows(&view, series.name().clone(), &mut f)
+}
@@ -0,0 +1,24 @@
+//! Conversions for Python extensions and polars plugins built with pyo3.
+
+use crate::ColbackError;
+use pyo3::{
+    PyErr,
+    exceptions::{PyIndexError, PyKeyError, PyTypeError, PyValueError},
+};
+
+impl From<ColbackError> for PyErr {
+    /// Convert an error into the closest matching Python exception, keeping its message.
+    fn from(err: ColbackError) -> Self {
+        let msg = err.to_string();
+        match err {
+            ColbackError::MissingColumn(_)
+            | ColbackError::MissingColumns(_)
+            | ColbackError::MissingFrame(_) => PyKeyError::new_err(msg),
+            ColbackError::WrongDtype { .. } | ColbackError::TimezoneMismatch { .. } => {
+                PyTypeError::new_err(msg)
+            }
+            ColbackError::IndexOutOfBounds { .. } => PyIndexError::new_err(msg),
+            _ => PyValueError::new_err(msg),
+        }
+    }
+}
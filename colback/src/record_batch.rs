@@ -0,0 +1,48 @@
+//! Conversion of arrow-rs record batches into dataframes.
+
+use crate::Result;
+use arrow::{
+    array::Array,
+    ffi::{FFI_ArrowArray, FFI_ArrowSchema, to_ffi},
+    record_batch::RecordBatch,
+};
+use polars::prelude::{Column, DataFrame, PlSmallStr, PolarsError, Series};
+use polars_arrow::ffi::{ArrowArray, ArrowSchema};
+use std::{mem::ManuallyDrop, ptr};
+
+// Both crates implement the same C structs, so they must agree on their layout
+const _: () = assert!(size_of::<FFI_ArrowSchema>() == size_of::<ArrowSchema>());
+const _: () = assert!(size_of::<FFI_ArrowArray>() == size_of::<ArrowArray>());
+
+/// Convert an arrow-rs record batch into a dataframe.
+///
+/// The arrays are passed across the Arrow C data interface, so their buffers are shared rather
+/// than copied.
+pub(crate) fn to_dataframe(batch: &RecordBatch) -> Result<DataFrame> {
+    let columns = batch
+        .schema()
+        .fields()
+        .iter()
+        .zip(batch.columns())
+        .map(|(field, array)| {
+            let (ffi_array, ffi_schema) = to_ffi(&array.to_data())
+                .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+            // The structs are moved to polars, so arrow-rs must not release them
+            let ffi_schema = ManuallyDrop::new(ffi_schema);
+            let ffi_array = ManuallyDrop::new(ffi_array);
+            // SAFETY: `FFI_ArrowSchema` and `FFI_ArrowArray` are the `#[repr(C)]` `ArrowSchema`
+            // and `ArrowArray` structs of the Arrow C data interface, as are polars' structs, so
+            // reading one as the other is sound. Each struct is read exactly once, and since
+            // arrow-rs never drops them, polars is the only owner and releases them when dropped.
+            let array = unsafe {
+                let ffi_schema = ptr::read(ptr::from_ref(&*ffi_schema).cast::<ArrowSchema>());
+                let ffi_array = ptr::read(ptr::from_ref(&*ffi_array).cast::<ArrowArray>());
+                let imported = polars_arrow::ffi::import_field_from_c(&ffi_schema)?;
+                polars_arrow::ffi::import_array_from_c(ffi_array, imported.dtype)?
+            };
+            let name = PlSmallStr::from(field.name().as_str());
+            Ok(Column::from(Series::try_from((name, array))?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(DataFrame::new(columns)?)
+}
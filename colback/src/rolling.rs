@@ -0,0 +1,146 @@
+//! Typed rolling window aggregations over views.
+
+use crate::{
+    ColbackError, ColumnField, Result, RowViewOps, temporal::duration_in_unit,
+    transform::spread_rows,
+};
+use chrono::TimeDelta;
+use polars::prelude::{DataFrame, DataType, NamedFrom, PlSmallStr, PolarsError, Series};
+
+/// The extent of the window ending at each row of a rolling aggregation.
+///
+/// Windows only ever look backwards, so the window for a row holds the row itself and some of the
+/// rows before it, in row order. Skipped rows are left out of windows, so they don't count
+/// towards a window's rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RollingWindow<F> {
+    /// The row and up to `n - 1` rows before it.
+    Rows(usize),
+    /// The row and the rows before it whose `index` value is within `period` of the row's value,
+    /// i.e. in `(t - period, t]`.
+    ///
+    /// The index must be a datetime field that is sorted in ascending order.
+    Period { index: F, period: TimeDelta },
+}
+
+impl<F: ColumnField> RollingWindow<F> {
+    /// A window over the row and up to `n - 1` rows before it.
+    pub fn rows(n: usize) -> Self {
+        Self::Rows(n)
+    }
+
+    /// A window over the rows whose datetime `index` field is within `period` of the row's.
+    pub fn period(index: F, period: TimeDelta) -> Self {
+        Self::Period { index, period }
+    }
+
+    /// The position in `rows` of the first row of the window ending at each of `rows`, which are
+    /// the indices of the dataframe's rows that windows hold.
+    fn starts(&self, df: &DataFrame, rows: &[usize]) -> Result<Vec<usize>> {
+        match *self {
+            Self::Rows(0) => Err(PolarsError::InvalidOperation(
+                "a rolling window must have at least one row".into(),
+            )
+            .into()),
+            Self::Rows(n) => Ok((0..rows.len()).map(|i| (i + 1).saturating_sub(n)).collect()),
+            Self::Period { index, period } => period_starts(df, rows, index.column_name(), period),
+        }
+    }
+}
+
+/// The position of the first row of the window ending at each row, for a period based window.
+fn period_starts(
+    df: &DataFrame,
+    rows: &[usize],
+    col: &str,
+    period: TimeDelta,
+) -> Result<Vec<usize>> {
+    let column = df
+        .column(col)
+        .map_err(|_| ColbackError::MissingColumn(col.to_string()))?;
+    let DataType::Datetime(unit, _) = column.dtype() else {
+        return Err(PolarsError::InvalidOperation(
+            format!(
+                "a rolling period requires a datetime index, got {}",
+                column.dtype()
+            )
+            .into(),
+        )
+        .into());
+    };
+    let period = duration_in_unit(period, *unit)
+        .filter(|&p| p > 0)
+        .ok_or_else(|| PolarsError::InvalidOperation("invalid rolling period".into()))?;
+
+    let physical = column.datetime()?.physical();
+    let values = rows
+        .iter()
+        .map(|&idx| {
+            physical.get(idx).ok_or_else(|| ColbackError::InvalidNull {
+                col: col.to_string(),
+                idx,
+            })
+        })
+        .collect::<Result<Vec<i64>>>()?;
+
+    let mut starts = Vec::with_capacity(values.len());
+    let mut start = 0;
+    for (i, &t) in values.iter().enumerate() {
+        if i > 0 && t < values[i - 1] {
+            return Err(PolarsError::InvalidOperation(
+                format!("rolling index {col} must be sorted in ascending order").into(),
+            )
+            .into());
+        }
+        let lower = t.saturating_sub(period);
+        while start < i && values[start] <= lower {
+            start += 1;
+        }
+        starts.push(start);
+    }
+    Ok(starts)
+}
+
+/// Compute a value from the rows of the window ending at every row of a view.
+///
+/// `f` is called once per row with the rows of its window, and the results are collected into a
+/// series that lines up with the view's dataframe. The dtype of the series is inferred from the
+/// type returned by `f`. Every row is materialized up front, so any row errors are returned
+/// before `f` is called.
+///
+/// Skipped rows are left out of every window, and `f` isn't called for them, so they're null in
+/// the series. A period window's index may be null in skipped rows.
+///
+/// # Errors
+///
+/// This errors if a row can't be materialized (see [RowViewOps::get_unskipped]), if the window
+/// has no rows, or if a period window's index isn't a non-null datetime column sorted in
+/// ascending order.
+pub fn rolling<'a, V, C, T, F>(
+    view: &V,
+    name: impl Into<PlSmallStr>,
+    window: RollingWindow<C>,
+    mut f: F,
+) -> Result<Series>
+where
+    V: RowViewOps<'a>,
+    C: ColumnField,
+    F: FnMut(&[V::Row]) -> T,
+    Series: NamedFrom<Vec<T>, [T]>,
+{
+    let mut indices = Vec::with_capacity(view.len());
+    let mut rows = Vec::with_capacity(view.len());
+    for idx in 0..view.len() {
+        if let Some(row) = view.get_unskipped(idx)? {
+            indices.push(idx);
+            rows.push(row);
+        }
+    }
+    let values: Vec<T> = window
+        .starts(&view.frame(), &indices)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, start)| f(&rows[start..=i]))
+        .collect();
+    spread_rows(Series::new(name.into(), values), &indices, view.len())
+}
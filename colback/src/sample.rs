@@ -0,0 +1,140 @@
+//! Deterministic synthetic rows for fixtures, benchmarks, and demos.
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use std::time::Duration;
+
+/// A small seeded random number generator (splitmix64) for sample values.
+///
+/// This is deliberately simple and stable, so the same seed produces the same frames across
+/// platforms and releases.
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct SampleRng(u64);
+
+impl SampleRng {
+    /// Create a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// The next random 64 bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// The next random value in `0..n`, for `n > 0`.
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+/// A field type that can produce a deterministic sample value for a row.
+#[doc(hidden)]
+pub trait SampleValue: Sized {
+    /// The value of the column `col` at row `idx`.
+    fn sample(col: &str, idx: usize, rng: &mut SampleRng) -> Self;
+}
+
+/// Unsigned integers are sequential ids, wrapping around at the type's maximum.
+macro_rules! sample_sequential {
+    ($($ty:ty),*) => {
+        $(impl SampleValue for $ty {
+            fn sample(_: &str, idx: usize, _: &mut SampleRng) -> Self {
+                idx as $ty
+            }
+        })*
+    };
+}
+
+sample_sequential!(u8, u16, u32, u64);
+
+/// Signed integers are seeded values in `-1000..1000`.
+macro_rules! sample_seeded {
+    ($($ty:ty),*) => {
+        $(impl SampleValue for $ty {
+            fn sample(_: &str, _: usize, rng: &mut SampleRng) -> Self {
+                rng.below(2000) as $ty - 1000
+            }
+        })*
+    };
+}
+
+sample_seeded!(i32, i64);
+
+/// Floats are seeded values in `0..1000` with two decimal places.
+macro_rules! sample_float {
+    ($($ty:ty),*) => {
+        $(impl SampleValue for $ty {
+            fn sample(_: &str, _: usize, rng: &mut SampleRng) -> Self {
+                rng.below(100_000) as $ty / 100.0
+            }
+        })*
+    };
+}
+
+sample_float!(f32, f64);
+
+impl SampleValue for bool {
+    fn sample(_: &str, _: usize, rng: &mut SampleRng) -> Self {
+        rng.below(2) == 1
+    }
+}
+
+/// Strings cycle through four categories named after the column.
+impl SampleValue for String {
+    fn sample(col: &str, idx: usize, _: &mut SampleRng) -> Self {
+        format!("{col}_{}", idx % 4)
+    }
+}
+
+/// Datetimes are hourly, starting at midnight on 2024-01-01.
+impl SampleValue for NaiveDateTime {
+    fn sample(_: &str, idx: usize, _: &mut SampleRng) -> Self {
+        let start = DateTime::from_timestamp(1_704_067_200, 0)
+            .expect("the start is in range")
+            .naive_utc();
+        start + TimeDelta::hours(idx as i64)
+    }
+}
+
+impl SampleValue for DateTime<Utc> {
+    fn sample(col: &str, idx: usize, rng: &mut SampleRng) -> Self {
+        NaiveDateTime::sample(col, idx, rng).and_utc()
+    }
+}
+
+/// Durations are whole minutes, counting up from zero.
+impl SampleValue for TimeDelta {
+    fn sample(_: &str, idx: usize, _: &mut SampleRng) -> Self {
+        TimeDelta::minutes(idx as i64)
+    }
+}
+
+/// Durations are whole minutes, counting up from zero.
+impl SampleValue for Duration {
+    fn sample(_: &str, idx: usize, _: &mut SampleRng) -> Self {
+        Duration::from_secs(60 * idx as u64)
+    }
+}
+
+/// Lists have up to three elements, sampled like values of the same column in later rows.
+impl<T: SampleValue> SampleValue for Vec<T> {
+    fn sample(col: &str, idx: usize, rng: &mut SampleRng) -> Self {
+        (0..idx % 4)
+            .map(|i| T::sample(col, idx * 4 + i, rng))
+            .collect()
+    }
+}
+
+/// Optional values are null for about one in five rows.
+impl<T: SampleValue> SampleValue for Option<T> {
+    fn sample(col: &str, idx: usize, rng: &mut SampleRng) -> Self {
+        // Always draw the inner value so that nulls don't shift the other fields' values
+        let value = T::sample(col, idx, rng);
+        (rng.below(5) != 0).then_some(value)
+    }
+}
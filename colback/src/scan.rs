@@ -0,0 +1,88 @@
+//! Lazy scans over custom sources of batches.
+
+use crate::{ColbackView, Result};
+use polars::prelude::{
+    AnonymousScan, AnonymousScanArgs, DataFrame, LazyFrame, PolarsError, PolarsResult,
+    ScanArgsAnonymous, SchemaRef,
+};
+use std::{any::Any, marker::PhantomData, sync::Arc, sync::Mutex};
+
+/// An anonymous scan that pulls batches from a producer, and validates each of them against a
+/// struct.
+struct BatchScan<T, F> {
+    producer: Mutex<F>,
+    _struct: PhantomData<fn() -> T>,
+}
+
+impl<T, F> BatchScan<T, F>
+where
+    T: ColbackView,
+    F: FnMut() -> Result<Option<DataFrame>>,
+{
+    /// Pull the next batch from the producer, projected down to the struct's columns.
+    fn next(&self) -> PolarsResult<Option<DataFrame>> {
+        let mut producer = self
+            .producer
+            .lock()
+            .map_err(|_| PolarsError::ComputeError("a batch producer panicked".into()))?;
+        match producer()? {
+            Some(batch) => Ok(Some(T::select(&batch)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T, F> AnonymousScan for BatchScan<T, F>
+where
+    T: ColbackView + 'static,
+    F: FnMut() -> Result<Option<DataFrame>> + Send + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn scan(&self, _scan_opts: AnonymousScanArgs) -> PolarsResult<DataFrame> {
+        let mut df = DataFrame::empty_with_schema(&T::schema());
+        while let Some(batch) = self.next()? {
+            df.vstack_mut_owned(batch)?;
+        }
+        Ok(df)
+    }
+
+    fn next_batch(&self, _scan_opts: AnonymousScanArgs) -> PolarsResult<Option<DataFrame>> {
+        self.next()
+    }
+
+    fn schema(&self, _infer_schema_length: Option<usize>) -> PolarsResult<SchemaRef> {
+        Ok(Arc::new(T::schema()))
+    }
+}
+
+/// Wrap a producer of batches from a custom source, such as a message queue consumer or a custom
+/// file format, as a lazy scan with a struct's schema.
+///
+/// `producer` is called for batches until it returns `None`. Each batch is validated against the
+/// struct and projected down to its columns, so the scan can be queried lazily like any other
+/// frame, and its results can be viewed as the struct. The producer is only drained once, so
+/// collecting the scan a second time yields no rows.
+///
+/// # Errors
+///
+/// This only errors if the scan can't be planned. Collecting the scan errors if the producer
+/// errors, or if a batch can't be viewed as the struct (see [ColbackView::view]).
+pub fn scan_batches<T, F>(producer: F) -> Result<LazyFrame>
+where
+    T: ColbackView + 'static,
+    F: FnMut() -> Result<Option<DataFrame>> + Send + 'static,
+{
+    let scan = BatchScan::<T, F> {
+        producer: Mutex::new(producer),
+        _struct: PhantomData,
+    };
+    let args = ScanArgsAnonymous {
+        schema: Some(Arc::new(T::schema())),
+        name: "colback_batches",
+        ..Default::default()
+    };
+    Ok(LazyFrame::anonymous_scan(Arc::new(scan), args)?)
+}
@@ -0,0 +1,142 @@
+//! Export of struct schemas for components outside of Rust, and comparison of struct schemas
+//! with dataframes.
+
+use polars::prelude::{ArrowSchema, CompatLevel, DataFrame, DataType, Schema};
+use std::fmt::Write;
+
+/// A column whose dtype differs between a struct and a dataframe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DtypeMismatch {
+    /// The name of the column.
+    pub col: String,
+    /// The dtype that the struct expects.
+    pub expected: DataType,
+    /// The dtype of the column in the dataframe.
+    pub actual: DataType,
+}
+
+/// A column with null values whose field doesn't allow nulls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NullConflict {
+    /// The name of the column.
+    pub col: String,
+    /// The number of null values in the column.
+    pub null_count: usize,
+}
+
+/// The differences between the columns of a struct and a dataframe.
+///
+/// Unlike the error from [ColbackView::view](crate::ColbackView::view), this lists every
+/// difference at once, so tooling can suggest migrations or generate casts.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SchemaDiff {
+    /// The struct's columns that aren't in the dataframe, in field order.
+    pub missing: Vec<String>,
+    /// The dataframe's columns that the struct doesn't declare, in dataframe order.
+    pub extra: Vec<String>,
+    /// The columns whose dtypes differ, in field order.
+    pub dtype_mismatches: Vec<DtypeMismatch>,
+    /// The columns with nulls whose fields use the "error" null handling policy, in field order.
+    pub null_conflicts: Vec<NullConflict>,
+}
+
+impl SchemaDiff {
+    /// Whether the dataframe has exactly the struct's columns, dtypes, and allowed nulls.
+    ///
+    /// Extra columns don't prevent a dataframe from being viewed, see
+    /// [SchemaDiff::is_compatible].
+    pub fn is_empty(&self) -> bool {
+        self.extra.is_empty() && self.is_compatible()
+    }
+
+    /// Whether the dataframe can be viewed as the struct, i.e. every difference is an extra
+    /// column.
+    pub fn is_compatible(&self) -> bool {
+        self.missing.is_empty()
+            && self.dtype_mismatches.is_empty()
+            && self.null_conflicts.is_empty()
+    }
+}
+
+/// Compare the columns of a struct with those of a dataframe.
+pub(crate) fn diff(schema: &Schema, nullable: &[&str], df: &DataFrame) -> SchemaDiff {
+    let mut diff = SchemaDiff {
+        extra: df
+            .get_column_names()
+            .into_iter()
+            .filter(|name| !schema.contains(name))
+            .map(|name| name.to_string())
+            .collect(),
+        ..Default::default()
+    };
+    for (name, expected) in schema.iter() {
+        let Ok(column) = df.column(name) else {
+            diff.missing.push(name.to_string());
+            continue;
+        };
+        if column.dtype() != expected {
+            diff.dtype_mismatches.push(DtypeMismatch {
+                col: name.to_string(),
+                expected: expected.clone(),
+                actual: column.dtype().clone(),
+            });
+        }
+        let null_count = column.null_count();
+        if null_count > 0 && !nullable.contains(&name.as_str()) {
+            diff.null_conflicts.push(NullConflict {
+                col: name.to_string(),
+                null_count,
+            });
+        }
+    }
+    diff
+}
+
+/// Convert a schema into an arrow schema, marking only the given columns as nullable.
+pub(crate) fn arrow_schema(schema: &Schema, nullable: &[&str]) -> ArrowSchema {
+    schema
+        .iter()
+        .map(|(name, dtype)| {
+            let mut field = dtype.to_arrow_field(name.clone(), CompatLevel::newest());
+            field.is_nullable = nullable.contains(&name.as_str());
+            (name.clone(), field)
+        })
+        .collect()
+}
+
+/// Serialize a schema as JSON, with the name, dtype, and nullability of every column.
+pub(crate) fn to_json(schema: &Schema, nullable: &[&str]) -> String {
+    let columns = schema
+        .iter()
+        .map(|(name, dtype)| {
+            format!(
+                "{{\"name\":{},\"dtype\":{},\"nullable\":{}}}",
+                json_string(name),
+                json_string(&dtype.to_string()),
+                nullable.contains(&name.as_str()),
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("{{\"columns\":[{}]}}", columns.join(","))
+}
+
+/// Quote and escape a string as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,57 @@
+//! Typed sorting by struct fields.
+
+use crate::ColumnField;
+use polars::prelude::SortMultipleOptions;
+
+/// A field to sort by, along with its sort direction and null ordering.
+///
+/// The field is one of the variants of the enum generated for a struct deriving
+/// [ColbackView](crate::ColbackView), so sorts can't reference columns that the struct doesn't
+/// declare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SortField<F> {
+    /// The field to sort by.
+    pub field: F,
+    /// Whether to sort in descending order.
+    pub descending: bool,
+    /// Whether null values should be placed after all other values.
+    pub nulls_last: bool,
+}
+
+impl<F: ColumnField> SortField<F> {
+    /// Sort by a field in ascending order, with nulls first.
+    pub fn asc(field: F) -> Self {
+        Self {
+            field,
+            descending: false,
+            nulls_last: false,
+        }
+    }
+
+    /// Sort by a field in descending order, with nulls first.
+    pub fn desc(field: F) -> Self {
+        Self {
+            field,
+            descending: true,
+            nulls_last: false,
+        }
+    }
+
+    /// Place null values after all other values.
+    pub fn nulls_last(mut self) -> Self {
+        self.nulls_last = true;
+        self
+    }
+}
+
+/// Get the column names and polars sort options for a list of sort fields.
+pub(crate) fn sort_args<F: ColumnField>(
+    by: &[SortField<F>],
+) -> (Vec<&'static str>, SortMultipleOptions) {
+    let names = by.iter().map(|s| s.field.column_name()).collect();
+    let options = SortMultipleOptions::default()
+        .with_order_descending_multi(by.iter().map(|s| s.descending))
+        .with_nulls_last_multi(by.iter().map(|s| s.nulls_last))
+        .with_maintain_order(true);
+    (names, options)
+}
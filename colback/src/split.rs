@@ -0,0 +1,221 @@
+//! Reproducible train/test and k-fold splits and random samples of views.
+
+use crate::{
+    ColbackError, ColumnField, Result, RowViewOps, groups::group_indices, sample::SampleRng,
+};
+use polars::prelude::{DataFrame, DataType, IdxCa, IdxSize, PolarsError};
+
+/// Split the rows of a view into a training and a test dataframe.
+///
+/// Roughly `frac` of the rows go into the training frame and the rest into the test frame, chosen
+/// at random with `seed` so the same seed always produces the same split. When `stratify` is
+/// given, the rows of each value of that field are split separately, so both frames have about
+/// the same share of every value. Both frames keep the rows in their original order and have the
+/// same columns as the view's dataframe, so they can be viewed as the same struct.
+///
+/// # Errors
+///
+/// This errors if `frac` isn't in `0..=1`, or if the dataframe doesn't have the `stratify` column.
+pub fn split<'a, V, C>(
+    view: &V,
+    frac: f64,
+    seed: u64,
+    stratify: Option<C>,
+) -> Result<(DataFrame, DataFrame)>
+where
+    V: RowViewOps<'a>,
+    C: ColumnField,
+{
+    if !(0.0..=1.0).contains(&frac) {
+        return Err(PolarsError::InvalidOperation(
+            format!("a split fraction must be between 0 and 1, got {frac}").into(),
+        )
+        .into());
+    }
+    let df = view.frame();
+    let mut rng = SampleRng::new(seed);
+    let (mut train, mut test) = (Vec::new(), Vec::new());
+    for mut rows in strata(&df, stratify)? {
+        shuffle(&mut rows, &mut rng);
+        let n_train = (rows.len() as f64 * frac).round() as usize;
+        test.extend_from_slice(&rows[n_train..]);
+        rows.truncate(n_train);
+        train.append(&mut rows);
+    }
+    Ok((take_sorted(&df, train)?, take_sorted(&df, test)?))
+}
+
+/// Split the rows of a view into `k` folds for cross validation.
+///
+/// Rows are assigned to folds at random with `seed`, and the folds differ in size by at most one
+/// row. Each element of the result is the training and test dataframe of a fold, where the test
+/// frame holds the fold's rows and the training frame holds the rest. Stratification and the
+/// frames are as in [split].
+///
+/// # Errors
+///
+/// This errors if `k` is less than 2, or if the dataframe doesn't have the `stratify` column.
+pub fn kfold<'a, V, C>(
+    view: &V,
+    k: usize,
+    seed: u64,
+    stratify: Option<C>,
+) -> Result<Vec<(DataFrame, DataFrame)>>
+where
+    V: RowViewOps<'a>,
+    C: ColumnField,
+{
+    if k < 2 {
+        return Err(PolarsError::InvalidOperation(
+            format!("k-fold splits need at least 2 folds, got {k}").into(),
+        )
+        .into());
+    }
+    let df = view.frame();
+    let mut rng = SampleRng::new(seed);
+    let mut folds = vec![Vec::new(); k];
+    // Folds are dealt in turn across strata, so their sizes stay balanced
+    let mut next = 0;
+    for mut rows in strata(&df, stratify)? {
+        shuffle(&mut rows, &mut rng);
+        for row in rows {
+            folds[next].push(row);
+            next = (next + 1) % k;
+        }
+    }
+    (0..k)
+        .map(|fold| {
+            let train = folds
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != fold)
+                .flat_map(|(_, rows)| rows.iter().copied())
+                .collect();
+            Ok((
+                take_sorted(&df, train)?,
+                take_sorted(&df, folds[fold].clone())?,
+            ))
+        })
+        .collect()
+}
+
+/// Sample up to `n_per_group` rows for each value of a field of a view.
+///
+/// The rows are chosen at random with `seed`, and groups with fewer rows are taken whole. The
+/// sample keeps the rows in their original order and has the same columns as the view's
+/// dataframe.
+///
+/// # Errors
+///
+/// This errors if the dataframe doesn't have the `by` column.
+pub fn sample_stratified<'a, V, C>(
+    view: &V,
+    by: C,
+    n_per_group: usize,
+    seed: u64,
+) -> Result<DataFrame>
+where
+    V: RowViewOps<'a>,
+    C: ColumnField,
+{
+    let df = view.frame();
+    let mut rng = SampleRng::new(seed);
+    let mut sample = Vec::new();
+    for mut rows in strata(&df, Some(by))? {
+        shuffle(&mut rows, &mut rng);
+        sample.extend(rows.into_iter().take(n_per_group));
+    }
+    take_sorted(&df, sample)
+}
+
+/// Sample up to `n` rows of a view without replacement, with chances proportional to a numeric
+/// field.
+///
+/// The rows are chosen at random with `seed`. Rows with a zero or null weight are never sampled,
+/// so the sample only has fewer than `n` rows if there aren't enough rows with a positive weight.
+/// The sample keeps the rows in their original order and has the same columns as the view's
+/// dataframe.
+///
+/// # Errors
+///
+/// This errors if the dataframe doesn't have the `weight` column, if it isn't numeric, or if it
+/// has negative or NaN weights.
+pub fn sample_weighted<'a, V, C>(view: &V, weight: C, n: usize, seed: u64) -> Result<DataFrame>
+where
+    V: RowViewOps<'a>,
+    C: ColumnField,
+{
+    let df = view.frame();
+    let name = weight.column_name();
+    let column = df
+        .column(name)
+        .map_err(|_| ColbackError::MissingColumn(name.to_string()))?;
+    if !column.dtype().is_primitive_numeric() {
+        return Err(PolarsError::InvalidOperation(
+            format!("sample weights must be numeric, got {}", column.dtype()).into(),
+        )
+        .into());
+    }
+    let weights = column.cast(&DataType::Float64)?;
+    let mut rng = SampleRng::new(seed);
+    // Each row gets the key ln(u) / w for a uniform u, and the rows with the largest keys are a
+    // weighted sample (Efraimidis and Spirakis)
+    let mut keyed = Vec::new();
+    for (idx, w) in weights.f64()?.iter().enumerate() {
+        let key = match w {
+            Some(w) if w.is_nan() || w < 0.0 => {
+                return Err(PolarsError::InvalidOperation(
+                    format!("sample weights must not be negative or NaN, got {w} in {name}").into(),
+                )
+                .into());
+            }
+            Some(w) if w > 0.0 => uniform(&mut rng).ln() / w,
+            _ => continue,
+        };
+        keyed.push((key, idx as IdxSize));
+    }
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    take_sorted(&df, keyed.into_iter().take(n).map(|(_, idx)| idx).collect())
+}
+
+/// A uniform random value in `0..1`.
+fn uniform(rng: &mut SampleRng) -> f64 {
+    (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// The row indices of each stratum of a dataframe, which is all rows if there's no `stratify`
+/// column.
+fn strata<C: ColumnField>(df: &DataFrame, stratify: Option<C>) -> Result<Vec<Vec<IdxSize>>> {
+    let Some(field) = stratify else {
+        return Ok(vec![(0..df.height() as IdxSize).collect()]);
+    };
+    let name = field.column_name();
+    if df.column(name).is_err() {
+        return Err(ColbackError::MissingColumn(name.to_string()));
+    }
+    Ok(df
+        .group_by_stable([name])?
+        .get_groups()
+        .iter()
+        .map(|group| {
+            group_indices(group)
+                .into_iter()
+                .map(|idx| idx as IdxSize)
+                .collect()
+        })
+        .collect())
+}
+
+/// Shuffle row indices in place with a Fisher-Yates shuffle.
+fn shuffle(rows: &mut [IdxSize], rng: &mut SampleRng) {
+    for i in (1..rows.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        rows.swap(i, j);
+    }
+}
+
+/// The rows of a dataframe at the given indices, in their original order.
+fn take_sorted(df: &DataFrame, mut rows: Vec<IdxSize>) -> Result<DataFrame> {
+    rows.sort_unstable();
+    Ok(df.take(&IdxCa::from_vec("".into(), rows))?)
+}
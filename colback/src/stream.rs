@@ -0,0 +1,61 @@
+//! Typed batches of lazy query results for async services.
+
+use crate::{ColbackError, ColbackView, Result};
+use futures::{Stream, StreamExt, stream};
+use polars::prelude::{DataFrame, LazyFrame, PlanCallback, PolarsError, PolarsResult, SpecialEq};
+use std::{num::NonZeroUsize, sync::Arc};
+use tokio::sync::mpsc;
+
+/// Run a lazy query for a struct on the streaming engine from a blocking thread, and stream its
+/// result in batches as the engine produces them.
+///
+/// Batches are sent through a channel with room for a single batch, so the query is paused until
+/// the stream is polled, and stops once the stream is dropped.
+pub(crate) fn stream_batches<T: ColbackView + 'static>(
+    lf: LazyFrame,
+    batch_size: usize,
+) -> impl Stream<Item = Result<DataFrame>> + Send + 'static {
+    stream::once(async move {
+        let Some(batch_size) = NonZeroUsize::new(batch_size) else {
+            let error = PolarsError::InvalidOperation("a batch must have at least one row".into());
+            return stream::iter([Err(error.into())]).left_stream();
+        };
+        let (tx, rx) = mpsc::channel(1);
+        let batches = tx.clone();
+        // Each batch is validated as in `collect_view`, and the query stops at the first invalid
+        // batch or once the receiver is dropped
+        let send = move |df: DataFrame| -> PolarsResult<bool> {
+            let valid = T::view(&df).map(|_| ());
+            let batch = valid.map(|()| df);
+            let failed = batch.is_err();
+            Ok(batches.blocking_send(batch).is_err() || failed)
+        };
+        let task = tokio::task::spawn_blocking(move || {
+            let result = lf
+                .select(T::projection())
+                .sink_batches(
+                    PlanCallback::Rust(SpecialEq::new(Arc::new(send))),
+                    true,
+                    Some(batch_size),
+                )
+                .and_then(|lf| lf.collect());
+            if let Err(e) = result {
+                let _ = tx.blocking_send(Err(e.into()));
+            }
+        });
+        let batches = stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|batch| (batch, rx))
+        });
+        // A panic in the blocking task drops its sender, which is reported after the last batch
+        let panicked = stream::once(async move {
+            task.await.err().map(|e| {
+                ColbackError::from(PolarsError::ComputeError(
+                    format!("collecting failed: {e}").into(),
+                ))
+            })
+        })
+        .filter_map(|error| async move { error.map(Err) });
+        batches.chain(panicked).right_stream()
+    })
+    .flatten()
+}
@@ -0,0 +1,38 @@
+//! Counters and histograms of view construction and iteration, recorded with the `metrics` facade.
+//!
+//! Nothing is recorded unless the application installs a `metrics` recorder.
+
+use polars::prelude::DataFrame;
+use std::time::Duration;
+
+/// Record the construction of a view of a struct over a dataframe.
+///
+/// Successful views count the rows viewed and the nulls in each of the struct's columns, and
+/// failed views count as a schema failure of the struct. The time taken is recorded either way.
+pub fn record_view(
+    name: &'static str,
+    df: &DataFrame,
+    columns: &'static [&'static str],
+    ok: bool,
+    elapsed: Duration,
+) {
+    metrics::histogram!("colback_view_duration_seconds", "struct" => name)
+        .record(elapsed.as_secs_f64());
+    if !ok {
+        metrics::counter!("colback_schema_failures_total", "struct" => name).increment(1);
+        return;
+    }
+    metrics::counter!("colback_rows_viewed_total", "struct" => name).increment(df.height() as u64);
+    for &column in columns {
+        // Columns that the struct doesn't require may be missing from a valid view
+        if let Ok(values) = df.column(column) {
+            metrics::counter!("colback_null_values_total", "struct" => name, "column" => column)
+                .increment(values.null_count() as u64);
+        }
+    }
+}
+
+/// Record a row that iteration left out because of a null in `column`.
+pub(crate) fn record_skipped_row(column: &str) {
+    metrics::counter!("colback_rows_skipped_total", "column" => column.to_string()).increment(1);
+}
@@ -0,0 +1,231 @@
+//! Conversions for temporal columns.
+
+use crate::{ColbackError, Result};
+use chrono::{DateTime, NaiveDateTime, TimeDelta, TimeZone as _, Utc};
+use chrono_tz::Tz;
+use polars::prelude::{AnyValue, DataType, TimeUnit, TimeZone};
+use std::{sync::Arc, time::Duration};
+
+/// Convert the physical value of a datetime column to a naive datetime.
+///
+/// Returns `None` if the value is out of the range that chrono can represent.
+pub fn naive_datetime(value: i64, unit: TimeUnit) -> Option<NaiveDateTime> {
+    let datetime = match unit {
+        TimeUnit::Nanoseconds => Some(DateTime::from_timestamp_nanos(value)),
+        TimeUnit::Microseconds => DateTime::from_timestamp_micros(value),
+        TimeUnit::Milliseconds => DateTime::from_timestamp_millis(value),
+    };
+    datetime.map(|d| d.naive_utc())
+}
+
+/// Convert a duration to a number of time units.
+///
+/// Returns `None` if the duration overflows when expressed in the given unit.
+pub(crate) fn duration_in_unit(duration: TimeDelta, unit: TimeUnit) -> Option<i64> {
+    match unit {
+        TimeUnit::Nanoseconds => duration.num_nanoseconds(),
+        TimeUnit::Microseconds => duration.num_microseconds(),
+        TimeUnit::Milliseconds => Some(duration.num_milliseconds()),
+    }
+}
+
+/// Convert a naive datetime to the physical value of a datetime column.
+///
+/// Returns `None` if the datetime overflows when expressed in the given unit.
+pub fn datetime_in_unit(value: NaiveDateTime, unit: TimeUnit) -> Option<i64> {
+    duration_in_unit(value - DateTime::UNIX_EPOCH.naive_utc(), unit)
+}
+
+/// Convert an optional naive datetime to the dynamic value of a datetime column.
+///
+/// Returns a null value if the datetime is `None` or overflows when expressed in the given unit.
+#[doc(hidden)]
+pub fn datetime_any_value(
+    value: Option<NaiveDateTime>,
+    unit: TimeUnit,
+    time_zone: Option<TimeZone>,
+) -> AnyValue<'static> {
+    let Some(v) = value.and_then(|v| datetime_in_unit(v, unit)) else {
+        return AnyValue::Null;
+    };
+    match time_zone {
+        Some(tz) => AnyValue::DatetimeOwned(v, unit, Some(Arc::new(tz))),
+        None => AnyValue::Datetime(v, unit, None),
+    }
+}
+
+/// The time zone of a `time_zone` field attribute.
+///
+/// # Panics
+///
+/// This panics if polars doesn't recognize the time zone, which the derive macros rule out when
+/// they're expanded.
+#[doc(hidden)]
+pub fn time_zone(tz: &str) -> TimeZone {
+    match TimeZone::opt_try_new(Some(tz)) {
+        Ok(Some(tz)) => tz,
+        _ => panic!("invalid time zone in #[polars(time_zone = {tz:?})]"),
+    }
+}
+
+/// How the values of a datetime column are converted to the time zone of a field.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeZoneConversion {
+    /// The values are read as they are.
+    None,
+    /// The column holds local times in a time zone, which are converted to UTC.
+    FromLocal(Tz),
+    /// The column holds UTC times, which are converted to local times in a time zone.
+    ToLocal(Tz),
+}
+
+impl TimeZoneConversion {
+    /// Convert a value of the column to the field's time zone.
+    ///
+    /// Returns `None` if the value is a local time that doesn't exist or is ambiguous in the
+    /// column's time zone, which happens around daylight saving time transitions.
+    pub fn apply(self, value: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self {
+            Self::None => Some(value),
+            Self::FromLocal(tz) => tz
+                .from_local_datetime(&value)
+                .single()
+                .map(|d| d.naive_utc()),
+            Self::ToLocal(tz) => Some(tz.from_utc_datetime(&value).naive_local()),
+        }
+    }
+
+    /// Convert the physical value of the column to the physical value of the field's column.
+    ///
+    /// Returns `None` if the value can't be converted, see [TimeZoneConversion::apply], or if
+    /// the converted value overflows.
+    pub fn apply_physical(self, value: i64, unit: TimeUnit) -> Option<i64> {
+        match self {
+            Self::None => Some(value),
+            _ => datetime_in_unit(self.apply(naive_datetime(value, unit)?)?, unit),
+        }
+    }
+}
+
+/// Check the time zone of a datetime column against the time zone that a field expects.
+///
+/// Returns how the column's values are converted to the field's time zone, or `None` if the
+/// column has another dtype than the field expects. Columns with the expected dtype are read as
+/// they are. Otherwise, a datetime column with the expected unit is accepted if `convert` is set:
+/// time zone aware columns hold UTC timestamps, so they're read as they are by fields with a
+/// time zone, and converted to local times for fields without one. Naive columns are read as
+/// local times in the field's time zone, which are converted to UTC.
+///
+/// # Errors
+///
+/// This returns [ColbackError::TimezoneMismatch] if the column only differs by its time zone and
+/// `convert` isn't set.
+#[doc(hidden)]
+pub fn check_time_zone(
+    col: &str,
+    actual: &DataType,
+    expected: &DataType,
+    convert: bool,
+) -> Result<Option<TimeZoneConversion>> {
+    if actual == expected {
+        return Ok(Some(TimeZoneConversion::None));
+    }
+    let (DataType::Datetime(unit, tz), DataType::Datetime(expected_unit, expected_tz)) =
+        (actual, expected)
+    else {
+        return Ok(None);
+    };
+    if unit != expected_unit {
+        return Ok(None);
+    }
+    let conversion = match (tz, expected_tz) {
+        (None, Some(tz)) => tz.to_chrono().ok().map(TimeZoneConversion::FromLocal),
+        (Some(tz), None) => tz.to_chrono().ok().map(TimeZoneConversion::ToLocal),
+        _ => Some(TimeZoneConversion::None),
+    };
+    match conversion {
+        Some(conversion) if convert => Ok(Some(conversion)),
+        _ => Err(ColbackError::TimezoneMismatch {
+            col: col.to_string(),
+            expected: expected_tz.as_ref().map(|tz| tz.to_string()),
+            actual: tz.as_ref().map(|tz| tz.to_string()),
+        }),
+    }
+}
+
+/// A field type that `epoch` fields read from integer columns.
+///
+/// Datetimes are counted in time units since the Unix epoch, and durations in time units.
+pub trait EpochValue: Sized {
+    /// Convert a number of time units to a value.
+    ///
+    /// Returns `None` if the value is out of the range of the type.
+    fn from_epoch(value: i64, unit: TimeUnit) -> Option<Self>;
+
+    /// Convert a value to a number of time units, truncating anything smaller than the unit.
+    ///
+    /// Returns `None` if the value overflows when expressed in the given unit.
+    fn to_epoch(self, unit: TimeUnit) -> Option<i64>;
+}
+
+impl EpochValue for DateTime<Utc> {
+    fn from_epoch(value: i64, unit: TimeUnit) -> Option<Self> {
+        naive_datetime(value, unit).map(|d| d.and_utc())
+    }
+
+    fn to_epoch(self, unit: TimeUnit) -> Option<i64> {
+        datetime_in_unit(self.naive_utc(), unit)
+    }
+}
+
+impl EpochValue for TimeDelta {
+    fn from_epoch(value: i64, unit: TimeUnit) -> Option<Self> {
+        match unit {
+            TimeUnit::Nanoseconds => Some(TimeDelta::nanoseconds(value)),
+            TimeUnit::Microseconds => Some(TimeDelta::microseconds(value)),
+            TimeUnit::Milliseconds => TimeDelta::try_milliseconds(value),
+        }
+    }
+
+    fn to_epoch(self, unit: TimeUnit) -> Option<i64> {
+        duration_in_unit(self, unit)
+    }
+}
+
+/// Standard durations can't be negative, so negative values are out of range.
+impl EpochValue for Duration {
+    fn from_epoch(value: i64, unit: TimeUnit) -> Option<Self> {
+        let value = u64::try_from(value).ok()?;
+        Some(match unit {
+            TimeUnit::Nanoseconds => Duration::from_nanos(value),
+            TimeUnit::Microseconds => Duration::from_micros(value),
+            TimeUnit::Milliseconds => Duration::from_millis(value),
+        })
+    }
+
+    fn to_epoch(self, unit: TimeUnit) -> Option<i64> {
+        let value = match unit {
+            TimeUnit::Nanoseconds => self.as_nanos(),
+            TimeUnit::Microseconds => self.as_micros(),
+            TimeUnit::Milliseconds => self.as_millis(),
+        };
+        i64::try_from(value).ok()
+    }
+}
+
+/// Convert the value of an `epoch` column to the field's type.
+///
+/// Returns `None` if the value is out of the range of the type.
+#[doc(hidden)]
+pub fn from_epoch<T: EpochValue, V: TryInto<i64>>(value: V, unit: TimeUnit) -> Option<T> {
+    T::from_epoch(value.try_into().ok()?, unit)
+}
+
+/// Convert a field value to the value of an `epoch` column.
+///
+/// Returns `None` if the value doesn't fit in the column's type.
+#[doc(hidden)]
+pub fn to_epoch<T: EpochValue, V: TryFrom<i64>>(value: T, unit: TimeUnit) -> Option<V> {
+    value.to_epoch(unit)?.try_into().ok()
+}
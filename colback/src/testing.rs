@@ -0,0 +1,121 @@
+//! Assertions for typed frames in tests, which report mismatches field by field.
+
+use crate::{ColbackOwned, RowViewOps};
+use polars::prelude::DataFrame;
+use std::fmt::{Debug, Write};
+
+/// A field whose value differs between two rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    /// The column name of the field.
+    pub field: &'static str,
+    /// The debug representation of the expected value.
+    pub expected: String,
+    /// The debug representation of the actual value.
+    pub actual: String,
+}
+
+/// Field by field comparison of owned rows.
+///
+/// This is implemented for the owned row structs generated by the `ColbackOwned` derive macro.
+pub trait FieldDiffs: Debug {
+    /// The fields whose values differ between an expected row and an actual row, in field order.
+    fn field_diffs(&self, actual: &Self) -> Vec<FieldDiff>;
+}
+
+/// Describe the differences between two sequences of rows, or `None` if they're equal.
+fn describe_diffs<R: FieldDiffs>(actual: &[R], expected: &[R]) -> Option<String> {
+    let mut report = String::new();
+    for (idx, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+        for diff in expected.field_diffs(actual) {
+            let _ = writeln!(
+                report,
+                "  row {idx}, field `{}`: expected {}, actual {}",
+                diff.field, diff.expected, diff.actual
+            );
+        }
+    }
+    if actual.len() != expected.len() {
+        let _ = writeln!(
+            report,
+            "  expected {} rows, actual {} rows",
+            expected.len(),
+            actual.len()
+        );
+        for (idx, row) in expected.iter().enumerate().skip(actual.len()) {
+            let _ = writeln!(report, "  row {idx} is missing: {row:?}");
+        }
+        for (idx, row) in actual.iter().enumerate().skip(expected.len()) {
+            let _ = writeln!(report, "  row {idx} is unexpected: {row:?}");
+        }
+    }
+    (!report.is_empty()).then_some(report)
+}
+
+/// Assert that rows equal the expected rows, panicking with the differing fields otherwise.
+///
+/// This backs [assert_rows_eq](crate::assert_rows_eq).
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_rows<R: FieldDiffs>(actual: &[R], expected: &[R]) {
+    if let Some(report) = describe_diffs(actual, expected) {
+        panic!("rows differ:\n{report}");
+    }
+}
+
+/// Assert that a dataframe can be viewed as a struct and that its rows equal `expected`.
+///
+/// On failure this panics with the view error, or with every field that differs between the
+/// expected and actual rows (the row index, column name, and both values) instead of the debug
+/// output of whole frames. Skipped rows are left out of the actual rows, as in
+/// [RowViewOps::iter].
+#[track_caller]
+pub fn assert_df_matches<T: ColbackOwned>(df: &DataFrame, expected: &[T::Owned]) {
+    let view = match T::view(df) {
+        Ok(view) => view,
+        Err(e) => panic!("dataframe can't be viewed as the struct: {e}"),
+    };
+    let actual = match RowViewOps::iter(&view)
+        .map(|row| row.map(|row| T::to_owned_row(&row)))
+        .collect::<crate::Result<Vec<_>>>()
+    {
+        Ok(rows) => rows,
+        Err(e) => panic!("dataframe rows can't be materialized: {e}"),
+    };
+    assert_rows(&actual, expected);
+}
+
+/// Assert that the rows of a view equal a list of owned rows.
+///
+/// The view's struct must derive `ColbackOwned`. On failure this panics with every field that
+/// differs between the expected and actual rows, along with any missing or unexpected rows.
+///
+/// ```rust
+/// use colback::{ColbackOwned, ColbackView, assert_rows_eq};
+/// use polars::prelude::*;
+///
+/// #[derive(ColbackView, ColbackOwned)]
+/// struct MyRow {
+///     col_a: u32,
+///     col_b: String,
+/// }
+///
+/// let df = df!["col_a" => [1u32, 2], "col_b" => ["x", "y"]].unwrap();
+/// let view = MyRow::view(&df).unwrap();
+/// assert_rows_eq!(
+///     view,
+///     [
+///         MyRowOwned { col_a: 1, col_b: "x".to_string() },
+///         MyRowOwned { col_a: 2, col_b: "y".to_string() },
+///     ]
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_rows_eq {
+    ($view:expr, $expected:expr $(,)?) => {
+        $crate::assert_rows(
+            &$view.to_vec().expect("the view's rows can be materialized"),
+            &$expected[..],
+        )
+    };
+}
@@ -0,0 +1,130 @@
+//! Typed row-to-row transformations between frames.
+
+use crate::{ColbackError, ColbackView, ColumnField, Result, RowViewOps};
+use polars::{
+    frame::DataFrame,
+    prelude::{BooleanChunked, DataType, IdxCa, IdxSize, NamedFrom, PlSmallStr, Series},
+};
+
+/// Compute a value from every row of a view, collecting the values into a series that lines up
+/// with the view's rows.
+///
+/// `f` isn't called for skipped rows, which are null in the series.
+///
+/// This backs `map_to_series` and `with_column` of generated views.
+#[doc(hidden)]
+pub fn map_rows<'a, V, T, F>(view: &V, name: PlSmallStr, mut f: F) -> Result<Series>
+where
+    V: RowViewOps<'a>,
+    F: FnMut(V::Row) -> T,
+    Series: NamedFrom<Vec<T>, [T]>,
+{
+    let mut indices = Vec::with_capacity(view.len());
+    let mut values = Vec::with_capacity(view.len());
+    for idx in 0..view.len() {
+        if let Some(row) = view.get_unskipped(idx)? {
+            indices.push(idx);
+            values.push(f(row));
+        }
+    }
+    spread_rows(Series::new(name, values), &indices, view.len())
+}
+
+/// Spread the values of some rows of a view out to a series of all `len` of its rows, with nulls
+/// for the other rows. `indices` are the ascending indices of the rows of the values.
+pub(crate) fn spread_rows(series: Series, indices: &[usize], len: usize) -> Result<Series> {
+    if indices.len() == len {
+        return Ok(series);
+    }
+    let mut positions = vec![None; len];
+    for (pos, &idx) in indices.iter().enumerate() {
+        positions[idx] = Some(pos as IdxSize);
+    }
+    // Null indices take null values
+    Ok(series.take(&positions.into_iter().collect::<IdxCa>())?)
+}
+
+/// Map every row of a dataframe to an owned output row, collecting the outputs into a new
+/// dataframe.
+///
+/// The input is viewed as `In` and each row reference is passed to `f`. The returned rows are
+/// collected column by column using [ColbackView::to_dataframe], so the output frame has the
+/// dtypes that `Out` expects.
+///
+/// Skipped rows of the input don't have an output row, as in [RowViewOps::iter].
+///
+/// # Errors
+///
+/// This errors if the input can't be viewed as `In` or if one of its rows can't be materialized.
+/// `f` isn't called for any rows after the first row that fails.
+pub fn transform<'a, In, Out, F>(df: &'a DataFrame, mut f: F) -> Result<DataFrame>
+where
+    In: ColbackView + 'a,
+    Out: ColbackView,
+    F: FnMut(In::RowRef<'a>) -> Out,
+{
+    let view = In::view(df)?;
+    let mut error = None;
+    let rows = RowViewOps::iter(&view).map_while(|row| match row {
+        Ok(row) => Some(f(row)),
+        Err(e) => {
+            error = Some(e);
+            None
+        }
+    });
+    let out = Out::to_dataframe(rows)?;
+    match error {
+        Some(e) => Err(e),
+        None => Ok(out),
+    }
+}
+
+/// Recompute one of a struct's columns from every row of a view, returning a copy of the view's
+/// dataframe with that column replaced.
+///
+/// The new values must have the column's dtype, so the result can still be viewed as the struct.
+/// Datetimes are the exception, and are converted to the column's time unit. `f` isn't called for
+/// skipped rows, which keep their value of the column.
+///
+/// # Errors
+///
+/// This errors if a row can't be materialized (see [RowViewOps::get_unskipped]), or with
+/// [ColbackError::WrongDtype] if the values returned by `f` don't have the column's dtype.
+pub fn replace_column<'a, V, C, T, F>(view: &V, field: C, mut f: F) -> Result<DataFrame>
+where
+    V: RowViewOps<'a>,
+    C: ColumnField,
+    F: FnMut(&V::Row) -> T,
+    Series: NamedFrom<Vec<T>, [T]>,
+{
+    let name = field.column_name();
+    let df = view.frame();
+    let column = df
+        .column(name)
+        .map_err(|_| ColbackError::MissingColumn(name.to_string()))?;
+    let expected = column.dtype();
+    let series = map_rows(view, name.into(), |row| f(&row))?;
+    let actual = series.dtype().clone();
+    let series = match (&actual, expected) {
+        (actual, expected) if actual == expected => series,
+        (DataType::Datetime(..), DataType::Datetime(..)) => series.cast(expected)?,
+        _ => {
+            return Err(ColbackError::WrongDtype {
+                col: name.to_string(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+    };
+    let kept: BooleanChunked = (0..view.len())
+        .map(|idx| view.skipped_column(idx).is_none())
+        .collect();
+    let series = if kept.all() {
+        series
+    } else {
+        series.zip_with(&kept, column.as_materialized_series())?
+    };
+    let mut out = df.into_owned();
+    out.with_column(series)?;
+    Ok(out)
+}
@@ -0,0 +1,140 @@
+//! Dataframes that own their data along with the struct they're viewed as.
+
+use crate::{ColbackView, Result, RowViewOps};
+use polars::prelude::DataFrame;
+use std::sync::Arc;
+
+/// An owned dataframe that has been validated as a struct deriving [ColbackView], along with
+/// its view.
+///
+/// Views borrow their dataframe, so keeping a dataframe and its view together, e.g. in
+/// application state, would need a self-referential struct. This owns both: the view that
+/// validated the dataframe is kept, so getting rows doesn't look up the struct's columns, fill
+/// nulls or check constraints again. The view is replaced after every update.
+///
+/// ```rust
+/// use colback::{ColbackView, RowViewOps, TypedFrame};
+/// use polars::df;
+///
+/// #[derive(ColbackView)]
+/// struct MyRow {
+///     col_a: u32,
+/// }
+///
+/// let mut frame = TypedFrame::<MyRow>::new(df!("col_a" => [1u32, 2]).unwrap()).unwrap();
+/// assert_eq!(frame.get(1).unwrap().col_a, 2);
+///
+/// // Updates that leave the frame invalid are rolled back
+/// let renamed = frame.update(|df| {
+///     df.rename("col_a", "col_b".into())?;
+///     Ok(())
+/// });
+/// assert!(renamed.is_err());
+/// assert_eq!(frame.iter().count(), 2);
+/// ```
+pub struct TypedFrame<T: ColbackView + 'static> {
+    // The view borrows `df` for as long as the frame lives, which `'static` stands in for. It's
+    // declared first so that it's dropped before `df`.
+    view: T::View<'static>,
+    // The dataframe is behind an `Arc` so that its address doesn't change when the frame is
+    // moved, and so that clones can share it with their copy of the view.
+    df: Arc<DataFrame>,
+}
+
+impl<T: ColbackView + 'static> TypedFrame<T> {
+    /// Wrap a dataframe after validating it as the struct.
+    ///
+    /// # Errors
+    ///
+    /// This errors if the dataframe can't be viewed as the struct, see [ColbackView::view].
+    pub fn new(df: DataFrame) -> Result<Self> {
+        let df = Arc::new(df);
+        // SAFETY: the dataframe is never mutated or moved out of its allocation while the frame
+        // holds it, and the view is dropped before the frame's `Arc`. The view is only handed
+        // out with the lifetime of a borrow of the frame.
+        let df_ref: &'static DataFrame = unsafe { &*Arc::as_ptr(&df) };
+        let view = T::view(df_ref)?;
+        Ok(Self { view, df })
+    }
+
+    /// The view of the dataframe.
+    pub fn view(&self) -> &T::View<'_> {
+        let view: *const T::View<'static> = &self.view;
+        // SAFETY: `T::View<'_>` only differs from `T::View<'static>` by its lifetime, which
+        // doesn't change its layout. The dataframe outlives the returned borrow of the frame,
+        // see [TypedFrame::new].
+        unsafe { &*view.cast::<T::View<'_>>() }
+    }
+
+    /// The dataframe.
+    pub fn df(&self) -> &DataFrame {
+        &self.df
+    }
+
+    /// Unwrap the dataframe.
+    pub fn into_inner(self) -> DataFrame {
+        let Self { view, df } = self;
+        drop(view);
+        Arc::unwrap_or_clone(df)
+    }
+
+    /// The number of rows in the dataframe.
+    pub fn len(&self) -> usize {
+        self.view().len()
+    }
+
+    /// Whether the dataframe has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.view().is_empty()
+    }
+
+    /// Get the row at a given index.
+    ///
+    /// # Errors
+    ///
+    /// This errors under the same conditions as [RowViewOps::get].
+    pub fn get(&self, idx: usize) -> Result<T::RowRef<'_>> {
+        self.view().get(idx)
+    }
+
+    /// Iterate over the rows of the dataframe, leaving out skipped rows.
+    ///
+    /// See [RowViewOps::iter].
+    pub fn iter(&self) -> impl Iterator<Item = Result<T::RowRef<'_>>> {
+        self.view().iter()
+    }
+
+    /// Update the dataframe in place and validate it again.
+    ///
+    /// `f` updates a copy of the dataframe, which is cheap since columns are reference counted,
+    /// and the copy only replaces the dataframe if it's still valid. So the frame is left as it
+    /// was if `f` or the validation fails.
+    ///
+    /// # Errors
+    ///
+    /// This errors if `f` errors, or if the updated dataframe can't be viewed as the struct.
+    pub fn update<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut DataFrame) -> Result<()>,
+    {
+        let mut df = DataFrame::clone(&self.df);
+        f(&mut df)?;
+        *self = Self::new(df)?;
+        Ok(())
+    }
+}
+
+// Deriving `Clone` would require `T: Clone`, but only the view is cloned, which keeps borrowing
+// the dataframe that the clone shares
+impl<T> Clone for TypedFrame<T>
+where
+    T: ColbackView + 'static,
+    T::View<'static>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            view: self.view.clone(),
+            df: Arc::clone(&self.df),
+        }
+    }
+}
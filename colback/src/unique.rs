@@ -0,0 +1,43 @@
+//! Typed deduplication by struct fields.
+
+use crate::{ColumnField, Result, groups::group_indices};
+use polars::prelude::{DataFrame, IdxCa};
+
+/// The keys that are duplicated in a dataframe, along with the rows where they occur.
+#[derive(Debug, Clone)]
+pub struct DuplicatesReport {
+    /// One row for every duplicated key, containing only the key columns.
+    ///
+    /// Keys are ordered by their first occurrence in the dataframe.
+    pub keys: DataFrame,
+
+    /// The row indices of each duplicated key, in the same order as `keys`.
+    pub indices: Vec<Vec<usize>>,
+}
+
+impl DuplicatesReport {
+    /// Whether any duplicated keys were found.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+/// Find the keys that occur more than once in a dataframe.
+pub(crate) fn duplicates_report<F: ColumnField>(
+    df: &DataFrame,
+    by: &[F],
+) -> Result<DuplicatesReport> {
+    let names: Vec<_> = by.iter().map(|f| f.column_name()).collect();
+    let group_by = df.group_by_stable(names.iter().copied())?;
+    let indices: Vec<Vec<usize>> = group_by
+        .get_groups()
+        .iter()
+        .filter(|g| g.len() > 1)
+        .map(group_indices)
+        .collect();
+    let firsts: Vec<_> = indices.iter().map(|idx| idx[0] as _).collect();
+    let keys = df
+        .select(names)?
+        .take(&IdxCa::from_vec("".into(), firsts))?;
+    Ok(DuplicatesReport { keys, indices })
+}
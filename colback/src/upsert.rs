@@ -0,0 +1,84 @@
+//! Typed upserts of owned rows into dataframes.
+
+use crate::{ColumnField, Result};
+use polars::prelude::{
+    Column, DataFrame, DataFrameJoinOps, IdxCa, IdxSize, JoinArgs, JoinType, UniqueKeepStrategy,
+};
+
+/// Name of the temporary row index column of the update rows.
+const UPDATE_IDX: &str = "__colback_update_idx";
+/// Name of the temporary row index column of the original rows.
+const ROW_IDX: &str = "__colback_row_idx";
+
+/// Merge update rows into a dataframe by some of the struct's fields.
+///
+/// `updates` must have exactly the struct's columns. Rows of `df` whose key matches an update are
+/// replaced in place, and updates that don't match any row are appended in order. If several
+/// updates share a key, the last one wins.
+pub(crate) fn upsert<F: ColumnField>(
+    df: &DataFrame,
+    updates: DataFrame,
+    by: &[F],
+) -> Result<DataFrame> {
+    let names: Vec<_> = by.iter().map(|f| f.column_name()).collect();
+    let names_owned: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+    let updates = updates.unique_stable(Some(&names_owned), UniqueKeepStrategy::Last, None)?;
+
+    let update_keys = updates
+        .select(names.iter().copied())?
+        .with_row_index(UPDATE_IDX.into(), None)?;
+    let row_keys = df
+        .select(names.iter().copied())?
+        .with_row_index(ROW_IDX.into(), None)?;
+    let matches = update_keys.join(
+        &row_keys,
+        names.iter().copied(),
+        names.iter().copied(),
+        JoinArgs::new(JoinType::Left),
+        None,
+    )?;
+
+    let height = df.height() as IdxSize;
+    let mut replacements: Vec<Option<IdxSize>> = vec![None; df.height()];
+    let mut appended = Vec::new();
+    let update_idx = matches.column(UPDATE_IDX)?.idx()?;
+    let row_idx = matches.column(ROW_IDX)?.idx()?;
+    for (update, row) in update_idx.into_no_null_iter().zip(row_idx) {
+        match row {
+            Some(row) => replacements[row as usize] = Some(update),
+            None => appended.push(update),
+        }
+    }
+    appended.sort_unstable();
+
+    // Each column is stacked with its update values, so a single gather both replaces the matched
+    // rows and appends the new ones. Columns that the struct doesn't declare keep their original
+    // values and are null for the appended rows.
+    let appended = appended.into_iter().map(|update| height + update);
+    let replaced_idx: Vec<IdxSize> = (0..height)
+        .zip(&replacements)
+        .map(|(row, update)| update.map_or(row, |update| height + update))
+        .chain(appended.clone())
+        .collect();
+    let kept_idx: Vec<IdxSize> = (0..height).chain(appended).collect();
+    let replaced_idx = IdxCa::from_vec("".into(), replaced_idx);
+    let kept_idx = IdxCa::from_vec("".into(), kept_idx);
+
+    let columns = df
+        .get_columns()
+        .iter()
+        .map(|c| {
+            let (values, idx) = match updates.column(c.name()) {
+                Ok(values) => (values.clone(), &replaced_idx),
+                Err(_) => (
+                    Column::full_null(c.name().clone(), updates.height(), c.dtype()),
+                    &kept_idx,
+                ),
+            };
+            let mut stacked = c.clone();
+            stacked.append(&values)?;
+            Ok(stacked.take(idx)?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(DataFrame::new(columns)?)
+}
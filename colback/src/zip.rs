@@ -0,0 +1,36 @@
+//! Lockstep iteration over views of the same rows.
+
+use crate::{ColbackError, Result, RowViewOps};
+
+/// Iterate over two views together, pairing the rows at the same index.
+///
+/// This is meant for structs that view different columns of the same dataframe, or of
+/// dataframes whose rows line up, so both rows can be read without separate lookups into each
+/// view. Indices where either row is skipped are left out.
+///
+/// # Errors
+///
+/// Returns [ColbackError::LengthMismatch] if the views have different lengths. Each item errors
+/// if either of its rows can't be materialized, see [RowViewOps::get_unskipped].
+pub fn zip<'v, 'a, 'b, A, B>(
+    a: &'v A,
+    b: &'v B,
+) -> Result<impl Iterator<Item = Result<(A::Row, B::Row)>> + use<'v, 'a, 'b, A, B>>
+where
+    A: RowViewOps<'a>,
+    B: RowViewOps<'b>,
+{
+    if a.len() != b.len() {
+        return Err(ColbackError::LengthMismatch {
+            expected: a.len(),
+            actual: b.len(),
+        });
+    }
+    let pair = |idx| -> Result<Option<(A::Row, B::Row)>> {
+        let Some(row_a) = a.get_unskipped(idx)? else {
+            return Ok(None);
+        };
+        Ok(b.get_unskipped(idx)?.map(|row_b| (row_a, row_b)))
+    };
+    Ok((0..a.len()).filter_map(move |idx| pair(idx).transpose()))
+}
@@ -0,0 +1,10 @@
+use chrono::NaiveDateTime;
+use colback::ColbackView;
+
+#[derive(ColbackView)]
+struct SomeStruct {
+    #[polars(time_zone = "Mars/Olympus_Mons")]
+    row_a: NaiveDateTime,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use colback::{ColbackCategory, ColbackView};
+
+#[derive(ColbackCategory, Clone, Copy, Debug, PartialEq)]
+enum Color {
+    Red,
+    Blue,
+}
+
+#[derive(ColbackView)]
+struct SomeStruct {
+    #[polars(category, unknown = "fallback")]
+    row_a: Color,
+}
+
+fn main() {}
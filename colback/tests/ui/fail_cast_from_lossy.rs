@@ -0,0 +1,9 @@
+use colback::ColbackView;
+
+#[derive(ColbackView)]
+struct SomeStruct {
+    #[polars(cast_from = "u64")]
+    row_a: i32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use colback::ColbackView;
+
+#[derive(ColbackView)]
+struct SomeStruct {
+    #[polars(default = 1)]
+    row_a: u32,
+}
+
+fn main() {}
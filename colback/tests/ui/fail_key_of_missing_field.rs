@@ -0,0 +1,15 @@
+use colback::{ColbackKey, ColbackView};
+
+#[derive(ColbackView, Eq, PartialEq)]
+struct Trade {
+    ticker: String,
+    qty: u32,
+}
+
+#[derive(ColbackKey, Eq, PartialEq)]
+#[polars(key_of(Trade))]
+struct Symbol {
+    symbol: String,
+}
+
+fn main() {}
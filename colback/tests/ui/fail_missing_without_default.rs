@@ -0,0 +1,9 @@
+use colback::ColbackView;
+
+#[derive(ColbackView)]
+struct SomeStruct {
+    #[polars(missing = "default")]
+    row_a: u32,
+}
+
+fn main() {}
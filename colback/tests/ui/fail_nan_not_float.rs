@@ -0,0 +1,9 @@
+use colback::ColbackView;
+
+#[derive(ColbackView)]
+struct SomeStruct {
+    #[polars(nan = "null")]
+    row_a: i64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use colback::ColbackView;
+
+#[derive(ColbackView)]
+struct SomeStruct {
+    row_a: u32,
+    #[polars(null = "option")]
+    row_b: Option<Vec<String>>,
+}
+
+fn main() {}